@@ -0,0 +1,7 @@
+//! Code shared by the Aegis Sentinel and the Supabase bridge
+//!
+//! Both sides read the same telemetry, so anything that decides how a
+//! reading is interpreted lives here once.
+
+pub mod mqtt;
+pub mod units;
@@ -0,0 +1,71 @@
+//! MQTT session settings for the Sentinel and bridge clients
+
+use rumqttc::MqttOptions;
+use tracing::warn;
+
+/// Broker session settings
+#[derive(Debug, Clone)]
+pub struct MqttSession {
+    /// Must be unique per broker; a second connection with the same id
+    /// kicks the first one off
+    pub client_id: String,
+    /// `false` asks the broker to keep subscriptions and queued messages
+    /// across reconnects (needs a stable `client_id`)
+    pub clean_session: bool,
+}
+
+impl MqttSession {
+    /// Default session: `<prefix>-<random suffix>`, clean session
+    pub fn with_prefix(prefix: &str) -> Self {
+        let suffix = uuid::Uuid::new_v4().simple().to_string();
+        Self {
+            client_id: format!("{}-{}", prefix, &suffix[..8]),
+            clean_session: true,
+        }
+    }
+
+    /// Read `MQTT_CLIENT_ID` and `MQTT_CLEAN_SESSION`, falling back to the default
+    pub fn from_env(prefix: &str) -> Self {
+        let mut session = Self::with_prefix(prefix);
+        let client_id = std::env::var("MQTT_CLIENT_ID").ok();
+        if let Ok(clean) = std::env::var("MQTT_CLEAN_SESSION") {
+            session.clean_session = !clean.eq_ignore_ascii_case("false");
+        }
+        match client_id {
+            Some(id) => session.client_id = id,
+            None if !session.clean_session => {
+                warn!(
+                    "Persistent MQTT session without MQTT_CLIENT_ID - session is lost on restart"
+                );
+            }
+            None => {}
+        }
+        session
+    }
+
+    /// Client options for `host:port` with this session's id and clean-session flag
+    pub fn options(&self, host: &str, port: u16) -> MqttOptions {
+        let mut mqttoptions = MqttOptions::new(self.client_id.clone(), host, port);
+        mqttoptions.set_keep_alive(std::time::Duration::from_secs(5));
+        mqttoptions.set_clean_session(self.clean_session);
+        mqttoptions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_client_ids_are_distinct() {
+        let a = MqttSession::with_prefix("aegis-sentinel");
+        let b = MqttSession::with_prefix("aegis-sentinel");
+
+        assert_ne!(a.client_id, b.client_id);
+        assert!(a.client_id.starts_with("aegis-sentinel-"));
+
+        let options = a.options("localhost", 1883);
+        assert_eq!(options.client_id(), a.client_id);
+        assert!(options.clean_session());
+    }
+}
@@ -0,0 +1,141 @@
+//! Canonical metric units and the conversions into them
+//!
+//! Detectors and the `sensor_readings` columns assume each metric arrives in
+//! one unit (°C, mm/s, Pa, ...). Telemetry may declare the unit of a metric;
+//! [`check_unit`] converts known alternatives and flags anything it cannot
+//! reconcile. The Sentinel and the bridge both go through it, so a reading
+//! means the same thing on either side.
+
+/// What a declared unit means for a reading
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnitCheck {
+    /// Already in the canonical unit, or a metric without one
+    Canonical,
+    /// Converted from a known alternative unit
+    Converted { value: f64, to: &'static str },
+    /// Not convertible to the canonical unit; the reading is left as sent
+    Mismatch { expected: &'static str },
+}
+
+/// Canonical unit the detectors assume for a metric
+pub fn canonical_unit(metric: &str) -> Option<&'static str> {
+    match metric {
+        "temperature" | "capillary_temp" => Some("C"),
+        "vibration" => Some("mm/s"),
+        "pressure" | "pressure_diff_pa" => Some("Pa"),
+        "airflow_mps" => Some("m/s"),
+        "exhaust_flow" => Some("m3/h"),
+        "chemical_ppm" => Some("ppm"),
+        "usg_impedance" => Some("ohm"),
+        "bond_time_ms" => Some("ms"),
+        "load_percent" => Some("%"),
+        _ => None,
+    }
+}
+
+/// Normalize a unit label for comparison ("°F" -> "f", "deg C" -> "c")
+fn normalize_label(unit: &str) -> String {
+    let label: String = unit
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '°')
+        .collect();
+
+    match label.as_str() {
+        "degc" | "celsius" => "c".to_string(),
+        "degf" | "fahrenheit" => "f".to_string(),
+        "kelvin" => "k".to_string(),
+        "ω" | "ohms" => "ohm".to_string(),
+        "percent" => "%".to_string(),
+        // Micro sign and Greek mu
+        "µs" | "μs" => "us".to_string(),
+        _ => label,
+    }
+}
+
+/// Convert `value` in `unit` to the canonical unit, if a conversion is known
+fn convert(canonical: &str, unit: &str, value: f64) -> Option<f64> {
+    match (canonical, unit) {
+        ("C", "f") => Some((value - 32.0) * 5.0 / 9.0),
+        ("C", "k") => Some(value - 273.15),
+        ("mm/s", "in/s") => Some(value * 25.4),
+        ("mm/s", "m/s") => Some(value * 1000.0),
+        ("Pa", "kpa") => Some(value * 1000.0),
+        ("Pa", "inh2o") => Some(value * 249.089),
+        ("m/s", "ft/min") | ("m/s", "fpm") => Some(value * 0.00508),
+        ("m3/h", "cfm") => Some(value * 1.699),
+        ("ms", "s") => Some(value * 1000.0),
+        ("ms", "us") => Some(value / 1000.0),
+        _ => None,
+    }
+}
+
+/// Reconcile a reading of `metric` declared in `unit` with its canonical unit
+pub fn check_unit(metric: &str, unit: &str, value: f64) -> UnitCheck {
+    let Some(canonical) = canonical_unit(metric) else {
+        return UnitCheck::Canonical;
+    };
+    let label = normalize_label(unit);
+    if label == normalize_label(canonical) {
+        return UnitCheck::Canonical;
+    }
+    match convert(canonical, &label, value) {
+        Some(value) => UnitCheck::Converted {
+            value,
+            to: canonical,
+        },
+        None => UnitCheck::Mismatch {
+            expected: canonical,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_declared_units_reconciled() {
+        let converted = |metric: &str, unit: &str, value: f64| match check_unit(metric, unit, value)
+        {
+            UnitCheck::Converted { value, .. } => value,
+            other => panic!(
+                "{} in {}: expected a conversion, got {:?}",
+                metric, unit, other
+            ),
+        };
+
+        assert!((converted("temperature", "°F", 149.0) - 65.0).abs() < 1e-9);
+        assert!((converted("temperature", "K", 338.15) - 65.0).abs() < 1e-9);
+        assert!((converted("vibration", "m/s", 0.002) - 2.0).abs() < 1e-9);
+        assert!((converted("vibration", "in/s", 1.0) - 25.4).abs() < 1e-9);
+        assert!((converted("pressure_diff_pa", "inH2O", 1.0) - 249.089).abs() < 1e-9);
+        assert!((converted("exhaust_flow", "CFM", 100.0) - 169.9).abs() < 1e-9);
+        assert!((converted("bond_time_ms", "µs", 15_000.0) - 15.0).abs() < 1e-9);
+        assert!((converted("bond_time_ms", "μs", 15_000.0) - 15.0).abs() < 1e-9);
+
+        for (metric, unit) in [
+            ("temperature", "deg C"),
+            ("temperature", "°C"),
+            ("usg_impedance", "Ω"),
+        ] {
+            assert_eq!(
+                check_unit(metric, unit, 1.0),
+                UnitCheck::Canonical,
+                "{} in {}",
+                metric,
+                unit
+            );
+        }
+        // No canonical unit: whatever was declared is taken as is
+        assert_eq!(
+            check_unit("particles_0_5um", "count/ft3", 1.0),
+            UnitCheck::Canonical
+        );
+        assert_eq!(
+            check_unit("vibration", "g", 0.5),
+            UnitCheck::Mismatch { expected: "mm/s" }
+        );
+    }
+}
@@ -44,24 +44,28 @@
 //! The Rust agent sends high-level commands (STOP, ADJUST) via MQTT.
 //! The Python bridge (`gem_adapter.py`) translates to SECS-II messages:
 //! - S2F41: Host Command Send (Remote commands)
-//! - S2F42: Host Command Acknowledge
+//! - S2F42: Host Command Acknowledge (returned on `sentinel/bridge/ack`)
 //! - S6F11: Event Report Send (Equipment events)
 //!
 //! This avoids writing a full SECS/GEM stack in Rust (6+ month effort).
 
 use async_trait::async_trait;
-use serde::Deserialize;
+use crate::detection::{linear_fit, Cadence, Warmup};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
+use crate::enrichment::thermal_expansion_mm;
 use crate::types::*;
-use super::SentinelAgent;
+use super::{check_range, merge_config, ConfigRanges, MachineStates, SentinelAgent};
 
 /// Assembly Sentinel configuration
 /// 
 /// These parameters tune the physics-based detection algorithms.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssemblyConfig {
     /// Maximum bond time in milliseconds (Performance check for OEE)
     /// Typical wire bond: 10-20ms. Above this = throughput degradation.
@@ -84,6 +88,102 @@ pub struct AssemblyConfig {
     
     /// Capillary length in mm (for thermal expansion calculation)
     pub capillary_length_mm: f64,
+
+    /// Capillary temperature (°C) at which the process idles
+    /// Drift is measured as expansion relative to this temperature.
+    #[serde(default = "default_capillary_baseline_temp")]
+    pub capillary_baseline_temp: f64,
+
+    /// Minimum acceptable ball-bond shear strength in gram-force
+    /// Below this the bond is a reliability escape.
+    #[serde(default = "default_min_shear_strength_g")]
+    pub min_shear_strength_g: f64,
+
+    /// How many shear samples ahead a downward trend may cross the minimum
+    /// before an early degradation warning is raised
+    #[serde(default = "default_shear_trend_horizon")]
+    pub shear_trend_horizon: usize,
+
+    /// NSOP detections within the escalation window that indicate a systemic
+    /// capillary/pad problem rather than a one-off non-stick
+    #[serde(default = "default_nsop_escalation_count")]
+    pub nsop_escalation_count: usize,
+
+    /// Sliding window (seconds) over which NSOP detections are counted
+    #[serde(default = "default_nsop_escalation_window_secs")]
+    pub nsop_escalation_window_secs: f64,
+
+    /// Feed holds (plain NSOP runs) tolerated per machine within the feed
+    /// hold window; more raise a recurring-NSOP pattern alarm
+    #[serde(default = "default_max_feed_holds")]
+    pub max_feed_holds: usize,
+
+    /// Sliding window (seconds) over which feed holds are counted
+    /// Longer than the NSOP escalation window, to catch runs spread too far
+    /// apart for the ladder.
+    #[serde(default = "default_feed_hold_window_secs")]
+    pub feed_hold_window_secs: f64,
+
+    /// Readings ignored for detection after startup (sensor settling)
+    #[serde(default)]
+    pub warmup_samples: usize,
+
+    /// Run detectors on every Nth sample; histories and NSOP checks still
+    /// take every bond
+    #[serde(default = "default_analyze_every_n")]
+    pub analyze_every_n: usize,
+
+    /// Bond force increase per cycle-time auto-optimization (0.05 = +5%)
+    #[serde(default = "default_bond_force_adjust_step")]
+    pub bond_force_adjust_step: f64,
+
+    /// Cap on the cumulative bond force increase over nominal (0.15 = +15%)
+    /// Past this, more force risks cratering the pad.
+    #[serde(default = "default_max_bond_force_adjust")]
+    pub max_bond_force_adjust: f64,
+}
+
+fn default_capillary_baseline_temp() -> f64 {
+    25.0
+}
+
+fn default_min_shear_strength_g() -> f64 {
+    15.0
+}
+
+fn default_shear_trend_horizon() -> usize {
+    20
+}
+
+/// Shear samples needed before the trend is trusted
+const SHEAR_TREND_MIN_SAMPLES: usize = 10;
+
+fn default_nsop_escalation_count() -> usize {
+    3
+}
+
+fn default_nsop_escalation_window_secs() -> f64 {
+    600.0
+}
+
+fn default_max_feed_holds() -> usize {
+    3
+}
+
+fn default_feed_hold_window_secs() -> f64 {
+    3600.0
+}
+
+fn default_analyze_every_n() -> usize {
+    1
+}
+
+fn default_bond_force_adjust_step() -> f64 {
+    0.05
+}
+
+fn default_max_bond_force_adjust() -> f64 {
+    0.15
 }
 
 impl Default for AssemblyConfig {
@@ -95,40 +195,81 @@ impl Default for AssemblyConfig {
             machine_id: "BOND-01".to_string(),
             material_cte: 5.5e-6,             // Tungsten carbide
             capillary_length_mm: 10.0,        // 10mm standard
+            capillary_baseline_temp: default_capillary_baseline_temp(),
+            min_shear_strength_g: default_min_shear_strength_g(),
+            shear_trend_horizon: default_shear_trend_horizon(),
+            nsop_escalation_count: default_nsop_escalation_count(),
+            nsop_escalation_window_secs: default_nsop_escalation_window_secs(),
+            max_feed_holds: default_max_feed_holds(),
+            feed_hold_window_secs: default_feed_hold_window_secs(),
+            warmup_samples: 0,
+            analyze_every_n: default_analyze_every_n(),
+            bond_force_adjust_step: default_bond_force_adjust_step(),
+            max_bond_force_adjust: default_max_bond_force_adjust(),
         }
     }
 }
 
-/// Wire Bonding & Packaging Sentinel Agent
-/// 
-/// Implements detection for:
-/// - NSOP (Non-Stick on Pad) via ultrasonic impedance
-/// - Throughput degradation (OEE impact)
-/// - Capillary thermal drift
-/// - Equipment degradation (USG, etc.)
-pub struct AssemblySentinel {
-    config: AssemblyConfig,
+impl ConfigRanges for AssemblyConfig {
+    fn check_ranges(&self) -> Result<(), AgentError> {
+        check_range("target_oee", self.target_oee, 0.0, 1.0)?;
+        check_range("max_bond_force_adjust", self.max_bond_force_adjust, 0.0, 1.0)
+    }
+}
+
+/// Rolling state for one bonder
+///
+/// One agent covers every BOND-/ASM-/WB- machine, so histories and the NSOP
+/// ladder are kept per machine; one bonder's bonds must not break another's
+/// consecutive-NSOP run or feed its OEE.
+struct BonderState {
     bond_time_history: VecDeque<f64>,
     impedance_history: VecDeque<f64>,
+    shear_history: VecDeque<f64>,  // Sampled pull/shear test results
     nsop_count: u32,  // Consecutive NSOP counter
+    nsop_events: VecDeque<Instant>,  // Recent NSOP detections (escalation ladder)
+    feed_holds: VecDeque<Instant>,  // Recent plain NSOP detections answered with a FeedHold
+    warmup: Warmup,
+    cadence: Cadence,
+    bond_force_scale: f64,  // Last executed bond force relative to nominal
+    bond_force_before: Option<f64>,  // Scale before the last step, until the bonder acks it
 }
 
-impl AssemblySentinel {
-    /// Create a new Assembly Sentinel
-    pub fn new(config: AssemblyConfig) -> Self {
+impl BonderState {
+    fn new(warmup_samples: usize) -> Self {
         Self {
-            config,
             bond_time_history: VecDeque::with_capacity(100),
             impedance_history: VecDeque::with_capacity(100),
+            shear_history: VecDeque::with_capacity(100),
             nsop_count: 0,
+            nsop_events: VecDeque::new(),
+            feed_holds: VecDeque::new(),
+            warmup: Warmup::new(warmup_samples),
+            cadence: Cadence::default(),
+            bond_force_scale: 1.0,
+            bond_force_before: None,
         }
     }
 
-    /// Create from YAML configuration
-    pub fn from_config(yaml: serde_yaml::Value) -> Result<Self, AgentError> {
-        let config: AssemblyConfig = serde_yaml::from_value(yaml)
-            .map_err(|e| AgentError::ConfigError(format!("Assembly config: {}", e)))?;
-        Ok(Self::new(config))
+    /// Update the rolling histories with one bond
+    fn record(&mut self, bond_time: f64, impedance: f64, shear: Option<f64>) {
+        self.bond_time_history.push_back(bond_time);
+        if self.bond_time_history.len() > 100 {
+            self.bond_time_history.pop_front();
+        }
+        
+        self.impedance_history.push_back(impedance);
+        if self.impedance_history.len() > 100 {
+            self.impedance_history.pop_front();
+        }
+        
+        // Shear strength is sampled (destructive test), not reported every bond
+        if let Some(shear_g) = shear {
+            self.shear_history.push_back(shear_g);
+            if self.shear_history.len() > 100 {
+                self.shear_history.pop_front();
+            }
+        }
     }
 
     /// Detect "Non-Stick on Pad" (NSOP)
@@ -146,16 +287,32 @@ impl AssemblySentinel {
     /// # Returns
     /// - `Some(Threat)` if NSOP detected (Critical severity)
     /// - `None` if bond is good
-    fn detect_bond_defect(&mut self, impedance_ohms: f64) -> Option<Threat> {
-        if impedance_ohms < self.config.min_ultrasonic_impedance {
+    fn detect_bond_defect(
+        &mut self,
+        machine_id: &str,
+        impedance_ohms: f64,
+        config: &AssemblyConfig,
+    ) -> Option<Threat> {
+        if impedance_ohms < config.min_ultrasonic_impedance {
             self.nsop_count += 1;
             
             // Trigger after 3 consecutive NSOPs to avoid false positives
             if self.nsop_count >= 3 {
                 self.nsop_count = 0;  // Reset after detection
+                let recent_runs = self.record_nsop_run(config.nsop_escalation_window_secs);
+
+                let defect_type = if recent_runs >= config.nsop_escalation_count {
+                    format!(
+                        "Recurring NSOP ({} runs in {:.0}s)",
+                        recent_runs, config.nsop_escalation_window_secs
+                    )
+                } else {
+                    "NSOP (Non-Stick on Pad)".to_string()
+                };
+
                 Some(Threat::QualityDefect {
-                    machine_id: self.config.machine_id.clone(),
-                    defect_type: "NSOP (Non-Stick on Pad)".to_string(),
+                    machine_id: machine_id.to_string(),
+                    defect_type,
                     confidence: 0.99,
                     severity: Severity::Critical,  // STOP IMMEDIATELY
                 })
@@ -169,6 +326,135 @@ impl AssemblySentinel {
         }
     }
 
+    /// Record an NSOP detection and return how many fall within the window
+    ///
+    /// Repeated NSOP runs in a short window point at a worn capillary or
+    /// contaminated pads, which a feed hold alone won't fix.
+    fn record_nsop_run(&mut self, window_secs: f64) -> usize {
+        let now = Instant::now();
+        let window = Duration::from_secs_f64(window_secs.max(0.0));
+
+        self.nsop_events.push_back(now);
+        while let Some(&oldest) = self.nsop_events.front() {
+            if now.duration_since(oldest) > window {
+                self.nsop_events.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        self.nsop_events.len()
+    }
+
+    /// Record a feed hold and flag a recurring-NSOP pattern if this machine
+    /// has now had more than `max_feed_holds` within the feed hold window
+    ///
+    /// Each hold clears one NSOP run, so the per-event logic never sees the
+    /// pattern when the runs are spread wider than the escalation window.
+    fn detect_feed_hold_pattern(
+        &mut self,
+        machine_id: &str,
+        config: &AssemblyConfig,
+    ) -> Option<Threat> {
+        let now = Instant::now();
+        let window = Duration::from_secs_f64(config.feed_hold_window_secs.max(0.0));
+
+        self.feed_holds.push_back(now);
+        while let Some(&oldest) = self.feed_holds.front() {
+            if now.duration_since(oldest) > window {
+                self.feed_holds.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if self.feed_holds.len() <= config.max_feed_holds {
+            return None;
+        }
+        Some(Threat::QualityDefect {
+            machine_id: machine_id.to_string(),
+            defect_type: format!(
+                "Recurring NSOP pattern ({} feed holds in {:.0}s)",
+                self.feed_holds.len(), config.feed_hold_window_secs
+            ),
+            confidence: 0.99,
+            severity: Severity::Critical,
+        })
+    }
+
+    /// Calculate OEE (Overall Equipment Effectiveness)
+    /// 
+    /// Simplified calculation based on performance component:
+    /// Performance = Theoretical Cycle Time / Actual Cycle Time
+    /// 
+    /// In real implementation, this would also consider:
+    /// - Availability: (Run Time / Planned Production Time)
+    /// - Quality: (Good Units / Total Units)
+    fn calculate_oee(&self) -> f64 {
+        if self.bond_time_history.is_empty() {
+            return 1.0;
+        }
+        
+        let avg_cycle_time = self.bond_time_history.iter()
+            .sum::<f64>() / self.bond_time_history.len() as f64;
+        
+        let theoretical_cycle_time = 15.0; // ms - typical for gold wire bonding
+        let performance = theoretical_cycle_time / avg_cycle_time;
+        
+        performance.min(1.0)
+    }
+}
+
+/// Wire Bonding & Packaging Sentinel Agent
+/// 
+/// Implements detection for:
+/// - NSOP (Non-Stick on Pad) via ultrasonic impedance
+/// - Throughput degradation (OEE impact)
+/// - Capillary thermal drift
+/// - Equipment degradation (USG, etc.)
+/// - Bond shear strength (hard floor and downward trend)
+/// - Recurring NSOP patterns (feed hold frequency per machine)
+pub struct AssemblySentinel {
+    config: AssemblyConfig,
+    /// Rolling state per bonder
+    bonders: MachineStates<BonderState>,
+}
+
+impl AssemblySentinel {
+    /// Create a new Assembly Sentinel
+    pub fn new(config: AssemblyConfig) -> Self {
+        Self {
+            config,
+            bonders: MachineStates::new(),
+        }
+    }
+
+    /// State for `machine_id`, created on its first reading
+    fn bonder(&self, machine_id: &str) -> Arc<Mutex<BonderState>> {
+        let warmup_samples = self.config.warmup_samples;
+        self.bonders.entry(machine_id, || BonderState::new(warmup_samples))
+    }
+
+    /// Bond force to command next: one step above what the machine last
+    /// executed, clamped to the configured maximum
+    ///
+    /// Returns the scale relative to nominal force (1.05 = 105%). The step is
+    /// only taken once the command has been executed (`action_executed`).
+    fn next_bond_force_scale(&self, machine_id: &str) -> f64 {
+        let current = self.bonders
+            .get(machine_id)
+            .map_or(1.0, |bonder| bonder.lock().unwrap().bond_force_scale);
+        let max_scale = 1.0 + self.config.max_bond_force_adjust.max(0.0);
+        (current + self.config.bond_force_adjust_step.max(0.0)).min(max_scale)
+    }
+
+    /// Create from YAML configuration
+    pub fn from_config(yaml: serde_yaml::Value) -> Result<Self, AgentError> {
+        let config: AssemblyConfig = serde_yaml::from_value(yaml)
+            .map_err(|e| AgentError::ConfigError(format!("Assembly config: {}", e)))?;
+        Ok(Self::new(config))
+    }
+
     /// Detect Throughput Degradation (OEE Killer)
     /// 
     /// Micro-stoppages and cycle time drift are the silent killers of OEE.
@@ -183,10 +469,10 @@ impl AssemblySentinel {
     /// # Returns
     /// - `Some(Threat)` if cycle time exceeds threshold
     /// - `None` if performance is nominal
-    fn detect_throughput_drift(&self, cycle_time_ms: f64) -> Option<Threat> {
+    fn detect_throughput_drift(&self, machine_id: &str, cycle_time_ms: f64) -> Option<Threat> {
         if cycle_time_ms > self.config.max_bond_time_ms {
             Some(Threat::ThroughputDegradation {
-                machine_id: self.config.machine_id.clone(),
+                machine_id: machine_id.to_string(),
                 issue: "Cycle Time Drift".to_string(),
                 impact_oee: true,
                 severity: Severity::Medium,
@@ -209,16 +495,18 @@ impl AssemblySentinel {
     /// - ΔT = temperature change
     /// 
     /// This affects Z-height and bond placement accuracy.
-    fn detect_capillary_drift(&self, temp: f64, baseline_temp: f64) -> Option<Threat> {
+    fn detect_capillary_drift(&self, machine_id: &str, temp: f64, baseline_temp: f64) -> Option<Threat> {
         let temp_rise = temp - baseline_temp;
-        let expansion_mm = self.config.material_cte * self.config.capillary_length_mm * temp_rise;
+        let expansion_mm = thermal_expansion_mm(self.config.material_cte, self.config.capillary_length_mm, temp_rise);
         
         // Alert if expansion exceeds 1µm (typical placement tolerance)
-        if expansion_mm > 0.001 {
+        let tolerance_mm = 0.001;
+        if expansion_mm > tolerance_mm {
             Some(Threat::ThermalDrift {
-                machine_id: self.config.machine_id.clone(),
+                machine_id: machine_id.to_string(),
                 drift_mm: expansion_mm,
                 axis: "Z".to_string(),
+                limit: tolerance_mm,
                 severity: if expansion_mm > 0.002 {
                     Severity::High
                 } else {
@@ -230,81 +518,141 @@ impl AssemblySentinel {
         }
     }
 
-    /// Calculate OEE (Overall Equipment Effectiveness)
-    /// 
-    /// Simplified calculation based on performance component:
-    /// Performance = Theoretical Cycle Time / Actual Cycle Time
-    /// 
-    /// In real implementation, this would also consider:
-    /// - Availability: (Run Time / Planned Production Time)
-    /// - Quality: (Good Units / Total Units)
-    fn calculate_oee(&self) -> f64 {
-        if self.bond_time_history.is_empty() {
-            return 1.0;
+    /// Detect weak bonds from a shear test result
+    fn detect_weak_bond(&self, machine_id: &str, shear_g: f64) -> Option<Threat> {
+        if shear_g < self.config.min_shear_strength_g {
+            Some(Threat::QualityDefect {
+                machine_id: machine_id.to_string(),
+                defect_type: format!("Weak Bond (shear {:.1} gf)", shear_g),
+                confidence: 0.99,  // Direct destructive measurement
+                severity: Severity::High,
+            })
+        } else {
+            None
         }
-        
-        let avg_cycle_time = self.bond_time_history.iter()
-            .sum::<f64>() / self.bond_time_history.len() as f64;
-        
-        let theoretical_cycle_time = 15.0; // ms - typical for gold wire bonding
-        let performance = theoretical_cycle_time / avg_cycle_time;
-        
-        performance.min(1.0)
     }
 
-    /// Get machine ID
-    pub fn machine_id(&self) -> &str {
-        &self.config.machine_id
+    /// Predict weak bonds from the shear strength trend
+    /// 
+    /// Fits a line through the shear history and warns when it projects
+    /// crossing the minimum within `shear_trend_horizon` samples, while
+    /// bonds are still passing.
+    fn detect_shear_trend(&self, machine_id: &str, shear_history: &VecDeque<f64>) -> Option<Threat> {
+        if shear_history.len() < SHEAR_TREND_MIN_SAMPLES {
+            return None;
+        }
+
+        let samples: Vec<f64> = shear_history.iter().copied().collect();
+        let (slope, intercept) = linear_fit(&samples)?;
+        if slope >= 0.0 {
+            return None;
+        }
+
+        let current = intercept + slope * (samples.len() - 1) as f64;
+        let margin = current - self.config.min_shear_strength_g;
+        if margin <= 0.0 {
+            return None;  // Already at the floor - weak bond detection owns this
+        }
+
+        let samples_to_floor = margin / -slope;
+        if samples_to_floor <= self.config.shear_trend_horizon as f64 {
+            Some(Threat::EquipmentDegradation {
+                machine_id: machine_id.to_string(),
+                component: "Bond_Shear_Strength".to_string(),
+                metric: samples_to_floor,
+                limit: self.config.shear_trend_horizon as f64,
+                severity: Severity::Medium,
+            })
+        } else {
+            None
+        }
     }
+
 }
 
 #[async_trait]
 impl SentinelAgent for AssemblySentinel {
-    fn analyze(&mut self, telemetry: &Telemetry) -> Vec<Threat> {
+    fn analyze(&self, telemetry: &Telemetry) -> Vec<Threat> {
         let mut threats = Vec::new();
         
         // Extract physics data from telemetry
-        let impedance = telemetry.metrics.get("usg_impedance").copied().unwrap_or(100.0);
-        let bond_time = telemetry.metrics.get("bond_time_ms").copied().unwrap_or(15.0);
-        let capillary_temp = telemetry.metrics.get("capillary_temp").copied().unwrap_or(25.0);
+        let impedance = telemetry.metric_or("usg_impedance", 100.0);
+        let bond_time = telemetry.metric_or("bond_time_ms", 15.0);
+        let capillary_temp = telemetry.metric("capillary_temp")
+            .unwrap_or(self.config.capillary_baseline_temp);
         
-        // Update rolling history for OEE calculation
-        self.bond_time_history.push_back(bond_time);
-        if self.bond_time_history.len() > 100 {
-            self.bond_time_history.pop_front();
-        }
-        
-        self.impedance_history.push_back(impedance);
-        if self.impedance_history.len() > 100 {
-            self.impedance_history.pop_front();
-        }
+        let machine_id = telemetry.machine_id.as_str();
+        let shear = telemetry.metric("shear_strength_g");
+        let bonder = self.bonder(machine_id);
+        let mut bonder = bonder.lock().unwrap();
+        bonder.record(bond_time, impedance, shear);
         
         // Run physics-based detection algorithms
         
         // 1. NSOP Detection (Critical - immediate stop)
-        if let Some(t) = self.detect_bond_defect(impedance) {
-            threats.push(t);
-        }
-        
-        // 2. Throughput/OEE Check
-        if let Some(t) = self.detect_throughput_drift(bond_time) {
+        if let Some(t) = bonder.detect_bond_defect(machine_id, impedance, &self.config) {
+            let escalated = matches!(
+                &t,
+                Threat::QualityDefect { defect_type, .. } if defect_type.starts_with("Recurring NSOP")
+            );
             threats.push(t);
+
+            // Escalated NSOP also pages maintenance for the capillary
+            if escalated {
+                threats.push(Threat::EquipmentDegradation {
+                    machine_id: machine_id.to_string(),
+                    component: "Bonding_Capillary".to_string(),
+                    metric: bonder.nsop_events.len() as f64,
+                    limit: self.config.nsop_escalation_count as f64,
+                    severity: Severity::Critical,
+                });
+            } else if let Some(t) = bonder.detect_feed_hold_pattern(machine_id, &self.config) {
+                // A plain NSOP is answered with a FeedHold; too many of them
+                // is a pattern of its own
+                threats.push(t);
+            }
         }
         
-        // 3. Thermal Drift Detection
-        if let Some(t) = self.detect_capillary_drift(capillary_temp, 25.0) {
-            threats.push(t);
+        // NSOP counts consecutive bonds, so only the remaining checks are
+        // thinned out by the analysis cadence
+        let analysis_due = bonder.cadence.tick(self.config.analyze_every_n);
+        let warmed_up = bonder.warmup.tick();
+        if analysis_due {
+            // 2. Throughput/OEE Check
+            if let Some(t) = self.detect_throughput_drift(machine_id, bond_time) {
+                threats.push(t);
+            }
+            
+            // 3. Thermal Drift Detection
+            if let Some(t) = self.detect_capillary_drift(machine_id, capillary_temp, self.config.capillary_baseline_temp) {
+                threats.push(t);
+            }
+            
+            // 4. Bond Strength (hard floor, then trend)
+            if let Some(shear_g) = shear {
+                if let Some(t) = self
+                    .detect_weak_bond(machine_id, shear_g)
+                    .or_else(|| self.detect_shear_trend(machine_id, &bonder.shear_history))
+                {
+                    threats.push(t);
+                }
+            }
+            
+            // 5. OEE Target Check
+            let oee = bonder.calculate_oee();
+            if oee < self.config.target_oee {
+                threats.push(Threat::ThroughputDegradation {
+                    machine_id: machine_id.to_string(),
+                    issue: format!("OEE Below Target: {:.1}%", oee * 100.0),
+                    impact_oee: true,
+                    severity: Severity::Medium,
+                });
+            }
         }
         
-        // 4. OEE Target Check
-        let oee = self.calculate_oee();
-        if oee < self.config.target_oee {
-            threats.push(Threat::ThroughputDegradation {
-                machine_id: self.config.machine_id.clone(),
-                issue: format!("OEE Below Target: {:.1}%", oee * 100.0),
-                impact_oee: true,
-                severity: Severity::Medium,
-            });
+        // Sensors still settling after startup
+        if !warmed_up {
+            threats.clear();
         }
         
         threats
@@ -312,6 +660,13 @@ impl SentinelAgent for AssemblySentinel {
 
     fn safety_circuit(&self, threat: &Threat) -> (ResponseTier, Action) {
         match threat {
+            // Recurring NSOP = systemic capillary/pad issue -> full stop
+            Threat::QualityDefect { defect_type, .. }
+                if defect_type.starts_with("Recurring NSOP") => (
+                ResponseTier::Red,
+                Action::EmergencyStop,
+            ),
+
             // NSOP = Immediate Stop (Waste Prevention)
             // This is a yield-critical defect - stop before more bad units are produced
             Threat::QualityDefect { defect_type, .. } 
@@ -334,12 +689,12 @@ impl SentinelAgent for AssemblySentinel {
             
             // Slow Cycle Time = Auto-Optimize (Green Zone)
             // Increase bond force slightly to improve bonding speed
-            Threat::ThroughputDegradation { issue, .. } 
+            Threat::ThroughputDegradation { machine_id, issue, .. } 
                 if issue.contains("Cycle Time") => (
                 ResponseTier::Green,
                 Action::AdjustParameter {
                     parameter_name: "bond_force".to_string(),
-                    new_value: 1.05,  // Increase force 5%
+                    new_value: self.next_bond_force_scale(machine_id),
                     unit: "percent".to_string(),
                 }
             ),
@@ -372,6 +727,16 @@ impl SentinelAgent for AssemblySentinel {
                     escalate_to: Some("Maintenance".to_string()),
                 }
             ),
+
+            // Equipment degradation = Maintenance page
+            Threat::EquipmentDegradation { component, .. } => (
+                ResponseTier::Yellow,
+                Action::CreateWorkOrder {
+                    priority: "urgent".to_string(),
+                    description: format!("{} degradation - inspect and replace", component),
+                    component: component.clone(),
+                }
+            ),
             
             _ => (ResponseTier::Green, Action::LogOnly),
         }
@@ -481,6 +846,40 @@ impl SentinelAgent for AssemblySentinel {
         }
     }
 
+    fn action_executed(&mut self, machine_id: &str, action: &Action) {
+        if let Action::AdjustParameter { parameter_name, new_value, .. } = action {
+            if parameter_name == "bond_force" {
+                let bonder = self.bonder(machine_id);
+                let mut bonder = bonder.lock().unwrap();
+                bonder.bond_force_before = Some(bonder.bond_force_scale);
+                bonder.bond_force_scale = *new_value;
+            }
+        }
+    }
+
+    fn command_acknowledged(&mut self, ack: &SecsCommandAck) -> Result<(), AgentError> {
+        info!(
+            "[SECS-BRIDGE] S2F42 from {}: {} HCACK={}",
+            ack.machine_id, ack.cmd, ack.hcack
+        );
+        let result = ack.to_result();
+        
+        // A rejected bond force step never reached the bonder: step back
+        if ack.cmd == "BOND_FORCE_ADJ" {
+            if let Some(bonder) = self.bonders.get(&ack.machine_id) {
+                let mut bonder = bonder.lock().unwrap();
+                if let (Err(_), Some(previous)) = (&result, bonder.bond_force_before.take()) {
+                    warn!(
+                        "[ASSEMBLY] {} rejected bond force step - back to {:.2}x nominal",
+                        ack.machine_id, previous
+                    );
+                    bonder.bond_force_scale = previous;
+                }
+            }
+        }
+        result
+    }
+
     fn metadata(&self) -> AgentMetadata {
         AgentMetadata {
             name: "Assembly Sentinel".to_string(),
@@ -507,6 +906,34 @@ impl SentinelAgent for AssemblySentinel {
         machine_id.starts_with("ASM-") ||
         machine_id.starts_with("WB-")
     }
+
+    fn machine_id(&self) -> &str {
+        &self.config.machine_id
+    }
+
+    fn reconfigure(&mut self, patch: &serde_json::Value) -> Result<(), AgentError> {
+        self.config = merge_config(&self.config, patch)?;
+        info!("[ASSEMBLY] {} reconfigured: {}", self.config.machine_id, patch);
+        Ok(())
+    }
+
+    fn acknowledge(&mut self, machine_id: &str) {
+        if let Some(bonder) = self.bonders.get(machine_id) {
+            let mut bonder = bonder.lock().unwrap();
+            bonder.nsop_count = 0;
+            bonder.nsop_events.clear();
+            bonder.feed_holds.clear();
+        }
+        info!("[ASSEMBLY] {} acknowledged: NSOP counters reset", machine_id);
+    }
+    
+    fn baseline_ready(&self) -> bool {
+        self.bonders
+            .get(&self.config.machine_id)
+            .map_or(self.config.warmup_samples == 0, |bonder| {
+                bonder.lock().unwrap().warmup.is_complete()
+            })
+    }
 }
 
 #[cfg(test)]
@@ -525,6 +952,7 @@ mod tests {
             machine_id: "BOND-01".to_string(),
             metrics,
             states: HashMap::new(),
+            units: HashMap::new(),
         }
     }
 
@@ -534,7 +962,7 @@ mod tests {
             min_ultrasonic_impedance: 30.0,
             ..Default::default()
         };
-        let mut agent = AssemblySentinel::new(config);
+        let agent = AssemblySentinel::new(config);
 
         // Normal bonds (impedance > threshold)
         for _ in 0..5 {
@@ -567,7 +995,7 @@ mod tests {
             max_bond_time_ms: 20.0,
             ..Default::default()
         };
-        let mut agent = AssemblySentinel::new(config);
+        let agent = AssemblySentinel::new(config);
 
         // Normal cycle time
         let telemetry = create_test_telemetry(50.0, 15.0);
@@ -589,7 +1017,7 @@ mod tests {
     #[test]
     fn test_oee_calculation() {
         let config = AssemblyConfig::default();
-        let mut agent = AssemblySentinel::new(config.clone());
+        let agent = AssemblySentinel::new(config.clone());
 
         // Add some bond times
         for _ in 0..10 {
@@ -597,17 +1025,17 @@ mod tests {
             agent.analyze(&telemetry);
         }
 
-        let oee = agent.calculate_oee();
+        let oee = agent.bonders.get("BOND-01").unwrap().lock().unwrap().calculate_oee();
         assert!((oee - 1.0).abs() < 0.01, "OEE should be ~100% with 15ms cycle time");
 
         // Now add slower bonds
-        let mut agent2 = AssemblySentinel::new(config);
+        let agent2 = AssemblySentinel::new(config);
         for _ in 0..10 {
             let telemetry = create_test_telemetry(50.0, 20.0);  // 20ms = 75% performance
             agent2.analyze(&telemetry);
         }
 
-        let oee2 = agent2.calculate_oee();
+        let oee2 = agent2.bonders.get("BOND-01").unwrap().lock().unwrap().calculate_oee();
         assert!(oee2 < 0.8, "OEE should be below 80% with 20ms cycle time");
     }
 
@@ -629,6 +1057,230 @@ mod tests {
         assert!(matches!(action, Action::FeedHold { .. }), "NSOP should trigger FeedHold");
     }
 
+    #[test]
+    fn test_nsop_escalation_ladder() {
+        let agent = AssemblySentinel::new(AssemblyConfig::default());
+
+        let mut detections = Vec::new();
+        for _ in 0..9 {
+            let telemetry = create_test_telemetry(20.0, 15.0);
+            detections.extend(
+                agent.analyze(&telemetry)
+                    .into_iter()
+                    .filter(|t| !matches!(t, Threat::ThroughputDegradation { .. })),
+            );
+        }
+
+        // First two runs are single-event NSOPs -> FeedHold
+        let (_, action) = agent.safety_circuit(&detections[0]);
+        assert!(matches!(action, Action::FeedHold { .. }));
+        let (_, action) = agent.safety_circuit(&detections[1]);
+        assert!(matches!(action, Action::FeedHold { .. }));
+
+        // Third run within the window escalates beyond FeedHold
+        let actions: Vec<_> = detections[2..].iter()
+            .map(|t| agent.safety_circuit(t))
+            .collect();
+        assert!(actions.iter().any(|(tier, a)| {
+            *tier == ResponseTier::Red && matches!(a, Action::EmergencyStop)
+        }));
+        assert!(actions.iter().any(|(_, a)| matches!(a, Action::CreateWorkOrder { .. })));
+    }
+
+    #[test]
+    fn test_feed_hold_pattern_alarm_per_machine() {
+        // Ladder out of the way: only the feed hold frequency can escalate
+        let agent = AssemblySentinel::new(AssemblyConfig {
+            nsop_escalation_count: 100,
+            max_feed_holds: 2,
+            ..Default::default()
+        });
+        let nsop_run = |machine_id: &str| -> Vec<Threat> {
+            (0..3)
+                .flat_map(|_| {
+                    let mut telemetry = create_test_telemetry(20.0, 15.0);
+                    telemetry.machine_id = machine_id.to_string();
+                    agent.analyze(&telemetry)
+                })
+                .filter(|t| matches!(t, Threat::QualityDefect { .. }))
+                .collect()
+        };
+        let is_pattern = |t: &Threat| matches!(
+            t,
+            Threat::QualityDefect { defect_type, .. } if defect_type.starts_with("Recurring NSOP pattern")
+        );
+
+        // Two holds on each bonder, interleaved: within the limit for both
+        for _ in 0..2 {
+            for machine_id in ["BOND-01", "BOND-02"] {
+                let detections = nsop_run(machine_id);
+                assert_eq!(detections.len(), 1, "{} should only get the NSOP", machine_id);
+                assert!(!is_pattern(&detections[0]));
+            }
+        }
+
+        // Third hold on BOND-01 raises the pattern on top of the NSOP itself
+        let detections = nsop_run("BOND-01");
+        assert_eq!(detections.len(), 2);
+        assert!(matches!(&detections[0], Threat::QualityDefect { defect_type, .. } if defect_type.starts_with("NSOP")));
+        assert!(is_pattern(&detections[1]));
+        assert_eq!(detections[1].machine_id(), "BOND-01");
+        let (tier, action) = agent.safety_circuit(&detections[1]);
+        assert_eq!(tier, ResponseTier::Red);
+        assert!(matches!(action, Action::EmergencyStop));
+    }
+
+    #[test]
+    fn test_acknowledge_resets_nsop_counter() {
+        let mut agent = AssemblySentinel::new(AssemblyConfig::default());
+        let nsops = |agent: &mut AssemblySentinel, n: usize| -> Vec<Threat> {
+            (0..n)
+                .flat_map(|_| agent.analyze(&create_test_telemetry(20.0, 15.0)))
+                .filter(|t| matches!(t, Threat::QualityDefect { .. }))
+                .collect()
+        };
+
+        // Two NSOPs in, then the operator resolves the incident
+        assert!(nsops(&mut agent, 2).is_empty());
+        agent.acknowledge("BOND-01");
+
+        // The next NSOP starts a fresh count: two more are not a run
+        assert!(nsops(&mut agent, 2).is_empty());
+        let detections = nsops(&mut agent, 1);
+        assert_eq!(detections.len(), 1);
+
+        // ...and the escalation ladder was cleared too
+        for _ in 0..2 {
+            agent.acknowledge("BOND-01");
+            let detections = nsops(&mut agent, 3);
+            let (_, action) = agent.safety_circuit(&detections[0]);
+            assert!(matches!(action, Action::FeedHold { .. }));
+        }
+    }
+
+    #[test]
+    fn test_acknowledge_only_resets_that_bonder() {
+        let mut agent = AssemblySentinel::new(AssemblyConfig::default());
+        let nsops = |agent: &mut AssemblySentinel, machine_id: &str, n: usize| -> usize {
+            (0..n)
+                .flat_map(|_| {
+                    let mut telemetry = create_test_telemetry(20.0, 15.0);
+                    telemetry.machine_id = machine_id.to_string();
+                    agent.analyze(&telemetry)
+                })
+                .filter(|t| matches!(t, Threat::QualityDefect { .. }))
+                .count()
+        };
+        let force_step = Action::AdjustParameter {
+            parameter_name: "bond_force".to_string(),
+            new_value: 1.10,
+            unit: "percent".to_string(),
+        };
+
+        // Both bonders two NSOPs into a run and stepped up to +10% force
+        assert_eq!(nsops(&mut agent, "BOND-01", 2), 0);
+        assert_eq!(nsops(&mut agent, "BOND-02", 2), 0);
+        agent.action_executed("BOND-01", &force_step);
+        agent.action_executed("BOND-02", &force_step);
+
+        agent.acknowledge("BOND-01");
+
+        // BOND-01 starts a fresh NSOP count; BOND-02's run is untouched
+        assert_eq!(nsops(&mut agent, "BOND-01", 1), 0);
+        assert_eq!(nsops(&mut agent, "BOND-02", 1), 1);
+
+        // The executed force is still what the bonder runs with on both
+        assert!((agent.next_bond_force_scale("BOND-01") - 1.15).abs() < 1e-9);
+        assert!((agent.next_bond_force_scale("BOND-02") - 1.15).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_capillary_baseline_temp() {
+        let capillary_drift = |baseline: f64| {
+            let config = AssemblyConfig {
+                capillary_baseline_temp: baseline,
+                ..Default::default()
+            };
+            let agent = AssemblySentinel::new(config);
+            let mut telemetry = create_test_telemetry(50.0, 15.0);
+            telemetry.metrics.insert("capillary_temp".to_string(), 50.0);
+            agent.analyze(&telemetry).into_iter().find_map(|t| match t {
+                Threat::ThermalDrift { drift_mm, .. } => Some(drift_mm),
+                _ => None,
+            })
+        };
+
+        // 25°C rise on a 10mm tungsten carbide capillary: ~1.4µm, over tolerance
+        let drift = capillary_drift(25.0).expect("Should flag drift from 25°C idle");
+        assert!((drift - 5.5e-6 * 10.0 * 25.0).abs() < 1e-12);
+
+        // A process idling at 40°C sees only a 10°C rise: within tolerance
+        assert!(capillary_drift(40.0).is_none());
+    }
+
+    #[test]
+    fn test_declining_shear_warns_before_floor() {
+        let agent = AssemblySentinel::new(AssemblyConfig::default());
+        
+        let mut warning = None;
+        // 30 gf falling 0.5 gf per sample; the last sample is still 22.5 gf
+        for i in 0..16 {
+            let mut telemetry = create_test_telemetry(50.0, 15.0);
+            telemetry.metrics.insert("shear_strength_g".to_string(), 30.0 - 0.5 * i as f64);
+            let threats = agent.analyze(&telemetry);
+            
+            assert!(!threats.iter().any(|t| matches!(t, Threat::QualityDefect { .. })));
+            warning = threats.into_iter().find(|t| matches!(
+                t,
+                Threat::EquipmentDegradation { component, .. } if component == "Bond_Shear_Strength"
+            ));
+        }
+        
+        match warning {
+            // (22.5 - 15) / 0.5 = 15 samples to the floor
+            Some(Threat::EquipmentDegradation { metric, .. }) => assert!((metric - 15.0).abs() < 1e-6),
+            _ => panic!("Declining shear should raise an early degradation warning"),
+        }
+        
+        // A below-floor sample is a weak bond, not a trend
+        let mut telemetry = create_test_telemetry(50.0, 15.0);
+        telemetry.metrics.insert("shear_strength_g".to_string(), 12.0);
+        let threats = agent.analyze(&telemetry);
+        assert!(threats.iter().any(|t| matches!(
+            t,
+            Threat::QualityDefect { defect_type, .. } if defect_type.starts_with("Weak Bond")
+        )));
+    }
+
+    #[test]
+    fn test_command_ack_hcack() {
+        let mut agent = AssemblySentinel::new(AssemblyConfig::default());
+        let ack = |cmd: &str, hcack: u8| SecsCommandAck {
+            cmd: cmd.to_string(),
+            machine_id: "BOND-01".to_string(),
+            hcack,
+        };
+        
+        assert!(agent.command_acknowledged(&ack("STOP", 0)).is_ok());
+        match agent.command_acknowledged(&ack("STOP", 2)) {
+            Err(AgentError::ExecutionError(msg)) => assert!(msg.contains("HCACK=2")),
+            other => panic!("Expected execution error, got {:?}", other),
+        }
+        
+        // Accepted steps stick, a rejected one is rolled back
+        let step = |scale: f64| Action::AdjustParameter {
+            parameter_name: "bond_force".to_string(),
+            new_value: scale,
+            unit: "percent".to_string(),
+        };
+        agent.action_executed("BOND-01", &step(1.02));
+        assert!(agent.command_acknowledged(&ack("BOND_FORCE_ADJ", 0)).is_ok());
+        agent.action_executed("BOND-01", &step(1.04));
+        assert!(agent.command_acknowledged(&ack("BOND_FORCE_ADJ", 2)).is_err());
+        let scale = agent.bonders.get("BOND-01").unwrap().lock().unwrap().bond_force_scale;
+        assert!((scale - 1.02).abs() < 1e-9);
+    }
+
     #[test]
     fn test_safety_circuit_throughput() {
         let config = AssemblyConfig::default();
@@ -646,4 +1298,48 @@ mod tests {
         assert_eq!(tier, ResponseTier::Green, "Throughput issue should trigger Green tier");
         assert!(matches!(action, Action::AdjustParameter { .. }), "Should auto-adjust parameters");
     }
+
+    #[test]
+    fn test_bond_force_step_is_configurable_and_clamped() {
+        let mut agent = AssemblySentinel::new(AssemblyConfig {
+            bond_force_adjust_step: 0.02,
+            max_bond_force_adjust: 0.05,
+            ..Default::default()
+        });
+        let slow_cycle = |machine_id: &str| Threat::ThroughputDegradation {
+            machine_id: machine_id.to_string(),
+            issue: "Cycle Time Drift".to_string(),
+            impact_oee: true,
+            severity: Severity::Medium,
+        };
+        let commanded = |agent: &AssemblySentinel, machine_id: &str| match agent.safety_circuit(&slow_cycle(machine_id)).1 {
+            action @ Action::AdjustParameter { .. } => action,
+            other => panic!("Expected bond force adjustment, got {:?}", other),
+        };
+        let force = |action: &Action| match action {
+            Action::AdjustParameter { parameter_name, new_value, .. } => {
+                assert_eq!(parameter_name, "bond_force");
+                *new_value
+            }
+            _ => unreachable!(),
+        };
+        
+        // Proposals that never execute (dropped, failed) don't step the force
+        assert!((force(&commanded(&agent, "BOND-01")) - 1.02).abs() < 1e-9);
+        assert!((force(&commanded(&agent, "BOND-01")) - 1.02).abs() < 1e-9);
+        
+        let mut executed = Vec::new();
+        for _ in 0..6 {
+            let action = commanded(&agent, "BOND-01");
+            agent.action_executed("BOND-01", &action);
+            executed.push(force(&action));
+        }
+        assert!((executed[0] - 1.02).abs() < 1e-9);
+        assert!((executed[1] - 1.04).abs() < 1e-9);
+        // Further steps stop at the +5% cap
+        assert!(executed[2..].iter().all(|f| (f - 1.05).abs() < 1e-9));
+        
+        // Each bonder steps from its own setting
+        assert!((force(&commanded(&agent, "BOND-02")) - 1.02).abs() < 1e-9);
+    }
 }
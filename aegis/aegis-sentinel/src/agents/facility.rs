@@ -1,28 +1,91 @@
 //! Facility Sentinel - Cleanroom & Infrastructure Agent
 //!
-//! Monitors FFU (Fan Filter Units), HVAC, and Chemical Delivery.
+//! Monitors FFU (Fan Filter Units), HVAC, Scrubber exhaust, and Chemical Delivery.
 //! Physics: Bernoulli's principle for flow, ISO 14644 for particles.
 //! Protocol: Modbus/BACnet (Building Automation)
 
 use async_trait::async_trait;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 use tracing::info;
 
+use crate::detection::{Cadence, Warmup};
+use crate::enrichment::{flow_impedance, thermal_expansion_mm, EnrichedTelemetry, TelemetryEnricher};
 use crate::types::*;
-use super::SentinelAgent;
+use super::{check_range, merge_config, ConfigRanges, MachineStates, SentinelAgent};
 
 /// Facility Sentinel configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FacilityConfig {
     pub iso_class: u8,                  // e.g., Class 5 (ISO 14644-1)
     pub min_airflow_velocity: f64,      // m/s (typically 0.45)
     pub max_filter_pressure_drop: f64,  // Pascals (Pa)
     pub chemical_leak_threshold: f64,   // ppm
-    pub material_cte: f64,              // Coefficient of Thermal Expansion
-    pub spindle_length_mm: f64,         // Distance from bearing to tool tip
+    pub material_cte: f64,              // Duct/chilled-water line CTE (1/°C)
+    #[serde(alias = "spindle_length_mm")]
+    pub duct_length_mm: f64,            // Straight run between expansion joints
+    #[serde(default = "default_duct_baseline_temp")]
+    pub duct_baseline_temp: f64,        // °C at which the run was installed/aligned
+    #[serde(default = "default_max_duct_expansion_mm")]
+    pub max_duct_expansion_mm: f64,     // Movement the expansion joints absorb
+    #[serde(default = "default_impedance_baseline_samples")]
+    pub impedance_baseline_samples: usize, // Readings that anchor the clean-filter baseline
+    #[serde(default = "default_particle_rise_rate_threshold")]
+    pub particle_rise_rate_threshold: f64, // particles/m^3 per minute (early warning)
+    #[serde(default = "default_filter_resistance")]
+    pub filter_resistance: f64,         // Pa per m/s of face velocity (clean filter)
+    #[serde(default = "default_airflow_consistency_ratio")]
+    pub airflow_consistency_ratio: f64, // Max measured/implied airflow disagreement
+    #[serde(default = "default_min_exhaust_flow")]
+    pub min_exhaust_flow: f64,          // m^3/h (scrubber/exhaust duct)
+    #[serde(default = "default_filter_breach_rise_rate")]
+    pub filter_breach_rise_rate: f64,   // particles/m^3 per minute that, with low airflow, means a breach
+    #[serde(default)]
+    pub warmup_samples: usize,          // Readings ignored for detection after startup
+    #[serde(default = "default_analyze_every_n")]
+    pub analyze_every_n: usize,         // Run detectors on every Nth sample (histories take all)
 }
 
+fn default_analyze_every_n() -> usize {
+    1
+}
+
+fn default_duct_baseline_temp() -> f64 {
+    20.0
+}
+
+fn default_max_duct_expansion_mm() -> f64 {
+    0.15 // ~25°C rise on the default 500mm steel run
+}
+
+fn default_impedance_baseline_samples() -> usize {
+    20
+}
+
+fn default_particle_rise_rate_threshold() -> f64 {
+    1000.0
+}
+
+fn default_filter_resistance() -> f64 {
+    333.0 // ~150 Pa at 0.45 m/s
+}
+
+fn default_airflow_consistency_ratio() -> f64 {
+    3.0
+}
+
+fn default_min_exhaust_flow() -> f64 {
+    1000.0
+}
+
+fn default_filter_breach_rise_rate() -> f64 {
+    100.0
+}
+
+/// Particle rates of change averaged into the rise rate (damps counter noise)
+const PARTICLE_SMOOTHING_SAMPLES: usize = 5;
+
 impl Default for FacilityConfig {
     fn default() -> Self {
         Self {
@@ -31,7 +94,81 @@ impl Default for FacilityConfig {
             max_filter_pressure_drop: 250.0,
             chemical_leak_threshold: 10.0,
             material_cte: 11.7e-6,      // Steel default
-            spindle_length_mm: 500.0,
+            duct_length_mm: 500.0,
+            duct_baseline_temp: default_duct_baseline_temp(),
+            max_duct_expansion_mm: default_max_duct_expansion_mm(),
+            impedance_baseline_samples: default_impedance_baseline_samples(),
+            particle_rise_rate_threshold: default_particle_rise_rate_threshold(),
+            filter_resistance: default_filter_resistance(),
+            airflow_consistency_ratio: default_airflow_consistency_ratio(),
+            min_exhaust_flow: default_min_exhaust_flow(),
+            filter_breach_rise_rate: default_filter_breach_rise_rate(),
+            warmup_samples: 0,
+            analyze_every_n: default_analyze_every_n(),
+        }
+    }
+}
+
+impl ConfigRanges for FacilityConfig {
+    fn check_ranges(&self) -> Result<(), AgentError> {
+        check_range("iso_class", self.iso_class as f64, 1.0, 9.0)?;
+        // Measured vs implied airflow, larger over smaller: never below 1
+        check_range("airflow_consistency_ratio", self.airflow_consistency_ratio, 1.0, f64::MAX)
+    }
+}
+
+/// Rolling state for one facility unit
+///
+/// One agent covers every FAC- unit, so the clean-filter baseline, the
+/// particle rates and the warmup are kept per unit; one unit's readings must
+/// not shape another's detections.
+struct UnitState {
+    /// Latest particle rates of change (per minute), from enrichment
+    particle_rates: VecDeque<f64>,
+    /// Earliest impedance readings since the last filter change
+    impedance_anchor: Vec<f64>,
+    warmup: Warmup,
+    cadence: Cadence,
+}
+
+impl UnitState {
+    fn new(warmup_samples: usize) -> Self {
+        Self {
+            particle_rates: VecDeque::with_capacity(PARTICLE_SMOOTHING_SAMPLES),
+            impedance_anchor: Vec::new(),
+            warmup: Warmup::new(warmup_samples),
+            cadence: Cadence::default(),
+        }
+    }
+    
+    /// Record the enriched particle rate and return the smoothed rise rate
+    /// At a steady reporting cadence the mean of the last N rates equals the
+    /// rate of the N-reading moving average.
+    fn particle_rise(&mut self, rate_per_min: Option<f64>) -> Option<f64> {
+        let rate = rate_per_min?;
+        self.particle_rates.push_back(rate);
+        if self.particle_rates.len() > PARTICLE_SMOOTHING_SAMPLES {
+            self.particle_rates.pop_front();
+        }
+        
+        Some(self.particle_rates.iter().sum::<f64>() / self.particle_rates.len() as f64)
+    }
+    
+    /// Clean-filter impedance baseline
+    /// Anchored to the earliest readings rather than a rolling mean: filters
+    /// load monotonically, so a rolling baseline would absorb the degradation.
+    fn baseline_impedance(&self) -> Option<f64> {
+        if self.impedance_anchor.is_empty() {
+            None
+        } else {
+            Some(self.impedance_anchor.iter().sum::<f64>() / self.impedance_anchor.len() as f64)
+        }
+    }
+    
+    /// Record an impedance reading, filling the anchor window if still open
+    fn record_impedance(&mut self, impedance: f64, baseline_samples: usize) {
+        if impedance > 0.0 && self.impedance_anchor.len() < baseline_samples.max(1) {
+            self.impedance_anchor.push(impedance);
         }
     }
 }
@@ -40,9 +177,11 @@ impl Default for FacilityConfig {
 pub struct FacilitySentinel {
     agent_id: String,
     config: FacilityConfig,
-    pressure_history: VecDeque<f64>,
-    particle_history: VecDeque<f64>,
-    airflow_history: VecDeque<f64>,
+    /// Rolling state per FAC- unit
+    units: MachineStates<UnitState>,
+    /// Derives rates for the raw `analyze` entry point; the orchestrator
+    /// enriches upstream and calls `analyze_enriched` directly
+    enricher: Mutex<TelemetryEnricher>,
 }
 
 impl FacilitySentinel {
@@ -50,12 +189,17 @@ impl FacilitySentinel {
         Self {
             agent_id,
             config,
-            pressure_history: VecDeque::with_capacity(100),
-            particle_history: VecDeque::with_capacity(100),
-            airflow_history: VecDeque::with_capacity(100),
+            units: MachineStates::new(),
+            enricher: Mutex::new(TelemetryEnricher::default()),
         }
     }
     
+    /// State for `machine_id`, created on its first reading
+    fn unit(&self, machine_id: &str) -> Arc<Mutex<UnitState>> {
+        let warmup_samples = self.config.warmup_samples;
+        self.units.entry(machine_id, || UnitState::new(warmup_samples))
+    }
+
     pub fn from_config(yaml: serde_yaml::Value) -> Result<Self, AgentError> {
         let config: FacilityConfig = serde_yaml::from_value(yaml.clone())
             .map_err(|e| AgentError::ConfigError(e.to_string()))?;
@@ -69,35 +213,33 @@ impl FacilitySentinel {
     /// Detect HEPA Filter Clogging (Fluid Dynamics)
     /// Physics: Darcy-Weisbach equation variant.
     /// As filter loads, dP increases for same Flow (Q).
-    fn detect_filter_clog(&self, pressure_drop_pa: f64, airflow_mps: f64) -> Option<Threat> {
-        // Normalizing pressure drop against airflow (P/Q)
-        let impedance = if airflow_mps > 0.0 {
-            pressure_drop_pa / airflow_mps
-        } else {
-            0.0
-        };
-        
-        // Track historical impedance for trend analysis
-        let baseline_impedance = if !self.pressure_history.is_empty() {
-            self.pressure_history.iter().sum::<f64>() / self.pressure_history.len() as f64
-        } else {
-            100.0 // Default baseline
-        };
+    /// `impedance` is pressure drop normalized against airflow (P/Q).
+    fn detect_filter_clog(
+        &self,
+        unit_id: &str,
+        pressure_drop_pa: f64,
+        impedance: f64,
+        baseline_impedance: Option<f64>,
+    ) -> Option<Threat> {
+        // Compare against the clean-filter baseline for trend analysis
+        let baseline_impedance = baseline_impedance.unwrap_or(impedance);
         
         // Alert if pressure exceeds threshold or impedance increases significantly
         if pressure_drop_pa > self.config.max_filter_pressure_drop {
             Some(Threat::FacilityIntegrity {
-                unit_id: self.agent_id.clone(),
+                unit_id: unit_id.to_string(),
                 issue: "HEPA Filter End-of-Life".to_string(),
                 severity: Severity::High,
                 metric: pressure_drop_pa,
+                limit: self.config.max_filter_pressure_drop,
             })
         } else if impedance > baseline_impedance * 1.5 {
             Some(Threat::FacilityIntegrity {
-                unit_id: self.agent_id.clone(),
+                unit_id: unit_id.to_string(),
                 issue: "Filter Loading Detected".to_string(),
                 severity: Severity::Medium,
                 metric: impedance,
+                limit: baseline_impedance * 1.5,
             })
         } else {
             None
@@ -106,26 +248,12 @@ impl FacilitySentinel {
     
     /// Detect ISO Class Violation (Particle Physics)
     /// ISO 14644-1 Formula: Cn = 10^N * (0.1/D)^2.08
-    fn detect_contamination(&self, particle_count_0_5um: f64) -> Option<Threat> {
-        // Limit for ISO Class 5 at 0.5µm is ~3,520 particles/m^3
-        let iso_multiplier = match self.config.iso_class {
-            1 => 10.0_f64,
-            2 => 100.0,
-            3 => 1_000.0,
-            4 => 10_000.0,
-            5 => 100_000.0,
-            6 => 1_000_000.0,
-            7 => 10_000_000.0,
-            8 => 100_000_000.0,
-            9 => 1_000_000_000.0,
-            _ => 100_000.0, // Default to Class 5
-        };
-        
-        let limit = iso_multiplier * (0.1_f64 / 0.5_f64).powf(2.08);
+    fn detect_contamination(&self, zone_id: &str, particle_count_0_5um: f64) -> Option<Threat> {
+        let limit = self.iso_limit();
         
         if particle_count_0_5um > limit {
             Some(Threat::Contamination {
-                zone_id: self.agent_id.clone(),
+                zone_id: zone_id.to_string(),
                 particle_count: particle_count_0_5um,
                 limit,
                 severity: Severity::Critical, // Yield killer!
@@ -133,7 +261,7 @@ impl FacilitySentinel {
         } else if particle_count_0_5um > limit * 0.8 {
             // Warning at 80% of limit
             Some(Threat::Contamination {
-                zone_id: self.agent_id.clone(),
+                zone_id: zone_id.to_string(),
                 particle_count: particle_count_0_5um,
                 limit,
                 severity: Severity::High,
@@ -143,14 +271,150 @@ impl FacilitySentinel {
         }
     }
     
+    /// Detect a sudden particle spike below the ISO limit (e.g. garment tear)
+    /// A fast rise usually precedes the breach, so warn on the slope alone.
+    fn detect_particle_spike(&self, zone_id: &str, particle_count_0_5um: f64, rise_per_min: Option<f64>) -> Option<Threat> {
+        match rise_per_min {
+            Some(rate) if rate > self.config.particle_rise_rate_threshold => Some(Threat::Contamination {
+                zone_id: zone_id.to_string(),
+                particle_count: particle_count_0_5um,
+                limit: self.iso_limit(),
+                severity: Severity::Medium,
+            }),
+            _ => None,
+        }
+    }
+    
+    /// ISO 14644-1 particle limit at 0.5µm for the configured class
+    fn iso_limit(&self) -> f64 {
+        // Limit for ISO Class 5 at 0.5µm is ~3,520 particles/m^3
+        let iso_multiplier = match self.config.iso_class {
+            1 => 10.0_f64,
+            2 => 100.0,
+            3 => 1_000.0,
+            4 => 10_000.0,
+            5 => 100_000.0,
+            6 => 1_000_000.0,
+            7 => 10_000_000.0,
+            8 => 100_000_000.0,
+            9 => 1_000_000_000.0,
+            _ => 100_000.0, // Default to Class 5
+        };
+        
+        iso_multiplier * (0.1_f64 / 0.5_f64).powf(2.08)
+    }
+    
     /// Detect airflow velocity drop (FFU failure)
-    fn detect_airflow_failure(&self, airflow_mps: f64) -> Option<Threat> {
+    fn detect_airflow_failure(&self, unit_id: &str, airflow_mps: f64) -> Option<Threat> {
         if airflow_mps < self.config.min_airflow_velocity * 0.8 {
             Some(Threat::FacilityIntegrity {
-                unit_id: self.agent_id.clone(),
+                unit_id: unit_id.to_string(),
                 issue: "FFU Airflow Failure".to_string(),
                 severity: Severity::Critical,
                 metric: airflow_mps,
+                limit: self.config.min_airflow_velocity * 0.8,
+            })
+        } else {
+            None
+        }
+    }
+    
+    /// Detect a filter breach: airflow below minimum while particles climb
+    /// Either alone is a maintenance issue; together they mean unfiltered air
+    /// is reaching the cleanroom, so this escalates before the ISO limit is hit.
+    fn detect_filter_breach(&self, unit_id: &str, airflow_mps: f64, particle_rise_per_min: Option<f64>) -> Option<Threat> {
+        let rising = particle_rise_per_min.is_some_and(|rate| rate > self.config.filter_breach_rise_rate);
+        if airflow_mps < self.config.min_airflow_velocity && rising {
+            Some(Threat::FacilityIntegrity {
+                unit_id: unit_id.to_string(),
+                issue: "Filter Breach (low airflow, rising particles)".to_string(),
+                severity: Severity::Critical,
+                metric: airflow_mps,
+                limit: self.config.min_airflow_velocity,
+            })
+        } else {
+            None
+        }
+    }
+    
+    /// Cross-check the airflow sensor against filter pressure drop (Darcy's law)
+    /// Flow through HEPA media is laminar, so dP scales linearly with face
+    /// velocity. A reading far from the velocity the dP implies points to a
+    /// failed or spoofed sensor rather than a real airflow change.
+    /// The fitted filter's anchored impedance sets the expected resistance
+    /// (the configured `filter_resistance` until the anchor has readings),
+    /// so ordinary loading shows up as filter loading, not a sensor fault.
+    fn detect_airflow_sensor_fault(
+        &self,
+        unit_id: &str,
+        pressure_drop_pa: f64,
+        airflow_mps: f64,
+        baseline_impedance: Option<f64>,
+    ) -> Option<Threat> {
+        let resistance = baseline_impedance.unwrap_or(self.config.filter_resistance);
+        if resistance <= 0.0 {
+            return None;
+        }
+        
+        let implied_mps = pressure_drop_pa.max(0.0) / resistance;
+        // Below this both readings are "no flow" and can't meaningfully disagree
+        let floor = self.config.min_airflow_velocity * 0.1;
+        let disagreement = implied_mps.max(airflow_mps).max(floor)
+            / implied_mps.min(airflow_mps).max(floor);
+        
+        if disagreement > self.config.airflow_consistency_ratio {
+            Some(Threat::FacilityIntegrity {
+                unit_id: unit_id.to_string(),
+                issue: format!("Airflow Sensor Inconsistent (dP implies {:.2} m/s)", implied_mps),
+                severity: Severity::Medium,
+                metric: airflow_mps,
+                // What the sensor should read at this pressure drop
+                limit: implied_mps,
+            })
+        } else {
+            None
+        }
+    }
+    
+    /// Detect scrubber/exhaust failure
+    /// Without exhaust, process off-gas is no longer contained: a safety
+    /// hazard for operators and a contamination source for the cleanroom.
+    fn detect_exhaust_failure(&self, unit_id: &str, exhaust_flow: f64) -> Option<Threat> {
+        if exhaust_flow >= self.config.min_exhaust_flow {
+            return None;
+        }
+        
+        Some(Threat::FacilityIntegrity {
+            unit_id: unit_id.to_string(),
+            issue: "Exhaust Flow Low".to_string(),
+            severity: if exhaust_flow < self.config.min_exhaust_flow * 0.5 {
+                Severity::Critical // Containment effectively lost
+            } else {
+                Severity::High
+            },
+            metric: exhaust_flow,
+            limit: self.config.min_exhaust_flow,
+        })
+    }
+    
+    /// Detect duct/chiller line thermal expansion past the joint allowance
+    /// Physics: dL = CTE * L * dT. Once the joints can't take up the growth the
+    /// run bows, pinching flow and loading flanges and hangers.
+    fn detect_duct_expansion(&self, unit_id: &str, duct_temp: f64) -> Option<Threat> {
+        if self.config.material_cte <= 0.0 || self.config.duct_length_mm <= 0.0 {
+            return None;
+        }
+        
+        let delta_t = duct_temp - self.config.duct_baseline_temp;
+        let expansion_mm = thermal_expansion_mm(self.config.material_cte, self.config.duct_length_mm, delta_t);
+        
+        if expansion_mm.abs() > self.config.max_duct_expansion_mm {
+            Some(Threat::FacilityIntegrity {
+                unit_id: unit_id.to_string(),
+                issue: "Duct Thermal Expansion".to_string(),
+                severity: Severity::Medium,
+                metric: expansion_mm,
+                limit: self.config.max_duct_expansion_mm,
             })
         } else {
             None
@@ -158,11 +422,12 @@ impl FacilitySentinel {
     }
     
     /// Detect chemical leak
-    fn detect_chemical_leak(&self, ppm: f64) -> Option<Threat> {
+    fn detect_chemical_leak(&self, zone_id: &str, ppm: f64) -> Option<Threat> {
         if ppm > self.config.chemical_leak_threshold {
             Some(Threat::ChemicalLeak {
-                zone_id: self.agent_id.clone(),
+                zone_id: zone_id.to_string(),
                 concentration_ppm: ppm,
+                limit: self.config.chemical_leak_threshold,
                 severity: if ppm > self.config.chemical_leak_threshold * 2.0 {
                     Severity::Critical
                 } else {
@@ -177,47 +442,87 @@ impl FacilitySentinel {
 
 #[async_trait]
 impl SentinelAgent for FacilitySentinel {
-    fn analyze(&mut self, telemetry: &Telemetry) -> Vec<Threat> {
+    fn analyze(&self, telemetry: &Telemetry) -> Vec<Threat> {
+        let telemetry = self.enricher.lock().unwrap().enrich(telemetry);
+        self.analyze_enriched(&telemetry)
+    }
+    
+    fn analyze_enriched(&self, telemetry: &EnrichedTelemetry) -> Vec<Threat> {
         let mut threats = Vec::new();
         
-        let pressure = telemetry.metrics.get("pressure_diff_pa").copied().unwrap_or(0.0);
-        let airflow = telemetry.metrics.get("airflow_mps").copied().unwrap_or(0.45);
-        let particles = telemetry.metrics.get("particles_0_5um").copied().unwrap_or(0.0);
-        let chemical_ppm = telemetry.metrics.get("chemical_ppm").copied().unwrap_or(0.0);
+        let pressure = telemetry.metric_or("pressure_diff_pa", 0.0);
+        let airflow = telemetry.metric_or("airflow_mps", 0.45);
+        let particles = telemetry.metric_or("particles_0_5um", 0.0);
+        let chemical_ppm = telemetry.metric_or("chemical_ppm", 0.0);
         
-        // Update history
-        self.pressure_history.push_back(pressure);
-        if self.pressure_history.len() > 100 {
-            self.pressure_history.pop_front();
-        }
+        let machine_id = telemetry.machine_id.as_str();
+        let impedance = telemetry.filter_impedance.unwrap_or_else(|| flow_impedance(pressure, airflow));
+        let baseline_samples = self.config.impedance_baseline_samples;
+        let every_n = self.config.analyze_every_n;
         
-        self.airflow_history.push_back(airflow);
-        if self.airflow_history.len() > 100 {
-            self.airflow_history.pop_front();
-        }
+        // Only the unit's own rolling state is locked; the detectors below
+        // read just the config
+        let (particle_rise, baseline_impedance, warmed_up) = {
+            let unit = self.unit(machine_id);
+            let mut unit = unit.lock().unwrap();
+            let particle_rise = unit.particle_rise(telemetry.rate_per_min("particles_0_5um"));
+            unit.record_impedance(impedance, baseline_samples);
+            let warmed_up = unit.warmup.tick();
+            if !unit.cadence.tick(every_n) {
+                return threats;
+            }
+            (particle_rise, unit.baseline_impedance(), warmed_up)
+        };
         
-        self.particle_history.push_back(particles);
-        if self.particle_history.len() > 100 {
-            self.particle_history.pop_front();
+        // Run detectors
+        if let Some(t) = self.detect_filter_clog(machine_id, pressure, impedance, baseline_impedance) {
+            threats.push(t);
         }
         
-        // Run detectors
-        if let Some(t) = self.detect_filter_clog(pressure, airflow) {
+        if let Some(t) = self.detect_contamination(machine_id, particles) {
+            threats.push(t);
+        } else if let Some(t) = self.detect_particle_spike(machine_id, particles, particle_rise) {
             threats.push(t);
         }
         
-        if let Some(t) = self.detect_contamination(particles) {
+        if let Some(t) = self.detect_airflow_failure(machine_id, airflow) {
             threats.push(t);
         }
         
-        if let Some(t) = self.detect_airflow_failure(airflow) {
+        if let Some(t) = self.detect_filter_breach(machine_id, airflow, particle_rise) {
             threats.push(t);
         }
         
-        if let Some(t) = self.detect_chemical_leak(chemical_ppm) {
+        // Only cross-check when both sensors actually reported
+        if telemetry.metric("pressure_diff_pa").is_some() && telemetry.metric("airflow_mps").is_some() {
+            if let Some(t) = self.detect_airflow_sensor_fault(machine_id, pressure, airflow, baseline_impedance) {
+                threats.push(t);
+            }
+        }
+        
+        // Not every facility unit has an exhaust duct
+        if let Some(exhaust_flow) = telemetry.metric("exhaust_flow") {
+            if let Some(t) = self.detect_exhaust_failure(machine_id, exhaust_flow) {
+                threats.push(t);
+            }
+        }
+        
+        // Only units with a temperature probe on the duct/chilled-water line
+        if let Some(duct_temp) = telemetry.metric("duct_temp") {
+            if let Some(t) = self.detect_duct_expansion(machine_id, duct_temp) {
+                threats.push(t);
+            }
+        }
+        
+        if let Some(t) = self.detect_chemical_leak(machine_id, chemical_ppm) {
             threats.push(t);
         }
         
+        // Sensors still settling after startup
+        if !warmed_up {
+            threats.clear();
+        }
+        
         threats
     }
     
@@ -255,6 +560,15 @@ impl SentinelAgent for FacilitySentinel {
                     component: "FFU_Filter".to_string(),
                 }
             ),
+            // Filter breach -> RED ZONE (contamination reaching product)
+            Threat::FacilityIntegrity { issue, .. } if issue.contains("Filter Breach") => (
+                ResponseTier::Red,
+                Action::SendAlert {
+                    severity: Severity::Critical,
+                    message: "CRITICAL: FFU Filter Breach - Stop Wafer Loading".to_string(),
+                    escalate_to: Some("Process_Engineering".to_string()),
+                }
+            ),
             // Airflow failure -> RED ZONE
             Threat::FacilityIntegrity { issue, .. } if issue.contains("Airflow Failure") => (
                 ResponseTier::Red,
@@ -264,6 +578,42 @@ impl SentinelAgent for FacilitySentinel {
                     escalate_to: Some("Facilities_Manager".to_string()),
                 }
             ),
+            // Exhaust lost -> RED ZONE (safety)
+            Threat::FacilityIntegrity { issue, severity: Severity::Critical, .. } if issue.contains("Exhaust") => (
+                ResponseTier::Red,
+                Action::SendAlert {
+                    severity: Severity::Critical,
+                    message: "CRITICAL: Scrubber Exhaust Failure - Stop Chemical Delivery".to_string(),
+                    escalate_to: Some("EHS".to_string()),
+                }
+            ),
+            // Exhaust degraded -> YELLOW ZONE
+            Threat::FacilityIntegrity { issue, .. } if issue.contains("Exhaust") => (
+                ResponseTier::Yellow,
+                Action::CreateWorkOrder {
+                    priority: "high".to_string(),
+                    description: "Exhaust flow below minimum - inspect scrubber and damper".to_string(),
+                    component: "Scrubber_Exhaust".to_string(),
+                }
+            ),
+            // Sensor disagreement -> YELLOW ZONE (verify before trusting airflow)
+            Threat::FacilityIntegrity { issue, .. } if issue.contains("Sensor Inconsistent") => (
+                ResponseTier::Yellow,
+                Action::CreateWorkOrder {
+                    priority: "high".to_string(),
+                    description: "Airflow reading contradicts filter dP - verify sensor".to_string(),
+                    component: "FFU_Airflow_Sensor".to_string(),
+                }
+            ),
+            // Duct expansion -> YELLOW ZONE (joints/hangers before flow suffers)
+            Threat::FacilityIntegrity { issue, .. } if issue.contains("Thermal Expansion") => (
+                ResponseTier::Yellow,
+                Action::CreateWorkOrder {
+                    priority: "medium".to_string(),
+                    description: "Duct thermal expansion beyond joint allowance - check chiller and expansion joints".to_string(),
+                    component: "HVAC_Duct".to_string(),
+                }
+            ),
             _ => (ResponseTier::Green, Action::LogOnly),
         }
     }
@@ -315,6 +665,7 @@ impl SentinelAgent for FacilitySentinel {
                 "Filter Life Prediction".to_string(),
                 "Particle Monitoring".to_string(),
                 "Chemical Leak Detection".to_string(),
+                "Exhaust Monitoring".to_string(),
             ],
         }
     }
@@ -322,6 +673,24 @@ impl SentinelAgent for FacilitySentinel {
     fn can_handle(&self, machine_id: &str) -> bool {
         self.agent_id == machine_id || machine_id.starts_with("FAC-")
     }
+
+    fn machine_id(&self) -> &str {
+        &self.agent_id
+    }
+
+    fn reconfigure(&mut self, patch: &serde_json::Value) -> Result<(), AgentError> {
+        self.config = merge_config(&self.config, patch)?;
+        info!("[FACILITY] {} reconfigured: {}", self.agent_id, patch);
+        Ok(())
+    }
+    
+    fn baseline_ready(&self) -> bool {
+        self.units
+            .get(&self.agent_id)
+            .map_or(self.config.warmup_samples == 0, |unit| {
+                unit.lock().unwrap().warmup.is_complete()
+            })
+    }
 }
 
 #[cfg(test)]
@@ -331,7 +700,7 @@ mod tests {
     
     #[test]
     fn test_contamination_detection() {
-        let mut agent = FacilitySentinel::new(
+        let agent = FacilitySentinel::new(
             "FAC-001".to_string(),
             FacilityConfig::default(),
         );
@@ -342,6 +711,7 @@ mod tests {
             machine_id: "FAC-001".to_string(),
             metrics: HashMap::new(),
             states: HashMap::new(),
+            units: HashMap::new(),
         };
         
         // Normal particle count for ISO 5
@@ -356,9 +726,333 @@ mod tests {
         assert!(matches!(threats[0], Threat::Contamination { .. }));
     }
     
+    #[test]
+    fn test_particle_spike_below_limit_warns_early() {
+        let agent = FacilitySentinel::new(
+            "FAC-001".to_string(),
+            FacilityConfig::default(),
+        );
+        
+        let mut telemetry = Telemetry {
+            timestamp: "2026-02-07T00:00:00Z".to_string(),
+            machine_id: "FAC-001".to_string(),
+            metrics: HashMap::new(),
+            states: HashMap::new(),
+            units: HashMap::new(),
+        };
+        
+        // Steady counts well inside ISO 5
+        telemetry.metrics.insert("particles_0_5um".to_string(), 800.0);
+        for _ in 0..5 {
+            assert!(agent.analyze(&telemetry).is_empty());
+        }
+        
+        // Fast ramp that stays below the 80% warning level (~2,800)
+        telemetry.timestamp = "2026-02-07T00:00:03Z".to_string();
+        telemetry.metrics.insert("particles_0_5um".to_string(), 2000.0);
+        let threats = agent.analyze(&telemetry);
+        
+        assert_eq!(threats.len(), 1, "Spike should raise an early warning");
+        match &threats[0] {
+            Threat::Contamination { particle_count, limit, severity, .. } => {
+                assert_eq!(*severity, Severity::Medium);
+                assert!(particle_count < limit);
+            }
+            other => panic!("Expected contamination warning, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_particle_rate_tracked_per_machine() {
+        let agent = FacilitySentinel::new(
+            "FAC-001".to_string(),
+            FacilityConfig::default(),
+        );
+        
+        let mut enricher = TelemetryEnricher::default();
+        let mut rise = |machine_id: &str, particles: f64, secs: u32| {
+            let mut telemetry = Telemetry {
+                timestamp: format!("2026-02-07T00:00:{:02}Z", secs),
+                machine_id: machine_id.to_string(),
+                metrics: HashMap::new(),
+                states: HashMap::new(),
+                units: HashMap::new(),
+            };
+            telemetry.metrics.insert("particles_0_5um".to_string(), particles);
+            let rate = enricher.enrich(&telemetry).rate_per_min("particles_0_5um");
+            agent.unit(machine_id).lock().unwrap().particle_rise(rate)
+        };
+        
+        // FAC-001 holds steady while FAC-002 ramps, readings interleaved
+        assert_eq!(rise("FAC-001", 800.0, 0), None);
+        assert_eq!(rise("FAC-002", 100.0, 1), None);
+        for step in 1..=4 {
+            let steady = rise("FAC-001", 800.0, 2 * step).expect("FAC-001 rate");
+            let ramp = rise("FAC-002", 100.0 + 200.0 * step as f64, 2 * step + 1).expect("FAC-002 rate");
+            
+            assert_eq!(steady, 0.0, "steady machine picked up its neighbour's ramp");
+            assert!(ramp > 0.0);
+        }
+    }
+    
+    #[test]
+    fn test_filter_baseline_kept_per_unit() {
+        let agent = FacilitySentinel::new(
+            "FAC-001".to_string(),
+            FacilityConfig::default(),
+        );
+        let reading = |machine_id: &str, pressure: f64| {
+            let mut telemetry = Telemetry {
+                timestamp: "2026-02-07T00:00:00Z".to_string(),
+                machine_id: machine_id.to_string(),
+                metrics: HashMap::new(),
+                states: HashMap::new(),
+                units: HashMap::new(),
+            };
+            telemetry.metrics.insert("pressure_diff_pa".to_string(), pressure);
+            telemetry.metrics.insert("airflow_mps".to_string(), 0.45);
+            telemetry
+        };
+        let loading = |threats: &[Threat]| threats.iter().find_map(|t| match t {
+            Threat::FacilityIntegrity { unit_id, issue, .. } if issue == "Filter Loading Detected" => Some(unit_id.clone()),
+            _ => None,
+        });
+        
+        // FAC-001 anchors on a low-resistance filter first
+        for _ in 0..20 {
+            agent.analyze(&reading("FAC-001", 100.0));
+        }
+        
+        // FAC-002's denser filter is its own clean baseline, not FAC-001 loading
+        for _ in 0..20 {
+            let threats = agent.analyze(&reading("FAC-002", 200.0));
+            assert_eq!(loading(&threats), None);
+        }
+        
+        // FAC-001 loading is still measured against its own anchor
+        let threats = agent.analyze(&reading("FAC-001", 200.0));
+        assert_eq!(loading(&threats).as_deref(), Some("FAC-001"));
+    }
+    
+    #[test]
+    fn test_low_airflow_with_rising_particles_escalates() {
+        // Steady counts, then a ramp that stays inside ISO 5
+        let run = |airflow: f64, ramp_to: f64| {
+            let agent = FacilitySentinel::new(
+                "FAC-001".to_string(),
+                FacilityConfig::default(),
+            );
+            let mut telemetry = Telemetry {
+                timestamp: "2026-02-07T00:00:00Z".to_string(),
+                machine_id: "FAC-001".to_string(),
+                metrics: HashMap::new(),
+                states: HashMap::new(),
+                units: HashMap::new(),
+            };
+            telemetry.metrics.insert("airflow_mps".to_string(), airflow);
+            telemetry.metrics.insert("particles_0_5um".to_string(), 800.0);
+            for _ in 0..5 {
+                agent.analyze(&telemetry);
+            }
+            telemetry.timestamp = "2026-02-07T00:00:03Z".to_string();
+            telemetry.metrics.insert("particles_0_5um".to_string(), ramp_to);
+            agent.analyze(&telemetry)
+                .into_iter()
+                .map(|t| {
+                    let (tier, _) = agent.safety_circuit(&t);
+                    (t.severity(), tier)
+                })
+                .collect::<Vec<_>>()
+        };
+        let escalated = |results: &[(Severity, ResponseTier)]| {
+            results.iter().any(|(severity, tier)| *severity == Severity::Critical && *tier == ResponseTier::Red)
+        };
+        
+        // 0.40 m/s: below the 0.45 minimum but above the 80% failure level
+        let low_airflow_only = run(0.40, 800.0);
+        let rising_particles_only = run(0.45, 2000.0);
+        let both = run(0.40, 2000.0);
+        
+        assert!(!escalated(&low_airflow_only));
+        assert!(!rising_particles_only.is_empty());
+        assert!(!escalated(&rising_particles_only));
+        assert!(escalated(&both));
+    }
+    
+    #[test]
+    fn test_airflow_sensor_inconsistent_with_pressure_drop() {
+        let agent = FacilitySentinel::new(
+            "FAC-001".to_string(),
+            FacilityConfig::default(),
+        );
+        
+        let reading = |pressure: f64, airflow: f64| {
+            let mut telemetry = Telemetry {
+                timestamp: "2026-02-07T00:00:00Z".to_string(),
+                machine_id: "FAC-001".to_string(),
+                metrics: HashMap::new(),
+                states: HashMap::new(),
+                units: HashMap::new(),
+            };
+            telemetry.metrics.insert("pressure_diff_pa".to_string(), pressure);
+            telemetry.metrics.insert("airflow_mps".to_string(), airflow);
+            telemetry
+        };
+        let is_sensor_fault = |t: &Threat| matches!(
+            t,
+            Threat::FacilityIntegrity { issue, .. } if issue.contains("Sensor Inconsistent")
+        );
+        
+        // Consistent: 150 Pa across a clean filter at 0.45 m/s
+        let threats = agent.analyze(&reading(150.0, 0.45));
+        assert!(!threats.iter().any(is_sensor_fault));
+        
+        // Sensor reports nominal airflow but the filter sees almost no dP
+        let threats = agent.analyze(&reading(2.0, 0.45));
+        let fault = threats.iter().find(|t| is_sensor_fault(t)).expect("Should flag sensor fault");
+        assert_eq!(fault.severity(), Severity::Medium);
+        let (tier, _) = agent.safety_circuit(fault);
+        assert_eq!(tier, ResponseTier::Yellow);
+    }
+    
+    #[test]
+    fn test_loading_filter_not_mistaken_for_sensor_fault() {
+        // Configured for a lighter filter grade than the one actually fitted
+        let config = FacilityConfig {
+            filter_resistance: 150.0,
+            ..Default::default()
+        };
+        let agent = FacilitySentinel::new("FAC-001".to_string(), config);
+        let reading = |pressure: f64| {
+            let mut telemetry = Telemetry {
+                timestamp: "2026-02-07T00:00:00Z".to_string(),
+                machine_id: "FAC-001".to_string(),
+                metrics: HashMap::new(),
+                states: HashMap::new(),
+                units: HashMap::new(),
+            };
+            telemetry.metrics.insert("pressure_diff_pa".to_string(), pressure);
+            telemetry.metrics.insert("airflow_mps".to_string(), 0.45);
+            telemetry
+        };
+        let has_issue = |threats: &[Threat], text: &str| threats.iter().any(|t| matches!(
+            t,
+            Threat::FacilityIntegrity { issue, .. } if issue.contains(text)
+        ));
+        
+        // Clean filter anchors at 150 Pa for 0.45 m/s
+        for _ in 0..20 {
+            assert!(!has_issue(&agent.analyze(&reading(150.0)), "Sensor Inconsistent"));
+        }
+        
+        // Loaded 1.5x at the same airflow: filter loading, sensor still trusted
+        let threats = agent.analyze(&reading(225.0));
+        assert!(has_issue(&threats, "Filter Loading"));
+        assert!(!has_issue(&threats, "Sensor Inconsistent"));
+    }
+    
+    #[test]
+    fn test_warmup_suppresses_startup_detections() {
+        let config = FacilityConfig {
+            warmup_samples: 3,
+            ..Default::default()
+        };
+        let agent = FacilitySentinel::new("FAC-001".to_string(), config);
+        
+        // Well above the ISO 5 limit from the very first reading
+        let mut telemetry = Telemetry {
+            timestamp: "2026-02-07T00:00:00Z".to_string(),
+            machine_id: "FAC-001".to_string(),
+            metrics: HashMap::new(),
+            states: HashMap::new(),
+            units: HashMap::new(),
+        };
+        telemetry.metrics.insert("particles_0_5um".to_string(), 5000.0);
+        
+        for _ in 0..3 {
+            assert!(agent.analyze(&telemetry).is_empty());
+        }
+        let threats = agent.analyze(&telemetry);
+        assert!(threats.iter().any(|t| matches!(t, Threat::Contamination { .. })));
+    }
+    
+    #[test]
+    fn test_low_exhaust_flow_detected() {
+        let agent = FacilitySentinel::new(
+            "FAC-001".to_string(),
+            FacilityConfig::default(),
+        );
+        
+        let reading = |exhaust_flow: f64| {
+            let mut telemetry = Telemetry {
+                timestamp: "2026-02-07T00:00:00Z".to_string(),
+                machine_id: "FAC-001".to_string(),
+                metrics: HashMap::new(),
+                states: HashMap::new(),
+                units: HashMap::new(),
+            };
+            telemetry.metrics.insert("exhaust_flow".to_string(), exhaust_flow);
+            telemetry
+        };
+        let exhaust_threat = |threats: Vec<Threat>| threats.into_iter().find(|t| matches!(
+            t,
+            Threat::FacilityIntegrity { issue, .. } if issue.contains("Exhaust")
+        ));
+        
+        assert!(exhaust_threat(agent.analyze(&reading(1500.0))).is_none());
+        
+        // Degraded exhaust -> work order
+        let threat = exhaust_threat(agent.analyze(&reading(800.0))).expect("Should flag low exhaust");
+        assert_eq!(threat.severity(), Severity::High);
+        assert_eq!(agent.safety_circuit(&threat).0, ResponseTier::Yellow);
+        
+        // Exhaust essentially gone -> safety stop
+        let threat = exhaust_threat(agent.analyze(&reading(200.0))).expect("Should flag exhaust loss");
+        assert_eq!(threat.severity(), Severity::Critical);
+        assert_eq!(agent.safety_circuit(&threat).0, ResponseTier::Red);
+    }
+    
+    #[test]
+    fn test_duct_expansion_uses_cte_and_length() {
+        let reading = |duct_temp: f64| {
+            let mut telemetry = Telemetry {
+                timestamp: "2026-02-07T00:00:00Z".to_string(),
+                machine_id: "FAC-001".to_string(),
+                metrics: HashMap::new(),
+                states: HashMap::new(),
+                units: HashMap::new(),
+            };
+            telemetry.metrics.insert("duct_temp".to_string(), duct_temp);
+            telemetry
+        };
+        let expansion = |threats: Vec<Threat>| threats.into_iter().find_map(|t| match t {
+            Threat::FacilityIntegrity { issue, metric, .. } if issue.contains("Thermal Expansion") => Some(metric),
+            _ => None,
+        });
+        
+        // 500mm steel run: +20°C grows 0.117mm, inside the 0.15mm allowance
+        let agent = FacilitySentinel::new("FAC-001".to_string(), FacilityConfig::default());
+        assert!(expansion(agent.analyze(&reading(40.0))).is_none());
+        
+        // +30°C grows 0.1755mm -> work order
+        let threats = agent.analyze(&reading(50.0));
+        let (tier, _) = agent.safety_circuit(&threats[0]);
+        assert_eq!(tier, ResponseTier::Yellow);
+        let grown = expansion(threats).expect("Should flag duct expansion");
+        assert!((grown - 11.7e-6 * 500.0 * 30.0).abs() < 1e-9);
+        
+        // A longer run (legacy key name still accepted) crosses at +20°C
+        let yaml: serde_yaml::Value = serde_yaml::from_str(
+            "iso_class: 5\nmin_airflow_velocity: 0.45\nmax_filter_pressure_drop: 250.0\n\
+             chemical_leak_threshold: 10.0\nmaterial_cte: 11.7e-6\nspindle_length_mm: 1000.0\n",
+        ).unwrap();
+        let agent = FacilitySentinel::from_config(yaml).unwrap();
+        assert!(expansion(agent.analyze(&reading(40.0))).is_some());
+    }
+    
     #[test]
     fn test_filter_clog_detection() {
-        let mut agent = FacilitySentinel::new(
+        let agent = FacilitySentinel::new(
             "FAC-001".to_string(),
             FacilityConfig::default(),
         );
@@ -370,6 +1064,7 @@ mod tests {
                 machine_id: "FAC-001".to_string(),
                 metrics: HashMap::new(),
                 states: HashMap::new(),
+                units: HashMap::new(),
             };
             telemetry.metrics.insert("pressure_diff_pa".to_string(), 150.0);
             telemetry.metrics.insert("airflow_mps".to_string(), 0.45);
@@ -382,6 +1077,7 @@ mod tests {
             machine_id: "FAC-001".to_string(),
             metrics: HashMap::new(),
             states: HashMap::new(),
+            units: HashMap::new(),
         };
         telemetry.metrics.insert("pressure_diff_pa".to_string(), 300.0);
         telemetry.metrics.insert("airflow_mps".to_string(), 0.45);
@@ -389,4 +1085,39 @@ mod tests {
         let threats = agent.analyze(&telemetry);
         assert!(!threats.is_empty(), "Should detect filter clog");
     }
+    
+    #[test]
+    fn test_gradual_filter_loading_detected() {
+        let agent = FacilitySentinel::new(
+            "FAC-001".to_string(),
+            FacilityConfig::default(),
+        );
+        
+        // Pressure drop creeps up 1% per reading at constant airflow, staying
+        // below the end-of-life threshold the whole time
+        let mut loading_detected = false;
+        for i in 0..60 {
+            let mut telemetry = Telemetry {
+                timestamp: "2026-02-07T00:00:00Z".to_string(),
+                machine_id: "FAC-001".to_string(),
+                metrics: HashMap::new(),
+                states: HashMap::new(),
+                units: HashMap::new(),
+            };
+            let pressure = 100.0 * 1.01_f64.powi(i);
+            telemetry.metrics.insert("pressure_diff_pa".to_string(), pressure);
+            telemetry.metrics.insert("airflow_mps".to_string(), 0.45);
+            
+            let threats = agent.analyze(&telemetry);
+            if i < 20 {
+                assert!(threats.is_empty(), "Clean filter flagged at reading {}", i);
+            }
+            loading_detected |= threats.iter().any(|t| matches!(
+                t,
+                Threat::FacilityIntegrity { issue, .. } if issue == "Filter Loading Detected"
+            ));
+        }
+        
+        assert!(loading_detected, "Sustained loading should not become the new normal");
+    }
 }
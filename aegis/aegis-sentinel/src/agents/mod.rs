@@ -9,6 +9,10 @@
 //! - **Assembly Sentinel**: Wire bonding & packaging
 
 use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use crate::enrichment::EnrichedTelemetry;
 use crate::types::*;
 
 pub mod precision;
@@ -19,7 +23,18 @@ pub mod assembly;
 #[async_trait]
 pub trait SentinelAgent: Send + Sync {
     /// Analyze telemetry and return detected threats
-    fn analyze(&mut self, telemetry: &Telemetry) -> Vec<Threat>;
+    ///
+    /// Takes `&self` so several machines can be analyzed at once: rolling
+    /// state lives behind per-machine locks (see [`MachineStates`]).
+    fn analyze(&self, telemetry: &Telemetry) -> Vec<Threat>;
+
+    /// Analyze telemetry with derived features already attached
+    ///
+    /// Agents that use the shared features override this; the rest just see
+    /// the raw telemetry.
+    fn analyze_enriched(&self, telemetry: &EnrichedTelemetry) -> Vec<Threat> {
+        self.analyze(telemetry)
+    }
 
     /// Determine safety circuit response for a threat
     fn safety_circuit(&self, threat: &Threat) -> (ResponseTier, Action);
@@ -27,9 +42,179 @@ pub trait SentinelAgent: Send + Sync {
     /// Execute an action
     async fn execute(&self, action: &Action) -> Result<(), AgentError>;
 
+    /// Record that `action` was carried out on `machine_id`
+    ///
+    /// Called once `execute` succeeded. Agents that step a setpoint from one
+    /// command to the next advance it here, so a command that was dropped or
+    /// failed leaves it where it was.
+    fn action_executed(&mut self, _machine_id: &str, _action: &Action) {}
+
+    /// Handle the equipment's S2F42 reply to a command relayed for this agent
+    ///
+    /// Returns `ExecutionError` if the equipment rejected the command (HCACK
+    /// != 0); agents that stepped a setpoint in `action_executed` roll it back.
+    fn command_acknowledged(&mut self, ack: &SecsCommandAck) -> Result<(), AgentError> {
+        ack.to_result()
+    }
+
     /// Get agent metadata
     fn metadata(&self) -> AgentMetadata;
 
     /// Check if this agent can handle a specific machine
     fn can_handle(&self, machine_id: &str) -> bool;
+
+    /// Machine this agent was configured for
+    fn machine_id(&self) -> &str;
+
+    /// Apply a partial config update (e.g. new thresholds) in place
+    fn reconfigure(&mut self, patch: &serde_json::Value) -> Result<(), AgentError>;
+
+    /// Clear `machine_id`'s incident state once an operator has resolved it
+    ///
+    /// Resets that machine's counters and escalation ladders so a past
+    /// incident doesn't shape later decisions; rolling history and baselines
+    /// are kept, and other machines the agent handles are left alone.
+    fn acknowledge(&mut self, _machine_id: &str) {}
+
+    /// Whether the agent has seen enough data to trust its detections
+    /// (agents without a warm-up are always ready)
+    fn baseline_ready(&self) -> bool {
+        true
+    }
+}
+
+/// Rolling state per machine, each behind its own lock
+///
+/// The map itself is only locked to look a machine up (or add it), so
+/// agents covering several machines analyze them concurrently; readings for
+/// one machine are serialized by its lock.
+pub struct MachineStates<S> {
+    states: Mutex<HashMap<String, Arc<Mutex<S>>>>,
+}
+
+impl<S> MachineStates<S> {
+    pub fn new() -> Self {
+        Self { states: Mutex::default() }
+    }
+
+    /// State for `machine_id`, created with `init` on its first reading
+    pub fn entry(&self, machine_id: &str, init: impl FnOnce() -> S) -> Arc<Mutex<S>> {
+        let mut states = self.states.lock().unwrap();
+        Arc::clone(
+            states
+                .entry(machine_id.to_string())
+                .or_insert_with(|| Arc::new(Mutex::new(init()))),
+        )
+    }
+
+    /// State for `machine_id`, if it has reported
+    pub fn get(&self, machine_id: &str) -> Option<Arc<Mutex<S>>> {
+        self.states.lock().unwrap().get(machine_id).cloned()
+    }
+}
+
+impl<S> Default for MachineStates<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Agent config with value ranges beyond "a non-negative number"
+pub trait ConfigRanges {
+    /// `ConfigError` naming the first field outside its usable range
+    fn check_ranges(&self) -> Result<(), AgentError> {
+        Ok(())
+    }
+}
+
+/// `ConfigError` unless `min <= value <= max`
+fn check_range(key: &str, value: f64, min: f64, max: f64) -> Result<(), AgentError> {
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(AgentError::ConfigError(format!(
+            "'{}' must be between {} and {}, got {}",
+            key, min, max, value
+        )))
+    }
+}
+
+/// Overlay `patch` on `config`
+///
+/// Only existing keys may be changed, and never `machine_id`, so a typo
+/// in a threshold name is rejected instead of silently ignored. Patched
+/// numbers must be finite and non-negative, and the merged config must
+/// pass its own range checks.
+pub fn merge_config<C>(config: &C, patch: &serde_json::Value) -> Result<C, AgentError>
+where
+    C: Serialize + DeserializeOwned + ConfigRanges,
+{
+    let patch = patch
+        .as_object()
+        .ok_or_else(|| AgentError::ConfigError("Config update must be an object".to_string()))?;
+    let mut merged = serde_json::to_value(config)
+        .map_err(|e| AgentError::ConfigError(e.to_string()))?;
+    let fields = merged
+        .as_object_mut()
+        .ok_or_else(|| AgentError::ConfigError("Config is not a struct".to_string()))?;
+
+    for (key, value) in patch {
+        if key == "machine_id" {
+            return Err(AgentError::ConfigError("machine_id cannot be changed".to_string()));
+        }
+        if let Some(number) = value.as_f64() {
+            if !number.is_finite() || number < 0.0 {
+                return Err(AgentError::ConfigError(format!(
+                    "'{}' must be a non-negative number, got {}",
+                    key, number
+                )));
+            }
+        }
+        match fields.get_mut(key) {
+            Some(field) => *field = value.clone(),
+            None => return Err(AgentError::ConfigError(format!("Unknown config key '{}'", key))),
+        }
+    }
+
+    let merged: C = serde_json::from_value(merged).map_err(|e| AgentError::ConfigError(e.to_string()))?;
+    merged.check_ranges()?;
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::precision::PrecisionConfig;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_config_rejects_unknown_keys() {
+        let config = PrecisionConfig::default();
+
+        let merged = merge_config(&config, &json!({"vibration_critical": 9.0})).unwrap();
+        assert_eq!(merged.vibration_critical, 9.0);
+        assert_eq!(merged.thermal_drift_max, config.thermal_drift_max);
+
+        assert!(merge_config(&config, &json!({"vibration_critcal": 9.0})).is_err());
+        assert!(merge_config(&config, &json!({"vibration_critical": "high"})).is_err());
+        assert!(merge_config(&config, &json!({"machine_id": "CNC-009"})).is_err());
+        assert!(merge_config(&config, &json!([1, 2])).is_err());
+    }
+
+    #[test]
+    fn test_merge_config_rejects_out_of_range_values() {
+        let precision = PrecisionConfig::default();
+        assert!(merge_config(&precision, &json!({"vibration_critical": -1.0})).is_err());
+        assert!(merge_config(&precision, &json!({"chatter_ratio_threshold": 0.5})).is_err());
+        assert!(merge_config(&precision, &json!({"thermal_runaway_warn_temp": 500.0})).is_err());
+
+        let assembly = assembly::AssemblyConfig::default();
+        assert!(merge_config(&assembly, &json!({"target_oee": 85.0})).is_err());
+        assert_eq!(merge_config(&assembly, &json!({"target_oee": 0.9})).unwrap().target_oee, 0.9);
+
+        let facility = facility::FacilityConfig::default();
+        assert!(merge_config(&facility, &json!({"iso_class": 0})).is_err());
+        assert!(merge_config(&facility, &json!({"airflow_consistency_ratio": 0.8})).is_err());
+        assert!(merge_config(&facility, &json!({"iso_class": 7})).is_ok());
+    }
 }
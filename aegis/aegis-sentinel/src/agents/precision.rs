@@ -5,20 +5,111 @@
 //! and surface finish optimization.
 
 use super::*;
-use serde::Deserialize;
+use crate::detection::{magnitude_spectrum, Cadence, DetectorPipeline, Warmup};
+use crate::enrichment::thermal_expansion_mm;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
 
 /// Precision Sentinel configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrecisionConfig {
     pub vibration_critical: f64,        // mm/s - ISO 10816 Category D threshold
     pub thermal_drift_max: f64,         // mm - Maximum allowable drift
     pub tool_wear_threshold: f64,       // % load increase indicating wear
     pub chatter_detection_enabled: bool,
+    /// Vibration / rolling-baseline ratio that flags chatter
+    #[serde(default = "default_chatter_ratio_threshold")]
+    pub chatter_ratio_threshold: f64,
     pub thermal_comp_enabled: bool,
+    /// Readings a small drift must hold steady over before it is auto-compensated
+    /// (drifts too large to compensate alert on the first reading)
+    #[serde(default = "default_thermal_comp_confirm_samples")]
+    pub thermal_comp_confirm_samples: usize,
+    /// Spread (mm) the confirming drift readings must stay within
+    #[serde(default = "default_thermal_comp_band_mm")]
+    pub thermal_comp_band_mm: f64,
+    /// Spindle temperature (°C) that trips runaway regardless of trend
+    #[serde(default = "default_thermal_runaway_temp")]
+    pub thermal_runaway_temp: f64,
+    /// Temperature (°C) above which a fast rise also trips runaway
+    #[serde(default = "default_thermal_runaway_warn_temp")]
+    pub thermal_runaway_warn_temp: f64,
+    /// Rate of rise (°C/min) that trips runaway above the warn temperature
+    #[serde(default = "default_thermal_runaway_roc")]
+    pub thermal_runaway_roc: f64,
     pub tool_wear_tracking_enabled: bool,
+    /// Load increase over baseline (fraction) at which the tool is worn out
+    #[serde(default = "default_critical_wear_fraction")]
+    pub critical_wear_fraction: f64,
+    /// Assumed wear progression (load fraction per hour) for remaining-life estimates
+    #[serde(default = "default_tool_wear_rate_per_hour")]
+    pub tool_wear_rate_per_hour: f64,
+    /// Vibration samples kept for spectral analysis (rounded up to a power of two)
+    #[serde(default = "default_fft_window")]
+    pub fft_window: usize,
+    /// Rate (Hz) vibration readings arrive at; turns spectrum bins into frequencies
+    #[serde(default = "default_vibration_sample_rate_hz")]
+    pub vibration_sample_rate_hz: f64,
+    /// Readings ignored for detection after startup (sensor settling)
+    #[serde(default)]
+    pub warmup_samples: usize,
+    /// Run detectors on every Nth sample (histories still take every one)
+    #[serde(default = "default_analyze_every_n")]
+    pub analyze_every_n: usize,
 }
 
+fn default_chatter_ratio_threshold() -> f64 {
+    3.0
+}
+
+fn default_thermal_comp_confirm_samples() -> usize {
+    3
+}
+
+fn default_thermal_comp_band_mm() -> f64 {
+    0.002
+}
+
+fn default_critical_wear_fraction() -> f64 {
+    0.25
+}
+
+fn default_tool_wear_rate_per_hour() -> f64 {
+    0.01
+}
+
+fn default_fft_window() -> usize {
+    256
+}
+
+fn default_vibration_sample_rate_hz() -> f64 {
+    1.0
+}
+
+fn default_analyze_every_n() -> usize {
+    1
+}
+
+fn default_thermal_runaway_temp() -> f64 {
+    95.0
+}
+
+fn default_thermal_runaway_warn_temp() -> f64 {
+    80.0
+}
+
+fn default_thermal_runaway_roc() -> f64 {
+    5.0
+}
+
+/// Degrees above the runaway temperature at which runaway becomes Critical
+const THERMAL_RUNAWAY_CRITICAL_MARGIN: f64 = 5.0;
+
+/// Largest thermal drift (mm) corrected with a Z offset; beyond it a
+/// stabilization cycle is proposed instead
+const THERMAL_COMP_MAX_DRIFT_MM: f64 = 0.02;
+
 impl Default for PrecisionConfig {
     fn default() -> Self {
         Self {
@@ -26,8 +117,98 @@ impl Default for PrecisionConfig {
             thermal_drift_max: 0.05,
             tool_wear_threshold: 0.15,
             chatter_detection_enabled: true,
+            chatter_ratio_threshold: default_chatter_ratio_threshold(),
             thermal_comp_enabled: true,
+            thermal_comp_confirm_samples: default_thermal_comp_confirm_samples(),
+            thermal_comp_band_mm: default_thermal_comp_band_mm(),
+            thermal_runaway_temp: default_thermal_runaway_temp(),
+            thermal_runaway_warn_temp: default_thermal_runaway_warn_temp(),
+            thermal_runaway_roc: default_thermal_runaway_roc(),
             tool_wear_tracking_enabled: true,
+            critical_wear_fraction: default_critical_wear_fraction(),
+            tool_wear_rate_per_hour: default_tool_wear_rate_per_hour(),
+            fft_window: default_fft_window(),
+            vibration_sample_rate_hz: default_vibration_sample_rate_hz(),
+            warmup_samples: 0,
+            analyze_every_n: default_analyze_every_n(),
+        }
+    }
+}
+
+impl ConfigRanges for PrecisionConfig {
+    fn check_ranges(&self) -> Result<(), AgentError> {
+        check_range("chatter_ratio_threshold", self.chatter_ratio_threshold, 1.0, f64::MAX)?;
+        check_range("critical_wear_fraction", self.critical_wear_fraction, 0.0, 1.0)?;
+        check_range(
+            "vibration_sample_rate_hz",
+            self.vibration_sample_rate_hz,
+            f64::MIN_POSITIVE,
+            f64::MAX,
+        )?;
+        check_range(
+            "thermal_runaway_warn_temp",
+            self.thermal_runaway_warn_temp,
+            0.0,
+            self.thermal_runaway_temp,
+        )
+    }
+}
+
+/// Rolling detection state, updated on every telemetry message
+struct PrecisionState {
+    vibration_history: VecDeque<f64>,
+    temp_history: VecDeque<f64>,
+    load_history: VecDeque<f64>,
+    fft_buffer: VecDeque<f64>,  // Spectral window, independent of statistics window
+    baseline_load: Option<f64>,
+    warmup: Warmup,
+    cadence: Cadence,
+}
+
+impl PrecisionState {
+    fn new(config: &PrecisionConfig) -> Self {
+        Self {
+            vibration_history: VecDeque::with_capacity(100),
+            temp_history: VecDeque::with_capacity(100),
+            load_history: VecDeque::with_capacity(100),
+            fft_buffer: VecDeque::with_capacity(config.fft_window),
+            baseline_load: None,
+            warmup: Warmup::new(config.warmup_samples),
+            cadence: Cadence::default(),
+        }
+    }
+    
+    /// Update rolling histories and baselines with a telemetry sample
+    fn record(&mut self, telemetry: &Telemetry, fft_window: usize) {
+        let vibration = vibration(telemetry);
+        let temp = temperature(telemetry);
+        let load = load_percent(telemetry);
+        
+        self.vibration_history.push_back(vibration);
+        if self.vibration_history.len() > 100 {
+            self.vibration_history.pop_front();
+        }
+        
+        self.fft_buffer.push_back(vibration);
+        if self.fft_buffer.len() > fft_window {
+            self.fft_buffer.pop_front();
+        }
+        
+        self.temp_history.push_back(temp);
+        if self.temp_history.len() > 100 {
+            self.temp_history.pop_front();
+        }
+        
+        self.load_history.push_back(load);
+        if self.load_history.len() > 100 {
+            self.load_history.pop_front();
+        }
+        
+        // Set baseline on first stable data
+        if self.baseline_load.is_none() && self.load_history.len() > 50 {
+            self.baseline_load = Some(
+                self.load_history.iter().sum::<f64>() / self.load_history.len() as f64
+            );
         }
     }
 }
@@ -37,29 +218,52 @@ pub struct PrecisionSentinel {
     machine_id: String,
     config: PrecisionConfig,
     
-    // Detection state
-    vibration_history: VecDeque<f64>,
-    temp_history: VecDeque<f64>,
-    load_history: VecDeque<f64>,
-    
-    // Baselines
-    baseline_load: Option<f64>,
+    // Detection state, behind a lock so `analyze` can take `&self`
+    state: Mutex<PrecisionState>,
     baseline_temp: f64,
+    
+    pipeline: DetectorPipeline<PrecisionSentinel>,
+}
+
+/// Metric readings, with the defaults assumed when a machine omits one
+fn vibration(telemetry: &Telemetry) -> f64 {
+    telemetry.metric_or("vibration", 0.0)
+}
+
+fn temperature(telemetry: &Telemetry) -> f64 {
+    telemetry.metric_or("temperature", 20.0)
+}
+
+fn load_percent(telemetry: &Telemetry) -> f64 {
+    telemetry.metric_or("load_percent", 0.0)
 }
 
 impl PrecisionSentinel {
-    pub fn new(machine_id: String, config: PrecisionConfig) -> Self {
+    pub fn new(machine_id: String, mut config: PrecisionConfig) -> Self {
+        config.fft_window = config.fft_window.max(2).next_power_of_two();
         Self {
             machine_id,
+            state: Mutex::new(PrecisionState::new(&config)),
             config,
-            vibration_history: VecDeque::with_capacity(100),
-            temp_history: VecDeque::with_capacity(100),
-            load_history: VecDeque::with_capacity(100),
-            baseline_load: None,
             baseline_temp: 20.0,
+            pipeline: Self::default_pipeline(),
         }
     }
     
+    /// Detectors run on every telemetry message, in order
+    fn default_pipeline() -> DetectorPipeline<Self> {
+        DetectorPipeline::new()
+            .with_stage("chatter", |agent: &Self, t| agent.detect_chatter(vibration(t)))
+            .with_stage("thermal_drift", |agent: &Self, t| agent.detect_thermal_drift(temperature(t)))
+            .with_stage("tool_wear", |agent: &Self, t| agent.detect_tool_wear(load_percent(t)))
+            .with_stage("thermal_runaway", |agent: &Self, t| agent.detect_thermal_runaway(temperature(t)))
+            .with_stage("bearing_failure", |agent: &Self, t| agent.detect_bearing_failure(vibration(t)))
+    }
+    
+    fn state(&self) -> MutexGuard<'_, PrecisionState> {
+        self.state.lock().unwrap()
+    }
+    
     pub fn from_config(yaml: serde_yaml::Value) -> Result<Self, AgentError> {
         let config: PrecisionConfig = serde_yaml::from_value(yaml.clone())
             .map_err(|e| AgentError::ConfigError(e.to_string()))?;
@@ -72,26 +276,55 @@ impl PrecisionSentinel {
         Ok(Self::new(machine_id, config))
     }
     
+    /// Vibration magnitude spectrum over the FFT window
+    ///
+    /// `None` until the buffer holds a full `fft_window` of samples.
+    pub fn vibration_spectrum(&self) -> Option<Vec<f64>> {
+        let state = self.state();
+        if state.fft_buffer.len() < self.config.fft_window {
+            return None;
+        }
+        let samples: Vec<f64> = state.fft_buffer.iter().copied().collect();
+        magnitude_spectrum(&samples)
+    }
+    
+    /// Frequency (Hz) of the strongest non-DC spectrum bin
+    ///
+    /// `None` until the FFT window is full.
+    fn dominant_frequency_hz(&self) -> Option<f64> {
+        let spectrum = self.vibration_spectrum()?;
+        let (bin, _) = spectrum
+            .iter()
+            .enumerate()
+            .skip(1)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+        Some(bin as f64 * self.config.vibration_sample_rate_hz / self.config.fft_window as f64)
+    }
+    
     /// Detect chatter (regenerative vibration)
     fn detect_chatter(&self, vibration: f64) -> Option<Threat> {
         if !self.config.chatter_detection_enabled {
             return None;
         }
         
-        if self.vibration_history.is_empty() {
-            return None;
-        }
-        
-        // Simplified chatter detection (production would use FFT)
-        let baseline = self.vibration_history.iter()
-            .copied()
-            .sum::<f64>() / self.vibration_history.len() as f64;
+        let baseline = {
+            let state = self.state();
+            if state.vibration_history.is_empty() {
+                return None;
+            }
+            state.vibration_history.iter()
+                .copied()
+                .sum::<f64>() / state.vibration_history.len() as f64
+        };
         
-        if vibration > baseline * 3.0 {
+        let limit = baseline * self.config.chatter_ratio_threshold;
+        if vibration > limit {
             Some(Threat::Chatter {
                 machine_id: self.machine_id.clone(),
-                frequency_hz: 0.0,  // TODO: FFT analysis
+                // 0.0 until the FFT window has filled
+                frequency_hz: self.dominant_frequency_hz().unwrap_or(0.0),
                 amplitude_mm_s: vibration,
+                limit,
                 severity: if vibration > self.config.vibration_critical {
                     Severity::Critical
                 } else {
@@ -109,18 +342,19 @@ impl PrecisionSentinel {
             return None;
         }
         
-        // Thermal expansion: ΔL = α * L * ΔT
-        let cte_steel = 11.7e-6;  // Coefficient of thermal expansion
-        let spindle_distance = 500.0;  // mm from spindle to part
-        
-        let temp_rise = temp - self.baseline_temp;
-        let drift_mm = cte_steel * spindle_distance * temp_rise;
+        let drift_mm = self.thermal_drift_mm(temp);
         
         if drift_mm.abs() > self.config.thermal_drift_max {
+            // Small drifts are compensated automatically, so one noisy
+            // reading must not move the axis
+            if drift_mm.abs() < THERMAL_COMP_MAX_DRIFT_MM && !self.drift_confirmed() {
+                return None;
+            }
             Some(Threat::ThermalDrift {
                 machine_id: self.machine_id.clone(),
                 drift_mm,
                 axis: "Z".to_string(),
+                limit: self.config.thermal_drift_max,
                 severity: if drift_mm.abs() > 0.1 {
                     Severity::Critical
                 } else {
@@ -132,21 +366,53 @@ impl PrecisionSentinel {
         }
     }
     
+    /// Z drift (mm) for a spindle temperature
+    fn thermal_drift_mm(&self, temp: f64) -> f64 {
+        // Thermal expansion: ΔL = α * L * ΔT
+        let cte_steel = 11.7e-6;  // Coefficient of thermal expansion
+        let spindle_distance = 500.0;  // mm from spindle to part
+        
+        thermal_expansion_mm(cte_steel, spindle_distance, temp - self.baseline_temp)
+    }
+    
+    /// Whether the drift held within the band over the last confirmation readings
+    fn drift_confirmed(&self) -> bool {
+        let samples = self.config.thermal_comp_confirm_samples.max(1);
+        let state = self.state();
+        if state.temp_history.len() < samples {
+            return false;
+        }
+        let (low, high) = state.temp_history.iter()
+            .rev()
+            .take(samples)
+            .map(|temp| self.thermal_drift_mm(*temp))
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), drift| {
+                (low.min(drift), high.max(drift))
+            });
+        high - low <= self.config.thermal_comp_band_mm
+    }
+    
     /// Detect tool wear
     fn detect_tool_wear(&self, load_percent: f64) -> Option<Threat> {
         if !self.config.tool_wear_tracking_enabled {
             return None;
         }
         
-        let baseline = self.baseline_load?;
+        let baseline = self.state().baseline_load?;
         let wear = (load_percent - baseline) / baseline;
         
         if wear > self.config.tool_wear_threshold {
+            let critical = self.config.critical_wear_fraction;
+            // Rough estimate: linear wear until the critical fraction
+            let remaining_life_minutes = (self.config.tool_wear_rate_per_hour > 0.0)
+                .then(|| (60.0 * (critical - wear) / self.config.tool_wear_rate_per_hour).max(0.0));
+            
             Some(Threat::ToolWear {
                 machine_id: self.machine_id.clone(),
                 wear_percent: wear * 100.0,
-                remaining_life_minutes: Some(60.0 * (0.25 - wear) / 0.01),  // Rough estimate
-                severity: if wear > 0.25 {
+                remaining_life_minutes,
+                limit: self.config.tool_wear_threshold * 100.0,
+                severity: if wear > critical {
                     Severity::Critical
                 } else {
                     Severity::High
@@ -159,20 +425,28 @@ impl PrecisionSentinel {
     
     /// Detect thermal runaway
     fn detect_thermal_runaway(&self, temp: f64) -> Option<Threat> {
-        if self.temp_history.len() < 10 {
+        let state = self.state();
+        if state.temp_history.len() < 10 {
             return None;
         }
         
         // Calculate rate of change (per minute)
-        let recent_temps: Vec<f64> = self.temp_history.iter().rev().take(10).copied().collect();
+        let recent_temps: Vec<f64> = state.temp_history.iter().rev().take(10).copied().collect();
         let roc = (recent_temps[0] - recent_temps[recent_temps.len() - 1]) * 6.0; // per minute
         
-        if temp > 95.0 || (temp > 80.0 && roc > 5.0) {
+        let runaway_temp = self.config.thermal_runaway_temp;
+        let warn_temp = self.config.thermal_runaway_warn_temp;
+        if temp > runaway_temp || (temp > warn_temp && roc > self.config.thermal_runaway_roc) {
             Some(Threat::ThermalRunaway {
                 machine_id: self.machine_id.clone(),
                 temperature: temp,
                 rate_of_change: roc,
-                severity: if temp > 100.0 { Severity::Critical } else { Severity::High },
+                limit: if temp > runaway_temp { runaway_temp } else { warn_temp },
+                severity: if temp > runaway_temp + THERMAL_RUNAWAY_CRITICAL_MARGIN {
+                    Severity::Critical
+                } else {
+                    Severity::High
+                },
             })
         } else {
             None
@@ -185,81 +459,38 @@ impl PrecisionSentinel {
         let warning_threshold = 0.02;  // mm/s RMS
         let critical_threshold = 0.05; // mm/s RMS
         
-        if vibration > critical_threshold {
-            Some(Threat::BearingFailure {
-                machine_id: self.machine_id.clone(),
-                vibration_mm_s: vibration,
-                frequency_hz: 0.0,  // TODO: FFT for bearing frequencies
-                severity: Severity::Critical,
-            })
+        let (limit, severity) = if vibration > critical_threshold {
+            (critical_threshold, Severity::Critical)
         } else if vibration > warning_threshold {
-            Some(Threat::BearingFailure {
-                machine_id: self.machine_id.clone(),
-                vibration_mm_s: vibration,
-                frequency_hz: 0.0,
-                severity: Severity::High,
-            })
+            (warning_threshold, Severity::High)
         } else {
-            None
-        }
+            return None;
+        };
+        
+        Some(Threat::BearingFailure {
+            machine_id: self.machine_id.clone(),
+            vibration_mm_s: vibration,
+            // 0.0 until the FFT window has filled
+            frequency_hz: self.dominant_frequency_hz().unwrap_or(0.0),
+            limit,
+            severity,
+        })
     }
 }
 
 #[async_trait]
 impl SentinelAgent for PrecisionSentinel {
-    fn analyze(&mut self, telemetry: &Telemetry) -> Vec<Threat> {
-        let mut threats = Vec::new();
-        
-        // Extract metrics
-        let vibration = telemetry.metrics.get("vibration").copied().unwrap_or(0.0);
-        let temp = telemetry.metrics.get("temperature").copied().unwrap_or(20.0);
-        let load = telemetry.metrics.get("load_percent").copied().unwrap_or(0.0);
-        
-        // Update history
-        self.vibration_history.push_back(vibration);
-        if self.vibration_history.len() > 100 {
-            self.vibration_history.pop_front();
-        }
-        
-        self.temp_history.push_back(temp);
-        if self.temp_history.len() > 100 {
-            self.temp_history.pop_front();
-        }
-        
-        self.load_history.push_back(load);
-        if self.load_history.len() > 100 {
-            self.load_history.pop_front();
-        }
-        
-        // Set baseline on first stable data
-        if self.baseline_load.is_none() && self.load_history.len() > 50 {
-            self.baseline_load = Some(
-                self.load_history.iter().sum::<f64>() / self.load_history.len() as f64
-            );
-        }
-        
-        // Run detectors
-        if let Some(threat) = self.detect_chatter(vibration) {
-            threats.push(threat);
-        }
-        
-        if let Some(threat) = self.detect_thermal_drift(temp) {
-            threats.push(threat);
-        }
-        
-        if let Some(threat) = self.detect_tool_wear(load) {
-            threats.push(threat);
-        }
-        
-        if let Some(threat) = self.detect_thermal_runaway(temp) {
-            threats.push(threat);
-        }
-        
-        if let Some(threat) = self.detect_bearing_failure(vibration) {
-            threats.push(threat);
+    fn analyze(&self, telemetry: &Telemetry) -> Vec<Threat> {
+        let (warmed_up, due) = {
+            let mut state = self.state();
+            state.record(telemetry, self.config.fft_window);
+            (state.warmup.tick(), state.cadence.tick(self.config.analyze_every_n))
+        };
+        if warmed_up && due {
+            self.pipeline.run(self, telemetry)
+        } else {
+            Vec::new()
         }
-        
-        threats
     }
     
     fn safety_circuit(&self, threat: &Threat) -> (ResponseTier, Action) {
@@ -288,7 +519,7 @@ impl SentinelAgent for PrecisionSentinel {
             },
             
             Threat::ThermalDrift { drift_mm, severity, .. } => {
-                if drift_mm.abs() < 0.02 {
+                if drift_mm.abs() < THERMAL_COMP_MAX_DRIFT_MM {
                     // GREEN: Small drift, apply compensation
                     (ResponseTier::Green, Action::AdjustParameter {
                         parameter_name: "z_axis_offset".to_string(),
@@ -323,8 +554,8 @@ impl SentinelAgent for PrecisionSentinel {
                 }
             },
             
-            Threat::ThermalRunaway { temperature, .. } => {
-                if *temperature > 100.0 {
+            Threat::ThermalRunaway { severity, .. } => {
+                if *severity == Severity::Critical {
                     // RED: Emergency stop required
                     (ResponseTier::Red, Action::EmergencyStop)
                 } else {
@@ -420,6 +651,26 @@ impl SentinelAgent for PrecisionSentinel {
     fn can_handle(&self, machine_id: &str) -> bool {
         self.machine_id == machine_id
     }
+
+    fn machine_id(&self) -> &str {
+        &self.machine_id
+    }
+
+    fn reconfigure(&mut self, patch: &serde_json::Value) -> Result<(), AgentError> {
+        let mut config = merge_config(&self.config, patch)?;
+        config.fft_window = config.fft_window.max(2).next_power_of_two();
+        let state = self.state.get_mut().unwrap();
+        while state.fft_buffer.len() > config.fft_window {
+            state.fft_buffer.pop_front();
+        }
+        self.config = config;
+        tracing::info!("[PRECISION] {} reconfigured: {}", self.machine_id, patch);
+        Ok(())
+    }
+    
+    fn baseline_ready(&self) -> bool {
+        self.state().warmup.is_complete()
+    }
 }
 
 #[cfg(test)]
@@ -429,7 +680,7 @@ mod tests {
     
     #[test]
     fn test_chatter_detection() {
-        let mut agent = PrecisionSentinel::new(
+        let agent = PrecisionSentinel::new(
             "TEST-001".to_string(),
             PrecisionConfig::default(),
         );
@@ -441,6 +692,7 @@ mod tests {
                 machine_id: "TEST-001".to_string(),
                 metrics: HashMap::new(),
                 states: HashMap::new(),
+                units: HashMap::new(),
             };
             telemetry.metrics.insert("vibration".to_string(), 0.001);
             telemetry.metrics.insert("temperature".to_string(), 65.0);
@@ -455,6 +707,7 @@ mod tests {
             machine_id: "TEST-001".to_string(),
             metrics: HashMap::new(),
             states: HashMap::new(),
+            units: HashMap::new(),
         };
         telemetry.metrics.insert("vibration".to_string(), 0.015);  // 15x baseline
         telemetry.metrics.insert("temperature".to_string(), 65.0);
@@ -465,4 +718,281 @@ mod tests {
         assert!(!threats.is_empty(), "Should detect chatter");
         assert!(matches!(threats[0], Threat::Chatter { .. }));
     }
+    
+    fn vibration_telemetry(vibration: f64) -> Telemetry {
+        let mut telemetry = Telemetry {
+            timestamp: "2026-02-07T00:00:00Z".to_string(),
+            machine_id: "TEST-001".to_string(),
+            metrics: HashMap::new(),
+            states: HashMap::new(),
+            units: HashMap::new(),
+        };
+        telemetry.metrics.insert("vibration".to_string(), vibration);
+        telemetry.metrics.insert("temperature".to_string(), 20.0);
+        telemetry
+    }
+    
+    fn detects_chatter(ratio: f64, spike: f64) -> bool {
+        let config = PrecisionConfig {
+            chatter_ratio_threshold: ratio,
+            ..Default::default()
+        };
+        let agent = PrecisionSentinel::new("TEST-001".to_string(), config);
+        for _ in 0..50 {
+            agent.analyze(&vibration_telemetry(0.001));
+        }
+        agent.analyze(&vibration_telemetry(spike))
+            .iter()
+            .any(|t| matches!(t, Threat::Chatter { .. }))
+    }
+    
+    #[test]
+    fn test_nan_vibration_treated_as_missing() {
+        let agent = PrecisionSentinel::new("TEST-001".to_string(), PrecisionConfig::default());
+        for _ in 0..50 {
+            agent.analyze(&vibration_telemetry(0.001));
+        }
+        
+        // A faulted accelerometer reports NaN: no panic, no spurious threat
+        for value in [f64::NAN, f64::INFINITY, f64::NAN] {
+            assert!(agent.analyze(&vibration_telemetry(value)).is_empty());
+        }
+        assert!(agent.state().vibration_history.iter().all(|v| v.is_finite()));
+        
+        // Detection still works once the sensor recovers
+        assert!(agent.analyze(&vibration_telemetry(0.015))
+            .iter()
+            .any(|t| matches!(t, Threat::Chatter { .. })));
+    }
+    
+    #[test]
+    fn test_analyze_every_n_thins_detection_not_history() {
+        let detections = |every_n: usize| {
+            let config = PrecisionConfig {
+                analyze_every_n: every_n,
+                ..Default::default()
+            };
+            let agent = PrecisionSentinel::new("TEST-001".to_string(), config);
+            // Past the ISO 10816 critical level: every analyzed sample raises a threat
+            let flagged = (0..20)
+                .filter(|_| !agent.analyze(&vibration_telemetry(0.06)).is_empty())
+                .count();
+            let history = agent.state().vibration_history.len();
+            (flagged, history)
+        };
+        
+        assert_eq!(detections(1), (20, 20));
+        assert_eq!(detections(5), (4, 20));
+    }
+    
+    #[test]
+    fn test_thermal_comp_needs_sustained_drift() {
+        let config = PrecisionConfig {
+            thermal_drift_max: 0.01,
+            ..Default::default()
+        };
+        let drift_threats = |agent: &mut PrecisionSentinel, temp: f64| -> Vec<Threat> {
+            let mut telemetry = vibration_telemetry(0.001);
+            telemetry.metrics.insert("temperature".to_string(), temp);
+            agent.analyze(&telemetry)
+                .into_iter()
+                .filter(|t| matches!(t, Threat::ThermalDrift { .. }))
+                .collect()
+        };
+        
+        // 2.5°C over baseline: ~0.015mm, small enough to auto-compensate
+        let mut agent = PrecisionSentinel::new("TEST-001".to_string(), config.clone());
+        for _ in 0..3 {
+            assert!(drift_threats(&mut agent, 20.0).is_empty());
+        }
+        assert!(drift_threats(&mut agent, 22.5).is_empty(), "single noisy reading");
+        assert!(drift_threats(&mut agent, 20.0).is_empty());
+        
+        // Held for three readings, the offset is applied
+        let sustained: Vec<Vec<Threat>> = (0..3).map(|_| drift_threats(&mut agent, 22.5)).collect();
+        assert!(sustained[..2].iter().all(|threats| threats.is_empty()));
+        let (tier, action) = agent.safety_circuit(&sustained[2][0]);
+        assert_eq!(tier, ResponseTier::Green);
+        assert!(matches!(action, Action::AdjustParameter { ref parameter_name, .. } if parameter_name == "z_axis_offset"));
+        
+        // A drift too large to compensate alerts on its first reading
+        let mut agent = PrecisionSentinel::new("TEST-001".to_string(), config);
+        drift_threats(&mut agent, 20.0);
+        let threats = drift_threats(&mut agent, 25.0);
+        assert_eq!(threats.len(), 1);
+        assert_eq!(agent.safety_circuit(&threats[0]).0, ResponseTier::Yellow);
+    }
+    
+    #[test]
+    fn test_chatter_ratio_threshold() {
+        // 2.5x baseline: missed at the default 3.0, flagged at 2.0
+        assert!(!detects_chatter(3.0, 0.0025));
+        assert!(detects_chatter(2.0, 0.0025));
+        
+        // 4x baseline: flagged at the default, suppressed at 5.0
+        assert!(detects_chatter(3.0, 0.004));
+        assert!(!detects_chatter(5.0, 0.004));
+    }
+    
+    fn runaway_severity(config: PrecisionConfig, temperature: f64) -> Option<Severity> {
+        let agent = PrecisionSentinel::new("TEST-001".to_string(), config);
+        let mut telemetry = Telemetry {
+            timestamp: "2026-02-07T00:00:00Z".to_string(),
+            machine_id: "TEST-001".to_string(),
+            metrics: HashMap::new(),
+            states: HashMap::new(),
+            units: HashMap::new(),
+        };
+        telemetry.metrics.insert("temperature".to_string(), temperature);
+        
+        let mut severity = None;
+        for _ in 0..10 {
+            severity = agent.analyze(&telemetry).iter().find_map(|t| match t {
+                Threat::ThermalRunaway { severity, .. } => Some(*severity),
+                _ => None,
+            });
+        }
+        severity
+    }
+    
+    #[test]
+    fn test_thermal_runaway_thresholds_configurable() {
+        // Steady 92°C is acceptable with the default 95°C runaway temperature
+        assert_eq!(runaway_severity(PrecisionConfig::default(), 92.0), None);
+        
+        // A low-expansion spindle tuned to 85°C: 92°C is past the critical margin
+        let config = PrecisionConfig {
+            thermal_runaway_temp: 85.0,
+            ..Default::default()
+        };
+        assert_eq!(runaway_severity(config, 92.0), Some(Severity::Critical));
+    }
+    
+    #[test]
+    fn test_critical_wear_fraction_configurable() {
+        let wear_threat = |critical_wear_fraction: f64| {
+            let config = PrecisionConfig {
+                critical_wear_fraction,
+                ..Default::default()
+            };
+            let agent = PrecisionSentinel::new("TEST-001".to_string(), config);
+            agent.state().baseline_load = Some(50.0);
+            // 22% over baseline: past the 15% wear threshold
+            match agent.detect_tool_wear(61.0) {
+                Some(Threat::ToolWear { severity, remaining_life_minutes, .. }) => {
+                    (severity, remaining_life_minutes.unwrap())
+                }
+                other => panic!("Expected tool wear, got {:?}", other),
+            }
+        };
+        
+        let (severity, remaining) = wear_threat(0.25);
+        assert_eq!(severity, Severity::High);
+        assert!((remaining - 180.0).abs() < 1e-6);  // 3% to go at 1%/h
+        
+        // Tighter limit for a finishing tool: same reading is now past critical
+        let (severity, remaining) = wear_threat(0.20);
+        assert_eq!(severity, Severity::Critical);
+        assert_eq!(remaining, 0.0);
+    }
+    
+    #[test]
+    fn test_pipeline_matches_hand_coded_detectors() {
+        let piped = PrecisionSentinel::new("TEST-001".to_string(), PrecisionConfig::default());
+        let manual = PrecisionSentinel::new("TEST-001".to_string(), PrecisionConfig::default());
+        
+        // Warm-up, then vibration spikes, load creep and a heating spindle
+        let mut raised = 0;
+        for i in 0..120 {
+            let mut telemetry = Telemetry {
+                timestamp: "2026-02-07T00:00:00Z".to_string(),
+                machine_id: "TEST-001".to_string(),
+                metrics: HashMap::new(),
+                states: HashMap::new(),
+                units: HashMap::new(),
+            };
+            let vibration = if i > 60 && i % 7 == 0 { 0.03 } else { 0.001 };
+            telemetry.metrics.insert("vibration".to_string(), vibration);
+            telemetry.metrics.insert("temperature".to_string(), 20.0 + i as f64 * 0.8);
+            telemetry.metrics.insert("load_percent".to_string(), 60.0 + (i as f64 - 60.0).max(0.0));
+            
+            let from_pipeline = piped.analyze(&telemetry);
+            
+            // The detectors exactly as `analyze` used to call them
+            manual.state().record(&telemetry, manual.config.fft_window);
+            let (v, temp, load) = (vibration, 20.0 + i as f64 * 0.8, 60.0 + (i as f64 - 60.0).max(0.0));
+            let expected: Vec<Threat> = [
+                manual.detect_chatter(v),
+                manual.detect_thermal_drift(temp),
+                manual.detect_tool_wear(load),
+                manual.detect_thermal_runaway(temp),
+                manual.detect_bearing_failure(v),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            
+            assert_eq!(format!("{:?}", from_pipeline), format!("{:?}", expected), "reading {}", i);
+            raised += expected.len();
+        }
+        assert!(raised > 0, "Scenario should exercise the detectors");
+    }
+    
+    #[test]
+    fn test_fft_buffer_independent_of_stats_window() {
+        let config = PrecisionConfig {
+            fft_window: 200,  // Rounded up to 256
+            ..Default::default()
+        };
+        let agent = PrecisionSentinel::new("TEST-001".to_string(), config);
+        
+        for i in 0..300 {
+            let mut telemetry = Telemetry {
+                timestamp: "2026-02-07T00:00:00Z".to_string(),
+                machine_id: "TEST-001".to_string(),
+                metrics: HashMap::new(),
+                states: HashMap::new(),
+                units: HashMap::new(),
+            };
+            telemetry.metrics.insert("vibration".to_string(), 0.001 * (1.0 + (i % 4) as f64));
+            agent.analyze(&telemetry);
+            
+            if i == 254 {
+                assert!(agent.vibration_spectrum().is_none(), "Buffer not yet full");
+            }
+        }
+        
+        assert_eq!(agent.state().vibration_history.len(), 100);
+        assert_eq!(agent.state().fft_buffer.len(), 256);
+        assert_eq!(agent.vibration_spectrum().unwrap().len(), 129);
+    }
+    
+    #[test]
+    fn test_vibration_threats_report_dominant_frequency() {
+        let config = PrecisionConfig {
+            chatter_ratio_threshold: 1.2,
+            vibration_sample_rate_hz: 1000.0,
+            ..Default::default()
+        };
+        let agent = PrecisionSentinel::new("TEST-001".to_string(), config);
+        
+        // 125 Hz: bin 32 of the 256-sample window at 1 kHz
+        let mut frequencies = Vec::new();
+        for i in 0..300 {
+            let phase = 2.0 * std::f64::consts::PI * 32.0 * i as f64 / 256.0;
+            for threat in agent.analyze(&vibration_telemetry(0.03 + 0.02 * phase.sin())) {
+                match threat {
+                    Threat::Chatter { frequency_hz, .. }
+                    | Threat::BearingFailure { frequency_hz, .. } => frequencies.push((i, frequency_hz)),
+                    _ => {}
+                }
+            }
+        }
+        
+        assert!(frequencies.iter().any(|(i, _)| *i >= 255));
+        for (i, frequency_hz) in frequencies {
+            let expected = if i < 255 { 0.0 } else { 125.0 };
+            assert!((frequency_hz - expected).abs() < 1e-9, "reading {}: {} Hz", i, frequency_hz);
+        }
+    }
 }
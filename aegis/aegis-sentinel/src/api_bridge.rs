@@ -4,17 +4,21 @@
 //! agent heartbeats, and telemetry directly to the YieldOps API.
 //! This ensures real-time data sharing with the Supabase backend.
 
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Mutex;
 use tracing::{debug, error, info, warn};
 
-use crate::types::{Action, Incident, ResponseTier, Severity, Threat};
+use crate::types::{Action, AgentHeartbeat, Incident, ResponseTier, Severity, Threat};
 
 /// YieldOps API Client
 pub struct YieldOpsClient {
     client: Client,
     base_url: String,
     api_key: Option<String>,
+    /// Agents registered this session, with the capabilities they registered
+    registered: Mutex<HashMap<String, Vec<String>>>,
 }
 
 impl YieldOpsClient {
@@ -29,6 +33,7 @@ impl YieldOpsClient {
             client,
             base_url,
             api_key,
+            registered: Mutex::new(HashMap::new()),
         }
     }
 
@@ -84,6 +89,10 @@ impl YieldOpsClient {
     }
 
     /// Register an agent with YieldOps
+    ///
+    /// Idempotent within a session: re-registering an agent with unchanged
+    /// capabilities is skipped, and an "already registered" response from
+    /// the server is treated as success.
     pub async fn register_agent(
         &self,
         agent_id: &str,
@@ -91,6 +100,14 @@ impl YieldOpsClient {
         machine_id: &str,
         capabilities: &[String],
     ) -> Result<(), ApiError> {
+        let mut sorted_capabilities = capabilities.to_vec();
+        sorted_capabilities.sort();
+
+        if self.registered.lock().unwrap().get(agent_id) == Some(&sorted_capabilities) {
+            debug!("Agent {} already registered this session - skipping", agent_id);
+            return Ok(());
+        }
+
         let url = format!("{}/api/v1/aegis/agents/register", self.base_url);
 
         let payload = json!({
@@ -109,23 +126,35 @@ impl YieldOpsClient {
 
         match request.send().await {
             Ok(response) => {
-                if response.status().is_success() {
+                let status = response.status();
+                if status.is_success() {
                     info!("Agent registered: {}", agent_id);
-                    Ok(())
                 } else {
-                    warn!("Failed to register agent: {}", response.status());
-                    Err(ApiError::HttpError(response.status().to_string()))
+                    let text = response.text().await.unwrap_or_default();
+                    if status != StatusCode::CONFLICT
+                        && !text.to_lowercase().contains("already registered")
+                    {
+                        warn!("Failed to register agent: {}", status);
+                        return Err(ApiError::HttpError(status.to_string()));
+                    }
+                    info!("Agent {} already registered with YieldOps", agent_id);
                 }
+
+                self.registered
+                    .lock()
+                    .unwrap()
+                    .insert(agent_id.to_string(), sorted_capabilities);
+                Ok(())
             }
             Err(e) => Err(ApiError::HttpError(e.to_string())),
         }
     }
 
-    /// Send agent heartbeat
-    pub async fn heartbeat(&self, agent_id: &str) -> Result<(), ApiError> {
+    /// Send agent heartbeat with its current health
+    pub async fn heartbeat(&self, agent_id: &str, health: &AgentHeartbeat) -> Result<(), ApiError> {
         let url = format!("{}/api/v1/aegis/agents/{}/heartbeat", self.base_url, agent_id);
 
-        let mut request = self.client.post(&url);
+        let mut request = self.client.post(&url).json(health);
 
         if let Some(key) = &self.api_key {
             request = request.header("Authorization", format!("Bearer {}", key));
@@ -221,6 +250,51 @@ pub async fn report_threat(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Minimal HTTP server answering every request with `status_line`,
+    /// counting requests and capturing their bodies
+    async fn mock_server(status_line: &'static str) -> (String, Arc<AtomicUsize>, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+
+        let (hits_clone, bodies_clone) = (Arc::clone(&hits), Arc::clone(&bodies));
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                // Read headers, then the declared body length
+                loop {
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some(header_end) = text.find("\r\n\r\n") {
+                        let content_length = text.lines()
+                            .map(|l| l.to_lowercase())
+                            .find_map(|l| l.strip_prefix("content-length:")?.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if request.len() >= header_end + 4 + content_length {
+                            bodies_clone.lock().unwrap().push(text[header_end + 4..].to_string());
+                            break;
+                        }
+                    }
+                }
+                hits_clone.fetch_add(1, Ordering::SeqCst);
+                let response = format!("{}\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{{}}", status_line);
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (url, hits, bodies)
+    }
 
     #[test]
     fn test_client_creation() {
@@ -230,4 +304,69 @@ mod tests {
         );
         assert_eq!(client.base_url, "http://localhost:8000");
     }
+
+    #[tokio::test]
+    async fn test_register_agent_is_idempotent() {
+        let (url, hits, _) = mock_server("HTTP/1.1 200 OK").await;
+        let client = YieldOpsClient::new(url, None);
+        let capabilities = vec!["nsop_detection".to_string()];
+
+        client.register_agent("agent-assembly-bond-01", "assembly", "BOND-01", &capabilities).await.unwrap();
+        client.register_agent("agent-assembly-bond-01", "assembly", "BOND-01", &capabilities).await.unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 1, "second registration should be skipped");
+
+        // Changed capabilities re-register
+        let capabilities = vec!["nsop_detection".to_string(), "oee_calculation".to_string()];
+        client.register_agent("agent-assembly-bond-01", "assembly", "BOND-01", &capabilities).await.unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_carries_agent_health() {
+        use crate::agents::precision::{PrecisionConfig, PrecisionSentinel};
+        use crate::agents::SentinelAgent;
+        use crate::orchestrator::Orchestrator;
+        use crate::types::Telemetry;
+        use tokio::sync::RwLock;
+
+        let agent: Arc<RwLock<dyn SentinelAgent>> = Arc::new(RwLock::new(PrecisionSentinel::new(
+            "CNC-001".to_string(),
+            PrecisionConfig::default(),
+        )));
+        let orchestrator = Orchestrator::new(vec![agent]);
+        let mut metrics = HashMap::new();
+        metrics.insert("temperature".to_string(), 22.0);
+        metrics.insert("vibration".to_string(), 0.02);
+        let telemetry = Telemetry {
+            timestamp: "2026-02-07T00:00:00Z".to_string(),
+            machine_id: "CNC-001".to_string(),
+            metrics,
+            states: HashMap::new(),
+            units: HashMap::new(),
+        };
+        for _ in 0..3 {
+            orchestrator.process(&telemetry).await;
+        }
+
+        let (url, _, bodies) = mock_server("HTTP/1.1 200 OK").await;
+        let client = YieldOpsClient::new(url, None);
+        let heartbeat = &orchestrator.heartbeats().await[0];
+        client.heartbeat("agent-precision-cnc-001", heartbeat).await.unwrap();
+
+        let body: serde_json::Value = serde_json::from_str(&bodies.lock().unwrap()[0]).unwrap();
+        assert_eq!(body["machine_id"], "CNC-001");
+        assert_eq!(body["samples_processed"], 3);
+        assert_eq!(body["baseline_ready"], true);
+        assert!(body["last_threat_at"].is_null());
+        assert_eq!(body["health_score"], 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_register_agent_tolerates_conflict() {
+        let (url, _, _) = mock_server("HTTP/1.1 409 Conflict").await;
+        let client = YieldOpsClient::new(url, None);
+
+        let result = client.register_agent("agent-facility-fac-001", "facility", "FAC-001", &[]).await;
+        assert!(result.is_ok());
+    }
 }
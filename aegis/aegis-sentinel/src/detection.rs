@@ -3,8 +3,11 @@
 //! Implements Z-score analysis, rate-of-change detection, and other
 //! statistical methods for anomaly detection.
 
+use chrono::{DateTime, Utc};
 use std::collections::VecDeque;
 
+use crate::types::{Telemetry, Threat};
+
 /// Statistical analyzer using Z-score and rate-of-change
 pub struct StatisticalAnalyzer {
     window_size: usize,
@@ -20,7 +23,12 @@ impl StatisticalAnalyzer {
     }
     
     /// Add a new value and calculate statistics
+    ///
+    /// NaN/Inf values are not recorded.
     pub fn update(&mut self, value: f64) -> Statistics {
+        if !value.is_finite() {
+            return Statistics::from_data(&self.history);
+        }
         self.history.push_back(value);
         if self.history.len() > self.window_size {
             self.history.pop_front();
@@ -32,6 +40,9 @@ impl StatisticalAnalyzer {
     /// Calculate Z-score for a value
     pub fn z_score(&self, value: f64) -> Option<f64> {
         let stats = Statistics::from_data(&self.history);
+        if !value.is_finite() || stats.count == 0 {
+            return None;
+        }
         if stats.std_dev == 0.0 {
             return Some(0.0);
         }
@@ -58,7 +69,9 @@ pub struct Statistics {
 }
 
 impl Statistics {
+    /// Summarize the finite values in `data`; NaN/Inf entries are skipped
     pub fn from_data(data: &VecDeque<f64>) -> Self {
+        let data: Vec<f64> = data.iter().copied().filter(|v| v.is_finite()).collect();
         if data.is_empty() {
             return Self {
                 mean: 0.0,
@@ -78,8 +91,8 @@ impl Statistics {
             .sum::<f64>() / count as f64;
         let std_dev = variance.sqrt();
         
-        let min = *data.iter().min_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
-        let max = *data.iter().max_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
+        let min = data.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = data.iter().copied().fold(f64::NEG_INFINITY, f64::max);
         
         Self {
             mean,
@@ -94,7 +107,7 @@ impl Statistics {
 /// Rate of change detector
 pub struct RateOfChangeDetector {
     last_value: Option<f64>,
-    last_time: Option<std::time::Instant>,
+    last_time: Option<DateTime<Utc>>,
 }
 
 impl RateOfChangeDetector {
@@ -105,12 +118,23 @@ impl RateOfChangeDetector {
         }
     }
     
-    /// Calculate rate of change per minute
+    /// Calculate rate of change per minute, timed by arrival
+    ///
+    /// A NaN/Inf value yields `None` and leaves the previous reading in place.
     pub fn update(&mut self, value: f64) -> Option<f64> {
-        let now = std::time::Instant::now();
+        self.update_at(value, Utc::now())
+    }
+    
+    /// Calculate rate of change per minute for a reading taken at `at`
+    ///
+    /// Readings that don't move forward in time yield `None`.
+    pub fn update_at(&mut self, value: f64, at: DateTime<Utc>) -> Option<f64> {
+        if !value.is_finite() {
+            return None;
+        }
         
         let roc = if let (Some(last_val), Some(last_time)) = (self.last_value, self.last_time) {
-            let time_delta = now.duration_since(last_time).as_secs_f64();
+            let time_delta = (at - last_time).num_microseconds().unwrap_or(0) as f64 / 1e6;
             if time_delta > 0.0 {
                 let value_delta = value - last_val;
                 Some(value_delta / time_delta * 60.0) // per minute
@@ -122,12 +146,170 @@ impl RateOfChangeDetector {
         };
         
         self.last_value = Some(value);
-        self.last_time = Some(now);
+        self.last_time = Some(at);
         
         roc
     }
 }
 
+impl Default for RateOfChangeDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Startup gate: holds back detections while sensors settle
+///
+/// Histories still fill during warm-up; only threat emission is suppressed.
+#[derive(Debug, Clone)]
+pub struct Warmup {
+    required: usize,
+    seen: usize,
+}
+
+impl Warmup {
+    pub fn new(required: usize) -> Self {
+        Self { required, seen: 0 }
+    }
+    
+    /// Count a sample; `true` once more than `required` samples have arrived
+    pub fn tick(&mut self) -> bool {
+        self.seen = self.seen.saturating_add(1);
+        self.is_complete()
+    }
+    
+    /// Whether warm-up has finished and detections are being emitted
+    pub fn is_complete(&self) -> bool {
+        self.seen > self.required
+    }
+}
+
+/// Detection cadence: detectors run on every Nth sample only
+///
+/// At high telemetry rates running every detector on every sample is wasted
+/// work. Agents still record every sample into their histories, so rolling
+/// statistics see all of them; only detection is skipped in between.
+#[derive(Debug, Clone, Default)]
+pub struct Cadence {
+    seen: usize,
+}
+
+impl Cadence {
+    /// Count a sample; `true` if detection is due (the first, then every Nth)
+    ///
+    /// `every_n` is read on each call so a runtime reconfigure takes effect
+    /// immediately; 0 is treated as 1.
+    pub fn tick(&mut self, every_n: usize) -> bool {
+        let due = self.seen.is_multiple_of(every_n.max(1));
+        self.seen = self.seen.wrapping_add(1);
+        due
+    }
+}
+
+/// A detection stage: inspects agent state `S` and the current telemetry
+pub type DetectorStage<S> = Box<dyn Fn(&S, &Telemetry) -> Option<Threat> + Send + Sync>;
+
+/// Ordered list of detectors an agent runs on every telemetry message
+///
+/// Agents keep their rolling state (histories, baselines) and compose their
+/// detectors here, so adding a detector doesn't mean editing `analyze`.
+pub struct DetectorPipeline<S> {
+    stages: Vec<(&'static str, DetectorStage<S>)>,
+}
+
+impl<S> DetectorPipeline<S> {
+    pub fn new() -> Self {
+        Self { stages: Vec::new() }
+    }
+    
+    /// Append a named detector; stages run in insertion order
+    pub fn with_stage<F>(mut self, name: &'static str, stage: F) -> Self
+    where
+        F: Fn(&S, &Telemetry) -> Option<Threat> + Send + Sync + 'static,
+    {
+        self.stages.push((name, Box::new(stage)));
+        self
+    }
+    
+    /// Run every stage and collect the threats they raise
+    pub fn run(&self, state: &S, telemetry: &Telemetry) -> Vec<Threat> {
+        self.stages
+            .iter()
+            .filter_map(|(name, stage)| {
+                let threat = stage(state, telemetry)?;
+                tracing::debug!("{} detector raised {}", name, threat.threat_type());
+                Some(threat)
+            })
+            .collect()
+    }
+}
+
+/// Least-squares line through equally spaced samples
+///
+/// Returns `(slope, intercept)` with x = 0, 1, 2, ... or `None` for fewer
+/// than two samples or any non-finite sample.
+pub fn linear_fit(samples: &[f64]) -> Option<(f64, f64)> {
+    let n = samples.len();
+    if n < 2 || samples.iter().any(|y| !y.is_finite()) {
+        return None;
+    }
+    
+    let x_mean = (n - 1) as f64 / 2.0;
+    let y_mean = samples.iter().sum::<f64>() / n as f64;
+    let (sxy, sxx) = samples.iter().enumerate().fold((0.0, 0.0), |(sxy, sxx), (x, y)| {
+        let dx = x as f64 - x_mean;
+        (sxy + dx * (y - y_mean), sxx + dx * dx)
+    });
+    
+    let slope = sxy / sxx;
+    Some((slope, y_mean - slope * x_mean))
+}
+
+/// One-sided magnitude spectrum of a real signal (radix-2 FFT)
+///
+/// `samples.len()` must be a power of two; returns `len / 2 + 1` bins from DC
+/// to Nyquist, or `None` for an empty or non-power-of-two input.
+pub fn magnitude_spectrum(samples: &[f64]) -> Option<Vec<f64>> {
+    let n = samples.len();
+    if n == 0 || !n.is_power_of_two() {
+        return None;
+    }
+
+    let mut re: Vec<f64> = samples.to_vec();
+    let mut im = vec![0.0; n];
+
+    // Bit-reversal permutation
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = if bits == 0 { 0 } else { i.reverse_bits() >> (usize::BITS - bits) };
+        if j > i {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    // Iterative Cooley-Tukey butterflies
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f64::consts::PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (w_re, w_im) = ((angle * k as f64).cos(), (angle * k as f64).sin());
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+
+    Some((0..=n / 2).map(|k| (re[k] * re[k] + im[k] * im[k]).sqrt()).collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,17 +329,58 @@ mod tests {
         // Extreme value should be anomaly
         assert!(analyzer.is_anomaly(100.0, 3.0));
     }
+
+    #[test]
+    fn test_non_finite_values_skipped() {
+        let data: VecDeque<f64> = [1.0, f64::NAN, 3.0, f64::INFINITY, 2.0].into_iter().collect();
+        let stats = Statistics::from_data(&data);
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.mean, 2.0);
+        assert_eq!((stats.min, stats.max), (1.0, 3.0));
+
+        let mut analyzer = StatisticalAnalyzer::new(10);
+        for value in [10.0, 10.2, f64::NAN, 9.8, 10.1] {
+            analyzer.update(value);
+        }
+        assert!(analyzer.z_score(10.0).unwrap().is_finite());
+        assert_eq!(analyzer.z_score(f64::NAN), None);
+        assert!(!analyzer.is_anomaly(f64::NAN, 3.0));
+
+        let mut roc = RateOfChangeDetector::new();
+        roc.update(10.0);
+        assert_eq!(roc.update(f64::NAN), None);
+        assert_eq!(linear_fit(&[1.0, f64::NAN, 3.0]), None);
+    }
     
+    #[test]
+    fn test_magnitude_spectrum_peak() {
+        // 8 cycles over 64 samples -> energy in bin 8
+        let samples: Vec<f64> = (0..64)
+            .map(|i| (2.0 * std::f64::consts::PI * 8.0 * i as f64 / 64.0).sin())
+            .collect();
+        let spectrum = magnitude_spectrum(&samples).unwrap();
+
+        assert_eq!(spectrum.len(), 33);
+        let peak = spectrum.iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap()
+            .0;
+        assert_eq!(peak, 8);
+        assert!(magnitude_spectrum(&samples[..60]).is_none());
+    }
+
     #[test]
     fn test_rate_of_change() {
         let mut detector = RateOfChangeDetector::new();
+        let start = "2026-02-07T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
         
-        detector.update(10.0);
-        std::thread::sleep(std::time::Duration::from_millis(100));
-        let roc = detector.update(11.0);
+        assert_eq!(detector.update_at(10.0, start), None);
+        // 1 degree over 0.1 second is 600 per minute
+        let roc = detector.update_at(11.0, start + chrono::Duration::milliseconds(100));
+        assert!((roc.unwrap() - 600.0).abs() < 1e-9);
         
-        assert!(roc.is_some());
-        // ROC should be approximately 600 per minute (1 degree per 0.1 second)
-        assert!(roc.unwrap() > 500.0);
+        // A reading stamped no later than the last one has no rate
+        assert_eq!(detector.update_at(12.0, start), None);
     }
 }
@@ -0,0 +1,152 @@
+//! Telemetry enrichment - derived features computed once per message
+//!
+//! Several detectors need the same physics quantities (filter impedance,
+//! thermal expansion, rates of change). They are computed here, after unit
+//! normalization and before the agents run, so each formula lives in one
+//! place and is evaluated once per telemetry message.
+
+use std::collections::HashMap;
+use std::ops::Deref;
+
+use crate::detection::RateOfChangeDetector;
+use crate::types::Telemetry;
+
+/// Flow impedance P/Q (Pa per m/s)
+///
+/// Normalizing pressure drop against airflow separates filter loading from a
+/// change in fan speed. Zero without airflow.
+pub fn flow_impedance(pressure_drop_pa: f64, airflow_mps: f64) -> f64 {
+    if airflow_mps > 0.0 {
+        pressure_drop_pa / airflow_mps
+    } else {
+        0.0
+    }
+}
+
+/// Linear thermal expansion in mm: ΔL = α × L₀ × ΔT
+pub fn thermal_expansion_mm(cte: f64, length_mm: f64, delta_t: f64) -> f64 {
+    cte * length_mm * delta_t
+}
+
+/// Telemetry plus the features derived from it
+///
+/// Derefs to the raw `Telemetry`, so detectors read metrics as before.
+#[derive(Debug, Clone)]
+pub struct EnrichedTelemetry {
+    pub telemetry: Telemetry,
+    /// `pressure_diff_pa` over `airflow_mps`; `None` unless both were reported
+    pub filter_impedance: Option<f64>,
+    /// Change per minute of each metric since the machine's previous message
+    pub rates_per_min: HashMap<String, f64>,
+}
+
+impl EnrichedTelemetry {
+    /// Features that need no history (no rates of change)
+    pub fn from_telemetry(telemetry: &Telemetry) -> Self {
+        let filter_impedance = telemetry
+            .metric("pressure_diff_pa")
+            .zip(telemetry.metric("airflow_mps"))
+            .map(|(pressure, airflow)| flow_impedance(pressure, airflow));
+        Self {
+            telemetry: telemetry.clone(),
+            filter_impedance,
+            rates_per_min: HashMap::new(),
+        }
+    }
+
+    /// Rate of change of a metric (per minute), once two readings have arrived
+    pub fn rate_per_min(&self, metric: &str) -> Option<f64> {
+        self.rates_per_min.get(metric).copied()
+    }
+}
+
+impl Deref for EnrichedTelemetry {
+    type Target = Telemetry;
+
+    fn deref(&self) -> &Telemetry {
+        &self.telemetry
+    }
+}
+
+/// Enrichment stage; keeps the previous reading of every machine metric
+///
+/// Rates are timed by the telemetry's own timestamp, so a queueing delay
+/// between sensor and Sentinel doesn't distort them.
+#[derive(Default)]
+pub struct TelemetryEnricher {
+    rates: HashMap<(String, String), RateOfChangeDetector>,
+}
+
+impl TelemetryEnricher {
+    /// Attach derived features to a telemetry message
+    pub fn enrich(&mut self, telemetry: &Telemetry) -> EnrichedTelemetry {
+        let mut enriched = EnrichedTelemetry::from_telemetry(telemetry);
+        let observed_at = telemetry.observed_at();
+        for (metric, value) in &telemetry.metrics {
+            let key = (telemetry.machine_id.clone(), metric.clone());
+            if let Some(rate) = self.rates.entry(key).or_default().update_at(*value, observed_at) {
+                enriched.rates_per_min.insert(metric.clone(), rate);
+            }
+        }
+        enriched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::facility::{FacilityConfig, FacilitySentinel};
+    use crate::agents::SentinelAgent;
+
+    fn facility_telemetry(pressure: f64, airflow: f64) -> Telemetry {
+        Telemetry {
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            machine_id: "FAC-001".to_string(),
+            metrics: HashMap::from([
+                ("pressure_diff_pa".to_string(), pressure),
+                ("airflow_mps".to_string(), airflow),
+                ("duct_temp".to_string(), 45.0),
+            ]),
+            states: HashMap::new(),
+            units: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_enriched_features_match_detector_physics() {
+        let mut enricher = TelemetryEnricher::default();
+        let first = enricher.enrich(&facility_telemetry(120.0, 0.4));
+        assert_eq!(first.filter_impedance, Some(120.0 / 0.4));
+        assert_eq!(first.rate_per_min("pressure_diff_pa"), None);
+
+        // +30 Pa over the 30 s between readings
+        let mut later = facility_telemetry(150.0, 0.4);
+        later.timestamp = "2024-01-01T00:00:30Z".to_string();
+        let second = enricher.enrich(&later);
+        assert_eq!(second.rate_per_min("pressure_diff_pa"), Some(60.0));
+
+        // No airflow reading: no impedance rather than a made-up one
+        let mut telemetry = facility_telemetry(120.0, 0.4);
+        telemetry.metrics.remove("airflow_mps");
+        assert_eq!(EnrichedTelemetry::from_telemetry(&telemetry).filter_impedance, None);
+        assert_eq!(flow_impedance(120.0, 0.0), 0.0);
+
+        // Capillary numbers from the assembly agent: 10mm at 5.5e-6/°C over 25°C
+        assert!((thermal_expansion_mm(5.5e-6, 10.0, 25.0) - 1.375e-3).abs() < 1e-12);
+
+        // The agent sees the same threats through either entry point
+        let config = FacilityConfig { warmup_samples: 0, ..FacilityConfig::default() };
+        let raw = FacilitySentinel::new("FAC-001".to_string(), config.clone());
+        let enriched = FacilitySentinel::new("FAC-001".to_string(), config);
+        for (pressure, airflow) in [(100.0, 0.45), (100.0, 0.45), (260.0, 0.3)] {
+            let telemetry = facility_telemetry(pressure, airflow);
+            let expected: Vec<String> = raw.analyze(&telemetry).iter().map(|t| format!("{:?}", t)).collect();
+            let actual: Vec<String> = enriched
+                .analyze_enriched(&enricher.enrich(&telemetry))
+                .iter()
+                .map(|t| format!("{:?}", t))
+                .collect();
+            assert_eq!(actual, expected);
+        }
+    }
+}
@@ -11,20 +11,35 @@ use tracing::{info, warn, error};
 mod agents;
 mod api_bridge;
 mod detection;
+mod enrichment;
 mod mqtt;
+mod orchestrator;
 mod safety;
 mod types;
+mod units;
+mod vectors;
 
 use agents::precision::PrecisionSentinel;
 use agents::facility::FacilitySentinel;
 use agents::assembly::{AssemblySentinel, AssemblyConfig};
 use agents::SentinelAgent;
 use api_bridge::{report_threat, YieldOpsClient};
-use mqtt::MqttClient;
+use mqtt::{Incoming, MqttClient, MqttSession, ACKNOWLEDGE_TOPICS, CONFIG_TOPIC_PREFIX, SECS_ACK_TOPIC};
+use orchestrator::Orchestrator;
 use types::*;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // Golden-file regression run for CI: `aegis-sentinel --run-vectors <file>`
+    let args: Vec<String> = std::env::args().collect();
+    if let [_, flag, path] = args.as_slice() {
+        if flag == "--run-vectors" {
+            let checked = vectors::run_vectors(std::path::Path::new(path))?;
+            println!("{} detection vectors passed", checked);
+            return Ok(());
+        }
+    }
+
     // Initialize logging
     tracing_subscriber::fmt()
         .with_env_filter("aegis_sentinel=info")
@@ -44,7 +59,7 @@ async fn main() -> anyhow::Result<()> {
     info!("Configuration loaded successfully");
 
     // Initialize YieldOps API client (optional, for direct HTTP reporting)
-    let yieldops_client = YieldOpsClient::from_env();
+    let yieldops_client = Arc::new(YieldOpsClient::from_env());
     if yieldops_client.is_some() {
         info!("✓ YieldOps API integration enabled");
     } else {
@@ -94,20 +109,25 @@ async fn main() -> anyhow::Result<()> {
     if agents.is_empty() {
         warn!("No agents configured - running in monitoring mode only");
     }
+    for (machine_id, threat_type) in &config.suppressions {
+        info!("Suppressing {} on {}", threat_type, machine_id);
+    }
+    let orchestrator = Arc::new(Orchestrator::new(agents).with_suppressions(config.suppressions.clone()));
 
     // Initialize MQTT client
     let broker = std::env::var("MQTT_BROKER").unwrap_or_else(|_| "localhost".to_string());
     info!("Connecting to MQTT broker at {}...", broker);
     
-    let mut mqtt_client = MqttClient::new(&broker).await?;
+    let session = MqttSession::from_env("aegis-sentinel");
+    let mut mqtt_client = MqttClient::new(&broker, &session).await?;
     info!("Connected to MQTT broker");
 
     // Register agents with YieldOps API
-    if let Some(ref api) = yieldops_client {
+    if let Some(ref api) = *yieldops_client {
         for agent_config in &config.agents {
             let agent_type = agent_config.agent_type.clone();
             let machine_id = agent_config.machine_id.clone();
-            let agent_id = format!("agent-{}-{}", agent_type, machine_id.to_lowercase());
+            let agent_id = agent_id(&agent_type, &machine_id);
             
             let capabilities = match agent_type.as_str() {
                 "precision" => vec![
@@ -140,35 +160,105 @@ async fn main() -> anyhow::Result<()> {
     // Subscribe to telemetry topics
     mqtt_client.subscribe("factory/+/telemetry").await?;
     info!("Subscribed to factory/+/telemetry");
+    let config_topic = format!("{}+", CONFIG_TOPIC_PREFIX);
+    mqtt_client.subscribe(&config_topic).await?;
+    info!("Subscribed to {} (threshold hot-reload)", config_topic);
+    for topic in ACKNOWLEDGE_TOPICS {
+        mqtt_client.subscribe(topic).await?;
+    }
+    info!("Subscribed to {} (incident acknowledgement)", ACKNOWLEDGE_TOPICS.join(", "));
+    mqtt_client.subscribe(SECS_ACK_TOPIC).await?;
+    info!("Subscribed to {} (S2F42 command acknowledgement)", SECS_ACK_TOPIC);
 
     // Wrap MQTT client in Arc<tokio::sync::Mutex> for shared access
     // Using Mutex instead of RwLock because MqttClient's internals are not Sync
     let mqtt_client = Arc::new(tokio::sync::Mutex::new(mqtt_client));
 
-    // Create channel for telemetry
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<Telemetry>(100);
+    // Create channel for telemetry and config updates
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<Incoming>(100);
 
     // Spawn MQTT receiver task
     let mqtt_client_clone = Arc::clone(&mqtt_client);
     tokio::spawn(async move {
         loop {
-            if let Some(telemetry) = mqtt_client_clone.lock().await.receive_telemetry().await {
-                if tx.send(telemetry).await.is_err() {
+            if let Some(incoming) = mqtt_client_clone.lock().await.receive().await {
+                if tx.send(incoming).await.is_err() {
                     break;
                 }
             }
         }
     });
 
+    // Analysis workers: each machine's telemetry always goes to the same one
+    let workers = spawn_analysis_workers(
+        config.analysis_workers,
+        &orchestrator,
+        &mqtt_client,
+        &yieldops_client,
+    );
+    info!("Analyzing telemetry on {} worker(s)", workers.len());
+
     // Main event loop
     info!("Aegis Sentinel is running - Press Ctrl+C to stop");
     info!("Full Value Chain Coverage: Precision → Facility → Assembly");
     
+    let mut snapshot_interval = tokio::time::interval(std::time::Duration::from_secs(30));
+    let agent_ids: std::collections::HashMap<String, String> = config.agents
+        .iter()
+        .map(|a| (a.machine_id.clone(), agent_id(&a.agent_type, &a.machine_id)))
+        .collect();
+    
     loop {
         tokio::select! {
-            // Handle incoming telemetry
-            Some(telemetry) = rx.recv() => {
-                handle_telemetry(&agents, &mqtt_client, &yieldops_client, telemetry).await?;
+            // Handle incoming telemetry and config updates
+            Some(incoming) = rx.recv() => match incoming {
+                Incoming::Telemetry(telemetry) => {
+                    let worker = &workers[worker_index(&telemetry.machine_id, workers.len())];
+                    if worker.send(telemetry).await.is_err() {
+                        anyhow::bail!("Telemetry analysis worker stopped");
+                    }
+                }
+                // Hot-reload thresholds pushed from the dashboard
+                Incoming::ConfigUpdate { machine_id, patch } => {
+                    match orchestrator.reconfigure(&machine_id, &patch).await {
+                        Ok(()) => info!("Thresholds updated for {}", machine_id),
+                        Err(e) => warn!("Rejected config update for {}: {}", machine_id, e),
+                    }
+                }
+                // Operator resolved an incident: start the machine's counters fresh
+                Incoming::Acknowledge { machine_id } => {
+                    match orchestrator.acknowledge(&machine_id).await {
+                        Ok(()) => info!("Incident state cleared for {}", machine_id),
+                        Err(e) => warn!("Ignored acknowledgement for {}: {}", machine_id, e),
+                    }
+                }
+                // Equipment accepted or rejected a command relayed by the SECS/GEM bridge
+                Incoming::CommandAck(ack) => {
+                    match orchestrator.command_ack(&ack).await {
+                        Ok(()) => info!("{} performed {}", ack.machine_id, ack.cmd),
+                        Err(e) => warn!("Command not performed: {}", e),
+                    }
+                }
+            },
+            
+            // Publish active threats for the status page
+            _ = snapshot_interval.tick() => {
+                let snapshot = orchestrator.snapshot();
+                if let Err(e) = mqtt_client.lock().await.publish_threat_snapshot(&snapshot).await {
+                    warn!("Failed to publish threat snapshot: {}", e);
+                }
+                
+                // Report agent health to the dashboard
+                if let Some(ref api) = *yieldops_client {
+                    for heartbeat in orchestrator.heartbeats().await {
+                        let Some(agent_id) = agent_ids.get(&heartbeat.machine_id) else {
+                            continue;
+                        };
+                        if let Err(e) = api.heartbeat(agent_id, &heartbeat).await {
+                            warn!("Failed to send heartbeat for {}: {}", agent_id, e);
+                        }
+                    }
+                }
             }
             
             // Handle shutdown signal
@@ -183,56 +273,104 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Agent ID used when registering with YieldOps
+fn agent_id(agent_type: &str, machine_id: &str) -> String {
+    format!("agent-{}-{}", agent_type, machine_id.to_lowercase())
+}
+
+/// Start `count` (at least one) telemetry analysis tasks sharing the orchestrator
+fn spawn_analysis_workers(
+    count: usize,
+    orchestrator: &Arc<Orchestrator>,
+    mqtt_client: &Arc<tokio::sync::Mutex<MqttClient>>,
+    yieldops_client: &Arc<Option<YieldOpsClient>>,
+) -> Vec<tokio::sync::mpsc::Sender<Telemetry>> {
+    (0..count.max(1))
+        .map(|_| {
+            let (tx, mut rx) = tokio::sync::mpsc::channel::<Telemetry>(100);
+            let orchestrator = Arc::clone(orchestrator);
+            let mqtt_client = Arc::clone(mqtt_client);
+            let yieldops_client = Arc::clone(yieldops_client);
+            tokio::spawn(async move {
+                while let Some(telemetry) = rx.recv().await {
+                    let machine_id = telemetry.machine_id.clone();
+                    if let Err(e) =
+                        handle_telemetry(&orchestrator, &mqtt_client, &yieldops_client, telemetry).await
+                    {
+                        error!("Failed to handle telemetry from {}: {}", machine_id, e);
+                    }
+                }
+            });
+            tx
+        })
+        .collect()
+}
+
+/// Worker a machine's telemetry is pinned to
+fn worker_index(machine_id: &str, workers: usize) -> usize {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    machine_id.hash(&mut hasher);
+    (hasher.finish() % workers.max(1) as u64) as usize
+}
+
 async fn handle_telemetry(
-    agents: &[Arc<RwLock<dyn SentinelAgent>>],
+    orchestrator: &Orchestrator,
     mqtt_client: &Arc<tokio::sync::Mutex<MqttClient>>,
     yieldops_client: &Option<YieldOpsClient>,
-    telemetry: Telemetry,
+    mut telemetry: Telemetry,
 ) -> anyhow::Result<()> {
-    for agent in agents {
-        let mut agent_guard = agent.write().await;
-        if agent_guard.can_handle(&telemetry.machine_id) {
-            // Analyze telemetry for threats
-            let threats = agent_guard.analyze(&telemetry);
-            
-            for threat in threats {
-                let (tier, action) = agent_guard.safety_circuit(&threat);
-                
-                log_threat(&threat, &tier, &action);
-                
-                // Report threat to YieldOps API
-                report_threat(yieldops_client, &threat, &action, &tier).await;
+    // Reconcile declared units with what the detectors assume
+    for issue in units::normalize_units(&mut telemetry) {
+        match issue {
+            units::UnitIssue::Converted { metric, from, to } => {
+                warn!("{}: converted {} from {} to {}", telemetry.machine_id, metric, from, to);
+            }
+            units::UnitIssue::Mismatch { metric, unit, expected } => {
+                warn!(
+                    "{}: {} reported in {} but detectors expect {} - thresholds may be wrong",
+                    telemetry.machine_id, metric, unit, expected
+                );
+            }
+        }
+    }
 
-                match tier {
-                    ResponseTier::Green => {
-                        // Auto-execute
-                        if let Err(e) = agent_guard.execute(&action).await {
-                            error!("Failed to execute action: {}", e);
-                        } else {
-                            // Publish command to machine
-                            let command = action_to_command(&action);
-                            mqtt_client.lock().await.publish_command(
-                                &telemetry.machine_id,
-                                &command,
-                            ).await?;
-                        }
-                    }
-                    ResponseTier::Yellow => {
-                        // Queue for approval (in production, wait for dashboard)
-                        warn!("YELLOW ZONE: Action '{}' queued for approval", action.name());
-                        // Publish for dashboard visibility
-                        let incident = Incident::from_threat(&threat, &action, "pending_approval");
-                        mqtt_client.lock().await.publish_incident(&incident).await?;
-                    }
-                    ResponseTier::Red => {
-                        // Alert only - no autonomous action
-                        error!("RED ZONE: Human intervention required for {:?}", threat);
-                        // Publish incident for dashboard
-                        let incident = Incident::from_threat(&threat, &action, "alert_only");
-                        mqtt_client.lock().await.publish_incident(&incident).await?;
-                    }
+    for decision in orchestrator.process(&telemetry).await {
+        let (threat, tier, action) = (&decision.threat, decision.tier, &decision.action);
+        
+        log_threat(threat, &tier, action);
+        
+        // Report threat to YieldOps API
+        report_threat(yieldops_client, threat, action, &tier).await;
+
+        match tier {
+            ResponseTier::Green => {
+                // Auto-execute
+                if let Err(e) = orchestrator.execute(&decision).await {
+                    error!("Failed to execute action: {}", e);
+                } else {
+                    // Publish command to machine
+                    let command = action_to_command(action);
+                    mqtt_client.lock().await.publish_command(
+                        &telemetry.machine_id,
+                        &command,
+                    ).await?;
                 }
             }
+            ResponseTier::Yellow => {
+                // Queue for approval (in production, wait for dashboard)
+                warn!("YELLOW ZONE: Action '{}' queued for approval", action.name());
+                // Publish for dashboard visibility
+                let incident = Incident::from_threat(threat, action, "pending_approval");
+                mqtt_client.lock().await.publish_incident(&incident).await?;
+            }
+            ResponseTier::Red => {
+                // Alert only - no autonomous action
+                error!("RED ZONE: Human intervention required for {:?}", threat);
+                // Publish incident for dashboard
+                let incident = Incident::from_threat(threat, action, "alert_only");
+                mqtt_client.lock().await.publish_incident(&incident).await?;
+            }
         }
     }
     
@@ -339,5 +477,7 @@ fn load_config() -> anyhow::Result<AegisConfig> {
                 config: serde_yaml::Value::Null,
             },
         ],
+        suppressions: Vec::new(),
+        analysis_workers: 4,
     })
 }
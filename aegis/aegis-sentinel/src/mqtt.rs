@@ -1,27 +1,92 @@
 //! MQTT client for Aegis Sentinel
 
-use rumqttc::{AsyncClient, EventLoop, MqttOptions, Packet, Publish, QoS};
+use rumqttc::{AsyncClient, EventLoop, Packet, Publish, QoS};
 use serde_json;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
+pub use aegis_common::mqtt::MqttSession;
+
+use crate::orchestrator::ThreatSnapshot;
 use crate::types::*;
 
+/// Prefix of the per-machine threshold update topics
+pub const CONFIG_TOPIC_PREFIX: &str = "aegis/config/";
+
+/// Per-machine incident acknowledgement topics (`aegis/{machine_id}/...`),
+/// published when an operator resolves an incident
+pub const ACKNOWLEDGE_TOPICS: [&str; 2] = ["aegis/+/acknowledge", "aegis/+/resolve"];
+
+/// Topic the SECS/GEM bridge (`gem_adapter.py`) publishes S2F42 acknowledgments on
+pub const SECS_ACK_TOPIC: &str = "sentinel/bridge/ack";
+
+/// Message received from the broker
+#[derive(Debug, Clone)]
+pub enum Incoming {
+    Telemetry(Telemetry),
+    /// Partial agent config from `aegis/config/{machine_id}`
+    ConfigUpdate {
+        machine_id: String,
+        patch: serde_json::Value,
+    },
+    /// Incident resolved from `aegis/{machine_id}/acknowledge` (or `/resolve`)
+    Acknowledge { machine_id: String },
+    /// Equipment reply to a relayed command, from `SECS_ACK_TOPIC`
+    CommandAck(SecsCommandAck),
+}
+
+impl Incoming {
+    fn parse(publish: &Publish) -> Option<Self> {
+        if let Some(machine_id) = acknowledged_machine(&publish.topic) {
+            return Some(Incoming::Acknowledge {
+                machine_id: machine_id.to_string(),
+            });
+        }
+        let payload = String::from_utf8_lossy(&publish.payload);
+        let parsed = match publish.topic.strip_prefix(CONFIG_TOPIC_PREFIX) {
+            Some(machine_id) => serde_json::from_str(&payload).map(|patch| Incoming::ConfigUpdate {
+                machine_id: machine_id.to_string(),
+                patch,
+            }),
+            None if publish.topic == SECS_ACK_TOPIC => {
+                serde_json::from_str(&payload).map(Incoming::CommandAck)
+            }
+            None => serde_json::from_str(&payload).map(Incoming::Telemetry),
+        };
+        match parsed {
+            Ok(incoming) => Some(incoming),
+            Err(e) => {
+                warn!("Ignoring malformed message on {}: {}", publish.topic, e);
+                None
+            }
+        }
+    }
+}
+
+/// Machine id of an acknowledgement topic; the payload is not needed
+fn acknowledged_machine(topic: &str) -> Option<&str> {
+    let rest = topic.strip_prefix("aegis/")?;
+    let machine_id = rest
+        .strip_suffix("/acknowledge")
+        .or_else(|| rest.strip_suffix("/resolve"))?;
+    (!machine_id.is_empty() && !machine_id.contains('/')).then_some(machine_id)
+}
+
 pub struct MqttClient {
     client: AsyncClient,
     eventloop: EventLoop,
 }
 
 impl MqttClient {
-    pub async fn new(broker: &str) -> anyhow::Result<Self> {
-        let mut mqttoptions = MqttOptions::new(
-            "aegis-sentinel",
-            broker,
-            1883,
-        );
-        mqttoptions.set_keep_alive(std::time::Duration::from_secs(5));
+    pub async fn new(broker: &str, session: &MqttSession) -> anyhow::Result<Self> {
+        let mqttoptions = session.options(broker, 1883);
         
         let (client, eventloop) = AsyncClient::new(mqttoptions, 10);
         
+        info!(
+            "MQTT client id {} ({} session)",
+            session.client_id,
+            if session.clean_session { "clean" } else { "persistent" }
+        );
         Ok(Self { client, eventloop })
     }
     
@@ -30,13 +95,14 @@ impl MqttClient {
         Ok(())
     }
     
-    pub async fn receive_telemetry(&mut self) -> Option<Telemetry> {
+    /// Wait for the next telemetry reading or config update
+    pub async fn receive(&mut self) -> Option<Incoming> {
         loop {
             match self.eventloop.poll().await {
                 Ok(notification) => {
                     if let rumqttc::Event::Incoming(Packet::Publish(publish)) = notification {
-                        if let Ok(telemetry) = Self::parse_telemetry(&publish) {
-                            return Some(telemetry);
+                        if let Some(incoming) = Incoming::parse(&publish) {
+                            return Some(incoming);
                         }
                     }
                 }
@@ -48,12 +114,6 @@ impl MqttClient {
         }
     }
     
-    fn parse_telemetry(publish: &Publish) -> anyhow::Result<Telemetry> {
-        let payload = String::from_utf8_lossy(&publish.payload);
-        let telemetry: Telemetry = serde_json::from_str(&payload)?;
-        Ok(telemetry)
-    }
-    
     pub async fn publish_command(&mut self, machine_id: &str, command: &Command) -> anyhow::Result<()> {
         let topic = format!("factory/{}/command", machine_id);
         let payload = serde_json::to_string(command)?;
@@ -69,4 +129,68 @@ impl MqttClient {
         info!("Published incident: {:?}", incident);
         Ok(())
     }
+    
+    /// Publish the active-threat snapshot (retained, so a status page gets it on connect)
+    pub async fn publish_threat_snapshot(&mut self, snapshot: &[ThreatSnapshot]) -> anyhow::Result<()> {
+        let topic = "aegis/threats";
+        let payload = serde_json::to_string(snapshot)?;
+        self.client.publish(topic, QoS::AtLeastOnce, true, payload).await?;
+        debug!("Published threat snapshot ({} active)", snapshot.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_topic_parsed_as_update() {
+        let publish = Publish::new(
+            "aegis/config/CNC-001",
+            QoS::AtLeastOnce,
+            r#"{"vibration_critical": 9.0}"#,
+        );
+        match Incoming::parse(&publish) {
+            Some(Incoming::ConfigUpdate { machine_id, patch }) => {
+                assert_eq!(machine_id, "CNC-001");
+                assert_eq!(patch["vibration_critical"], 9.0);
+            }
+            other => panic!("expected config update, got {:?}", other),
+        }
+
+        let publish = Publish::new("factory/CNC-001/telemetry", QoS::AtLeastOnce, "not json");
+        assert!(Incoming::parse(&publish).is_none());
+    }
+
+    #[test]
+    fn test_secs_ack_topic_parsed_as_command_ack() {
+        let publish = Publish::new(
+            SECS_ACK_TOPIC,
+            QoS::AtLeastOnce,
+            r#"{"cmd": "BOND_FORCE_ADJ", "status": "FAILED", "machine_id": "BOND-01",
+                "timestamp": "2026-02-08T10:00:00", "secs_stream": 2, "secs_function": 42, "hcack": 2}"#,
+        );
+        match Incoming::parse(&publish) {
+            Some(Incoming::CommandAck(ack)) => {
+                assert_eq!(ack.machine_id, "BOND-01");
+                assert_eq!(ack.cmd, "BOND_FORCE_ADJ");
+                assert_eq!(ack.hcack, 2);
+            }
+            other => panic!("expected command ack, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_acknowledge_topic_parsed_without_payload() {
+        for topic in ["aegis/BOND-01/acknowledge", "aegis/BOND-01/resolve"] {
+            let publish = Publish::new(topic, QoS::AtLeastOnce, "");
+            match Incoming::parse(&publish) {
+                Some(Incoming::Acknowledge { machine_id }) => assert_eq!(machine_id, "BOND-01"),
+                other => panic!("expected acknowledge, got {:?}", other),
+            }
+        }
+        assert_eq!(acknowledged_machine("aegis/incidents"), None);
+        assert_eq!(acknowledged_machine("factory/BOND-01/acknowledge"), None);
+    }
 }
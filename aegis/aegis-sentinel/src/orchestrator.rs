@@ -0,0 +1,756 @@
+//! Orchestrator - routes telemetry through the agents
+//!
+//! Runs every agent that handles a machine, applies its Safety Circuit to
+//! each detected threat, and keeps track of which threats are still active
+//! so the current state can be pulled as a single snapshot.
+//!
+//! Threat types can be suppressed per machine (e.g. during a planned
+//! experiment). Suppressed threats are never reported or acted on; each
+//! agent's heartbeat says how many it dropped.
+//!
+//! When several threats on one machine call for machine commands in the same
+//! cycle, only the most conservative command is kept (an emergency stop
+//! beats a feed hold, which beats a speed reduction or parameter tweak).
+//! The other threats are still reported, with their action reduced to
+//! `LogOnly`; alerts and work orders are never changed.
+//!
+//! Agent thresholds can be changed at runtime (`aegis/config/{machine_id}`)
+//! without restarting; detection history is kept across the change.
+//! Acknowledging a machine (`aegis/{machine_id}/acknowledge`) clears its
+//! agents' incident counters and its active threats.
+//!
+//! All methods take `&self`, so one orchestrator can be shared (`Arc`) by
+//! several analysis tasks. Analysis only read-locks an agent; the agent keeps
+//! each machine's rolling state behind its own mutex, and the shared
+//! bookkeeping sits behind short-lived mutexes, so telemetry for different
+//! machines is analyzed concurrently, even by the same agent.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use crate::agents::SentinelAgent;
+use crate::enrichment::TelemetryEnricher;
+use crate::types::*;
+
+/// Threats not re-detected within this window are considered cleared
+const DEFAULT_THREAT_TTL_SECS: i64 = 300;
+
+/// Enricher shards; machines hash onto one so concurrent workers rarely
+/// wait on each other's enrichment
+const ENRICHER_SHARDS: usize = 16;
+
+/// A threat together with the Safety Circuit's response to it
+#[derive(Debug, Clone)]
+pub struct Decision {
+    pub threat: Threat,
+    pub tier: ResponseTier,
+    pub action: Action,
+    /// Index of the agent that produced the threat
+    agent: usize,
+}
+
+/// Serializable view of one active threat (for status pages)
+#[derive(Debug, Clone, Serialize)]
+pub struct ThreatSnapshot {
+    pub machine_id: String,
+    pub threat_type: String,
+    pub severity: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub age_secs: f64,
+}
+
+struct ActiveThreat {
+    threat: Threat,
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+}
+
+/// Per-agent activity counters for heartbeats
+#[derive(Debug, Clone, Default)]
+struct AgentActivity {
+    samples_processed: u64,
+    suppressed_threats: u64,
+    last_threat_at: Option<DateTime<Utc>>,
+}
+
+type ThreatKey = (String, &'static str);
+
+pub struct Orchestrator {
+    agents: Vec<Arc<RwLock<dyn SentinelAgent>>>,
+    /// Indexed like `agents`
+    activity: Vec<Mutex<AgentActivity>>,
+    active: Mutex<HashMap<ThreatKey, ActiveThreat>>,
+    threat_ttl: Duration,
+    /// (machine_id, threat_type) pairs muted by the operator
+    suppressions: Vec<(String, String)>,
+    /// Derive shared features once per message, before the agents run;
+    /// a machine always maps to the same shard
+    enrichers: Vec<Mutex<TelemetryEnricher>>,
+}
+
+impl Orchestrator {
+    pub fn new(agents: Vec<Arc<RwLock<dyn SentinelAgent>>>) -> Self {
+        Self {
+            activity: agents.iter().map(|_| Mutex::default()).collect(),
+            agents,
+            active: Mutex::default(),
+            threat_ttl: Duration::seconds(DEFAULT_THREAT_TTL_SECS),
+            suppressions: Vec::new(),
+            enrichers: (0..ENRICHER_SHARDS).map(|_| Mutex::default()).collect(),
+        }
+    }
+
+    /// Mute the given (machine_id, threat_type) pairs
+    pub fn with_suppressions(mut self, suppressions: Vec<(String, String)>) -> Self {
+        self.suppressions = suppressions;
+        self
+    }
+
+    /// Analyze telemetry with every agent that handles the machine
+    ///
+    /// Agents are only read-locked, so calls for different machines run at
+    /// the same time. Readings for one machine should still be fed in order
+    /// (agents keep rolling history).
+    pub async fn process(&self, telemetry: &Telemetry) -> Vec<Decision> {
+        let now = Utc::now();
+        let mut decisions = Vec::new();
+        let telemetry = self.enricher(&telemetry.machine_id).lock().unwrap().enrich(telemetry);
+
+        for (index, agent) in self.agents.iter().enumerate() {
+            let agent_guard = agent.read().await;
+            if !agent_guard.can_handle(&telemetry.machine_id) {
+                continue;
+            }
+
+            let (suppressed, threats): (Vec<Threat>, Vec<Threat>) = agent_guard
+                .analyze_enriched(&telemetry)
+                .into_iter()
+                .partition(|threat| self.is_suppressed(threat));
+            for threat in &suppressed {
+                debug!("Suppressed {} on {}", threat.threat_type(), threat.machine_id());
+            }
+            {
+                let mut activity = self.activity[index].lock().unwrap();
+                activity.samples_processed += 1;
+                activity.suppressed_threats += suppressed.len() as u64;
+                if !threats.is_empty() {
+                    activity.last_threat_at = Some(now);
+                }
+            }
+
+            for threat in threats {
+                let (tier, action) = agent_guard.safety_circuit(&threat);
+                decisions.push(Decision { threat, tier, action, agent: index });
+            }
+        }
+
+        let mut active = self.active.lock().unwrap();
+        for decision in &decisions {
+            let key = (
+                decision.threat.machine_id().to_string(),
+                decision.threat.threat_type(),
+            );
+            active
+                .entry(key)
+                .and_modify(|active| {
+                    active.threat = decision.threat.clone();
+                    active.last_seen = now;
+                })
+                .or_insert_with(|| ActiveThreat {
+                    threat: decision.threat.clone(),
+                    first_seen: now,
+                    last_seen: now,
+                });
+        }
+        let ttl = self.threat_ttl;
+        active.retain(|_, threat| now - threat.last_seen <= ttl);
+        drop(active);
+
+        resolve_conflicts(decisions)
+    }
+
+    /// Execute a decision's action on the agent that produced it
+    ///
+    /// Once it succeeds the agent is told, so stepped setpoints only advance
+    /// for commands that actually went out.
+    pub async fn execute(&self, decision: &Decision) -> Result<(), AgentError> {
+        let agent = &self.agents[decision.agent];
+        agent.read().await.execute(&decision.action).await?;
+        agent
+            .write()
+            .await
+            .action_executed(decision.threat.machine_id(), &decision.action);
+        Ok(())
+    }
+
+    /// Apply a partial config update to the agent configured for `machine_id`
+    pub async fn reconfigure(
+        &self,
+        machine_id: &str,
+        patch: &serde_json::Value,
+    ) -> Result<(), AgentError> {
+        for agent in &self.agents {
+            let mut agent_guard = agent.write().await;
+            if agent_guard.machine_id() == machine_id {
+                return agent_guard.reconfigure(patch);
+            }
+        }
+        Err(AgentError::ConfigError(format!("No agent configured for {}", machine_id)))
+    }
+
+    /// Reset incident state for `machine_id` after an operator resolved it
+    ///
+    /// Every agent handling the machine clears its counters, and the
+    /// machine's active threats are dropped from the snapshot.
+    pub async fn acknowledge(&self, machine_id: &str) -> Result<(), AgentError> {
+        let mut handled = false;
+        for agent in &self.agents {
+            let mut agent_guard = agent.write().await;
+            if agent_guard.can_handle(machine_id) {
+                agent_guard.acknowledge(machine_id);
+                handled = true;
+            }
+        }
+        if !handled {
+            return Err(AgentError::ConfigError(format!("No agent configured for {}", machine_id)));
+        }
+        self.active
+            .lock()
+            .unwrap()
+            .retain(|(machine, _), _| machine != machine_id);
+        Ok(())
+    }
+
+    /// Route an S2F42 acknowledgment to the agents handling its machine
+    ///
+    /// # Returns
+    /// `ExecutionError` if the equipment rejected the command, `ConfigError`
+    /// if no agent handles the machine.
+    pub async fn command_ack(&self, ack: &SecsCommandAck) -> Result<(), AgentError> {
+        let mut outcome = Err(AgentError::ConfigError(format!("No agent configured for {}", ack.machine_id)));
+        for agent in &self.agents {
+            let mut agent_guard = agent.write().await;
+            if agent_guard.can_handle(&ack.machine_id) {
+                let result = agent_guard.command_acknowledged(ack);
+                if outcome.is_err() || result.is_err() {
+                    outcome = result;
+                }
+            }
+        }
+        outcome
+    }
+
+    /// All currently active threats, most severe first
+    pub fn snapshot(&self) -> Vec<ThreatSnapshot> {
+        let now = Utc::now();
+        let active = self.active.lock().unwrap();
+        let mut active: Vec<&ActiveThreat> = active
+            .values()
+            .filter(|active| now - active.last_seen <= self.threat_ttl)
+            .collect();
+        active.sort_by(|a, b| {
+            severity_rank(b.threat.severity())
+                .cmp(&severity_rank(a.threat.severity()))
+                .then_with(|| a.first_seen.cmp(&b.first_seen))
+        });
+
+        active
+            .into_iter()
+            .map(|active| ThreatSnapshot {
+                machine_id: active.threat.machine_id().to_string(),
+                threat_type: active.threat.threat_type().to_string(),
+                severity: format!("{:?}", active.threat.severity()).to_lowercase(),
+                first_seen: active.first_seen,
+                last_seen: active.last_seen,
+                age_secs: (now - active.first_seen).num_milliseconds() as f64 / 1000.0,
+            })
+            .collect()
+    }
+
+    /// Health of every agent, in configuration order
+    pub async fn heartbeats(&self) -> Vec<AgentHeartbeat> {
+        let now = Utc::now();
+        let mut heartbeats = Vec::with_capacity(self.agents.len());
+        for (agent, activity) in self.agents.iter().zip(&self.activity) {
+            let agent_guard = agent.read().await;
+            let machine_id = agent_guard.machine_id().to_string();
+            let activity = activity.lock().unwrap().clone();
+            let worst = self
+                .active
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|active| {
+                    active.threat.machine_id() == machine_id
+                        && now - active.last_seen <= self.threat_ttl
+                })
+                .map(|active| active.threat.severity())
+                .max_by_key(|severity| severity_rank(*severity));
+
+            heartbeats.push(AgentHeartbeat {
+                machine_id,
+                samples_processed: activity.samples_processed,
+                suppressed_threats: activity.suppressed_threats,
+                baseline_ready: agent_guard.baseline_ready(),
+                last_threat_at: activity.last_threat_at,
+                health_score: health_score(worst),
+            });
+        }
+        heartbeats
+    }
+
+    fn enricher(&self, machine_id: &str) -> &Mutex<TelemetryEnricher> {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        machine_id.hash(&mut hasher);
+        &self.enrichers[(hasher.finish() % self.enrichers.len() as u64) as usize]
+    }
+
+    fn is_suppressed(&self, threat: &Threat) -> bool {
+        self.suppressions.iter().any(|(machine_id, threat_type)| {
+            machine_id == threat.machine_id()
+                && threat_type.eq_ignore_ascii_case(threat.threat_type())
+        })
+    }
+}
+
+/// Keep one machine command per machine: the most restrictive, then the
+/// most severe; the other commands on that machine become `LogOnly`, so
+/// their threats are still logged and published
+fn resolve_conflicts(decisions: Vec<Decision>) -> Vec<Decision> {
+    let mut winners: HashMap<String, usize> = HashMap::new();
+    for (index, decision) in decisions.iter().enumerate() {
+        let Some(rank) = decision.action.restrictiveness() else {
+            continue;
+        };
+        let key = (rank, severity_rank(decision.threat.severity()));
+        winners
+            .entry(decision.threat.machine_id().to_string())
+            .and_modify(|best| {
+                let current = &decisions[*best];
+                let best_key = (
+                    current.action.restrictiveness().unwrap_or_default(),
+                    severity_rank(current.threat.severity()),
+                );
+                if key > best_key {
+                    *best = index;
+                }
+            })
+            .or_insert(index);
+    }
+
+    let chosen: HashMap<usize, &'static str> = winners
+        .values()
+        .map(|&index| (index, decisions[index].action.name()))
+        .collect();
+    decisions
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut decision)| {
+            if decision.action.restrictiveness().is_none() || chosen.contains_key(&index) {
+                return decision;
+            }
+            warn!(
+                "{}: dropped {} for {} in favor of {}",
+                decision.threat.machine_id(),
+                decision.action.name(),
+                decision.threat.threat_type(),
+                chosen[&winners[decision.threat.machine_id()]]
+            );
+            decision.action = Action::LogOnly;
+            decision
+        })
+        .collect()
+}
+
+/// Agent health given the worst threat still active on its machine
+fn health_score(worst: Option<Severity>) -> f64 {
+    match worst {
+        None => 1.0,
+        Some(Severity::Low) => 0.9,
+        Some(Severity::Medium) => 0.7,
+        Some(Severity::High) => 0.4,
+        Some(Severity::Critical) => 0.1,
+    }
+}
+
+fn severity_rank(severity: Severity) -> u8 {
+    match severity {
+        Severity::Low => 0,
+        Severity::Medium => 1,
+        Severity::High => 2,
+        Severity::Critical => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::assembly::{AssemblyConfig, AssemblySentinel};
+    use crate::agents::facility::{FacilityConfig, FacilitySentinel};
+    use crate::agents::precision::{PrecisionConfig, PrecisionSentinel};
+
+    fn precision_agent(machine_id: &str) -> Arc<RwLock<dyn SentinelAgent>> {
+        Arc::new(RwLock::new(PrecisionSentinel::new(
+            machine_id.to_string(),
+            PrecisionConfig::default(),
+        )))
+    }
+
+    fn telemetry(machine_id: &str, temperature: f64) -> Telemetry {
+        let mut telemetry = Telemetry {
+            timestamp: "2026-02-07T00:00:00Z".to_string(),
+            machine_id: machine_id.to_string(),
+            metrics: HashMap::new(),
+            states: HashMap::new(),
+            units: HashMap::new(),
+        };
+        telemetry.metrics.insert("temperature".to_string(), temperature);
+        telemetry
+    }
+
+    #[tokio::test]
+    async fn test_suppressed_threat_not_reported() {
+        let orchestrator =
+            Orchestrator::new(vec![precision_agent("CNC-001"), precision_agent("CNC-002")])
+                .with_suppressions(vec![("CNC-001".to_string(), "ThermalDrift".to_string())]);
+
+        let is_drift = |d: &Decision| d.threat.threat_type() == "ThermalDrift";
+        let mut drift_cnc_001 = 0;
+        let mut drift_cnc_002 = 0;
+        for _ in 0..10 {
+            drift_cnc_001 += orchestrator
+                .process(&telemetry("CNC-001", 97.0))
+                .await
+                .iter()
+                .filter(|d| is_drift(d))
+                .count();
+            drift_cnc_002 += orchestrator
+                .process(&telemetry("CNC-002", 97.0))
+                .await
+                .iter()
+                .filter(|d| is_drift(d))
+                .count();
+        }
+
+        assert_eq!(drift_cnc_001, 0);
+        assert!(drift_cnc_002 > 0);
+        // Still counted, just not reported
+        let heartbeats = orchestrator.heartbeats().await;
+        assert_eq!(heartbeats[0].suppressed_threats as usize, drift_cnc_002);
+        assert_eq!(heartbeats[1].suppressed_threats, 0);
+        assert!(!orchestrator
+            .snapshot()
+            .iter()
+            .any(|t| t.machine_id == "CNC-001" && t.threat_type == "ThermalDrift"));
+    }
+
+    /// Raises a critical runaway (RED stop) and chatter (GREEN parameter
+    /// tweak) on every reading, and records what it is asked to execute
+    struct ConflictingAgent {
+        executed: Arc<std::sync::Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl SentinelAgent for ConflictingAgent {
+        fn analyze(&self, _telemetry: &Telemetry) -> Vec<Threat> {
+            vec![
+                Threat::Chatter {
+                    machine_id: "CNC-001".to_string(),
+                    frequency_hz: 0.0,
+                    amplitude_mm_s: 0.02,
+                    limit: 0.01,
+                    severity: Severity::Medium,
+                },
+                Threat::ThermalRunaway {
+                    machine_id: "CNC-001".to_string(),
+                    temperature: 105.0,
+                    rate_of_change: 0.0,
+                    limit: 80.0,
+                    severity: Severity::Critical,
+                },
+            ]
+        }
+
+        fn safety_circuit(&self, threat: &Threat) -> (ResponseTier, Action) {
+            match threat {
+                Threat::ThermalRunaway { .. } => (ResponseTier::Red, Action::EmergencyStop),
+                _ => (
+                    ResponseTier::Green,
+                    Action::AdjustParameter {
+                        parameter_name: "feed_rate".to_string(),
+                        new_value: 120.0,
+                        unit: "percent".to_string(),
+                    },
+                ),
+            }
+        }
+
+        async fn execute(&self, action: &Action) -> Result<(), AgentError> {
+            self.executed.lock().unwrap().push(action.name());
+            Ok(())
+        }
+
+        fn metadata(&self) -> AgentMetadata {
+            AgentMetadata {
+                name: "Conflicting".to_string(),
+                version: "0".to_string(),
+                target_equipment: Vec::new(),
+                capabilities: Vec::new(),
+            }
+        }
+
+        fn can_handle(&self, machine_id: &str) -> bool {
+            machine_id == "CNC-001"
+        }
+
+        fn machine_id(&self) -> &str {
+            "CNC-001"
+        }
+
+        fn reconfigure(&mut self, _patch: &serde_json::Value) -> Result<(), AgentError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_actions_resolved_to_most_conservative() {
+        let executed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let agent: Arc<RwLock<dyn SentinelAgent>> = Arc::new(RwLock::new(ConflictingAgent {
+            executed: Arc::clone(&executed),
+        }));
+        let orchestrator = Orchestrator::new(vec![agent]);
+
+        let decisions = orchestrator.process(&telemetry("CNC-001", 105.0)).await;
+        for decision in &decisions {
+            orchestrator.execute(decision).await.unwrap();
+        }
+
+        // The parameter tweak is never sent alongside the stop
+        assert_eq!(*executed.lock().unwrap(), vec!["LogOnly", "EmergencyStop"]);
+        assert_eq!(decisions[1].tier, ResponseTier::Red);
+        // Both threats are still tracked for the status page
+        assert_eq!(orchestrator.snapshot().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_losing_threat_still_reported() {
+        let agent: Arc<RwLock<dyn SentinelAgent>> = Arc::new(RwLock::new(ConflictingAgent {
+            executed: Arc::default(),
+        }));
+        let orchestrator = Orchestrator::new(vec![agent]);
+
+        let decisions = orchestrator.process(&telemetry("CNC-001", 105.0)).await;
+
+        assert_eq!(decisions.len(), 2);
+        let chatter = decisions
+            .iter()
+            .find(|d| d.threat.threat_type() == "Chatter")
+            .expect("losing threat should still be reported");
+        assert!(matches!(chatter.action, Action::LogOnly));
+        assert_eq!(chatter.tier, ResponseTier::Green);
+    }
+
+    #[tokio::test]
+    async fn test_reconfigure_applies_new_thresholds() {
+        let orchestrator = Orchestrator::new(vec![precision_agent("CNC-001")]);
+        let runaways = |decisions: Vec<Decision>| {
+            decisions
+                .iter()
+                .filter(|d| d.threat.threat_type() == "ThermalRunaway")
+                .count()
+        };
+
+        let mut before = 0;
+        for _ in 0..10 {
+            before += runaways(orchestrator.process(&telemetry("CNC-001", 105.0)).await);
+        }
+        assert!(before > 0);
+
+        let patch = serde_json::json!({
+            "thermal_runaway_temp": 120.0,
+            "thermal_runaway_warn_temp": 110.0,
+        });
+        orchestrator.reconfigure("CNC-001", &patch).await.unwrap();
+
+        let mut after = 0;
+        for _ in 0..10 {
+            after += runaways(orchestrator.process(&telemetry("CNC-001", 105.0)).await);
+        }
+        assert_eq!(after, 0);
+
+        assert!(orchestrator.reconfigure("CNC-009", &patch).await.is_err());
+        assert!(orchestrator
+            .reconfigure("CNC-001", &serde_json::json!({"runaway_temp": 120.0}))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_machines_keep_independent_state() {
+        let orchestrator = Arc::new(Orchestrator::new(vec![
+            precision_agent("CNC-001"),
+            precision_agent("CNC-002"),
+        ]));
+
+        // One task per machine, interleaving on the shared orchestrator
+        let feed = |machine_id: &'static str, temperature: f64| {
+            let orchestrator = Arc::clone(&orchestrator);
+            tokio::spawn(async move {
+                let mut runaways = 0;
+                for _ in 0..50 {
+                    runaways += orchestrator
+                        .process(&telemetry(machine_id, temperature))
+                        .await
+                        .iter()
+                        .filter(|d| d.threat.threat_type() == "ThermalRunaway")
+                        .count();
+                    tokio::task::yield_now().await;
+                }
+                runaways
+            })
+        };
+        let (hot, cool) = tokio::join!(feed("CNC-001", 105.0), feed("CNC-002", 22.0));
+
+        assert!(hot.unwrap() > 0);
+        assert_eq!(cool.unwrap(), 0);
+        let heartbeats = orchestrator.heartbeats().await;
+        assert!(heartbeats.iter().all(|h| h.samples_processed == 50));
+        assert!(heartbeats[0].health_score < 1.0);
+        assert_eq!(heartbeats[1].health_score, 1.0);
+        assert!(orchestrator.snapshot().iter().all(|t| t.machine_id == "CNC-001"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_multi_machine_agents_keep_state_per_machine() {
+        let facility: Arc<RwLock<dyn SentinelAgent>> = Arc::new(RwLock::new(
+            FacilitySentinel::new("FAC-001".to_string(), FacilityConfig::default()),
+        ));
+        let assembly: Arc<RwLock<dyn SentinelAgent>> =
+            Arc::new(RwLock::new(AssemblySentinel::new(AssemblyConfig::default())));
+        let orchestrator = Arc::new(Orchestrator::new(vec![facility, assembly]));
+
+        // One task per machine, so each agent sees two machines interleaved
+        let feed = |machine_id: &'static str, reading: fn(usize) -> Vec<(&'static str, f64)>| {
+            let orchestrator = Arc::clone(&orchestrator);
+            tokio::spawn(async move {
+                let mut threats = Vec::new();
+                for i in 0..40 {
+                    let mut telemetry = telemetry(machine_id, 22.0);
+                    for (metric, value) in reading(i) {
+                        telemetry.metrics.insert(metric.to_string(), value);
+                    }
+                    threats.extend(orchestrator.process(&telemetry).await.into_iter().map(|d| d.threat));
+                    tokio::task::yield_now().await;
+                }
+                threats
+            })
+        };
+        let (fac_1, fac_2, bond_1, bond_2) = tokio::join!(
+            // Anchors on a clean filter, then loads
+            feed("FAC-001", |i| vec![
+                ("pressure_diff_pa", if i < 20 { 100.0 } else { 200.0 }),
+                ("airflow_mps", 0.45),
+            ]),
+            // A denser filter, steady from the start
+            feed("FAC-002", |_| vec![("pressure_diff_pa", 200.0), ("airflow_mps", 0.45)]),
+            // Every bond a non-stick
+            feed("BOND-01", |_| vec![("usg_impedance", 20.0)]),
+            feed("BOND-02", |_| vec![("usg_impedance", 50.0)]),
+        );
+        let (fac_1, fac_2, bond_1, bond_2) =
+            (fac_1.unwrap(), fac_2.unwrap(), bond_1.unwrap(), bond_2.unwrap());
+
+        let filter_loading = |threats: &[Threat]| {
+            threats.iter().any(|t| matches!(
+                t,
+                Threat::FacilityIntegrity { issue, .. } if issue == "Filter Loading Detected"
+            ))
+        };
+        let nsop = |threats: &[Threat]| {
+            threats.iter().any(|t| matches!(t, Threat::QualityDefect { .. }))
+        };
+        assert!(filter_loading(&fac_1));
+        assert!(!filter_loading(&fac_2));
+        assert!(nsop(&bond_1));
+        assert!(!nsop(&bond_2));
+        for (machine_id, threats) in [("FAC-001", &fac_1), ("FAC-002", &fac_2), ("BOND-01", &bond_1)] {
+            assert!(threats.iter().all(|t| t.machine_id() == machine_id));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_lists_active_threats_across_machines() {
+        let orchestrator =
+            Orchestrator::new(vec![precision_agent("CNC-001"), precision_agent("CNC-002")]);
+
+        // Runaway detection needs a short temperature history
+        for _ in 0..10 {
+            orchestrator.process(&telemetry("CNC-001", 105.0)).await;
+            orchestrator.process(&telemetry("CNC-002", 97.0)).await;
+        }
+
+        let snapshot = orchestrator.snapshot();
+        let runaway = |machine_id: &str| {
+            snapshot
+                .iter()
+                .find(|t| t.machine_id == machine_id && t.threat_type == "ThermalRunaway")
+                .unwrap()
+        };
+        assert_eq!(runaway("CNC-001").severity, "critical");
+        assert_eq!(runaway("CNC-002").severity, "high");
+
+        // One entry per (machine, threat type), however often it was re-detected
+        let mut keys: Vec<_> = snapshot.iter().map(|t| (&t.machine_id, &t.threat_type)).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), snapshot.len());
+
+        // Most severe first, and serializable for the status page
+        assert_eq!(snapshot[0].severity, "critical");
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(json.contains("\"severity\":\"critical\""));
+    }
+
+    #[tokio::test]
+    async fn test_analysis_does_not_lock_out_other_machines() {
+        let assembly: Arc<RwLock<dyn SentinelAgent>> =
+            Arc::new(RwLock::new(AssemblySentinel::new(AssemblyConfig::default())));
+        let orchestrator = Orchestrator::new(vec![Arc::clone(&assembly)]);
+
+        // Stands in for BOND-01's analysis, still holding the agent
+        let _bond_01 = assembly.read().await;
+        let mut telemetry = telemetry("BOND-02", 22.0);
+        telemetry.metrics.insert("usg_impedance".to_string(), 50.0);
+        let processed = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            orchestrator.process(&telemetry),
+        )
+        .await;
+        assert!(processed.is_ok(), "BOND-02 waited on BOND-01's analysis");
+    }
+
+    #[tokio::test]
+    async fn test_suppressed_threats_do_not_touch_last_threat_at() {
+        let orchestrator = Orchestrator::new(vec![precision_agent("CNC-001")]).with_suppressions(
+            vec![
+                ("CNC-001".to_string(), "ThermalDrift".to_string()),
+                ("CNC-001".to_string(), "ThermalRunaway".to_string()),
+            ],
+        );
+
+        for _ in 0..10 {
+            assert!(orchestrator.process(&telemetry("CNC-001", 105.0)).await.is_empty());
+        }
+
+        let heartbeat = &orchestrator.heartbeats().await[0];
+        assert!(heartbeat.suppressed_threats > 0);
+        assert_eq!(heartbeat.last_threat_at, None);
+        assert_eq!(heartbeat.health_score, 1.0);
+    }
+}
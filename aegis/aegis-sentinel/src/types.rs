@@ -11,6 +11,31 @@ pub struct Telemetry {
     pub machine_id: String,
     pub metrics: HashMap<String, f64>,
     pub states: HashMap<String, String>,
+    /// Declared unit per metric (e.g. "temperature" -> "F"); optional
+    #[serde(default)]
+    pub units: HashMap<String, String>,
+}
+
+impl Telemetry {
+    /// Metric value, if reported and finite
+    ///
+    /// NaN/Inf readings (a faulted sensor, a bad conversion) are treated as
+    /// missing so they can't poison rolling histories.
+    pub fn metric(&self, name: &str) -> Option<f64> {
+        self.metrics.get(name).copied().filter(|v| v.is_finite())
+    }
+
+    /// Metric value, or `default` when the machine didn't report a usable one
+    pub fn metric_or(&self, name: &str, default: f64) -> f64 {
+        self.metric(name).unwrap_or(default)
+    }
+
+    /// When the reading was taken; arrival time if `timestamp` isn't RFC 3339
+    pub fn observed_at(&self) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .map(|at| at.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now())
+    }
 }
 
 /// Threat types detected by agents
@@ -21,6 +46,8 @@ pub enum Threat {
         machine_id: String,
         frequency_hz: f64,
         amplitude_mm_s: f64,
+        /// Amplitude that triggered the detection (baseline x chatter ratio)
+        limit: f64,
         severity: Severity,
     },
     /// Thermal drift affecting precision
@@ -28,6 +55,7 @@ pub enum Threat {
         machine_id: String,
         drift_mm: f64,
         axis: String,
+        limit: f64,
         severity: Severity,
     },
     /// Tool wear detected
@@ -35,6 +63,8 @@ pub enum Threat {
         machine_id: String,
         wear_percent: f64,
         remaining_life_minutes: Option<f64>,
+        /// Wear (%) at which tool wear is reported
+        limit: f64,
         severity: Severity,
     },
     /// Thermal runaway condition
@@ -42,6 +72,9 @@ pub enum Threat {
         machine_id: String,
         temperature: f64,
         rate_of_change: f64,
+        /// Temperature past which the detection fired (the warning
+        /// temperature when it fired on the rate of rise)
+        limit: f64,
         severity: Severity,
     },
     /// Bearing failure signature
@@ -49,6 +82,7 @@ pub enum Threat {
         machine_id: String,
         vibration_mm_s: f64,
         frequency_hz: f64,
+        limit: f64,
         severity: Severity,
     },
     /// Coolant system failure
@@ -56,6 +90,8 @@ pub enum Threat {
         machine_id: String,
         flow_rate: f64,
         temperature: f64,
+        /// Minimum flow rate
+        limit: f64,
         severity: Severity,
     },
     /// Facility integrity issue (FFU, HVAC, etc.)
@@ -64,6 +100,9 @@ pub enum Threat {
         issue: String,
         severity: Severity,
         metric: f64,
+        /// Value `metric` crossed: a maximum (pressure drop, expansion) or a
+        /// minimum (airflow, exhaust flow), depending on the issue
+        limit: f64,
     },
     /// Cleanroom contamination (ISO 14644)
     Contamination {
@@ -76,6 +115,7 @@ pub enum Threat {
     ChemicalLeak {
         zone_id: String,
         concentration_ppm: f64,
+        limit: f64,
         severity: Severity,
     },
     /// Quality defect (NSOP, weak bonds, etc.)
@@ -97,6 +137,8 @@ pub enum Threat {
         machine_id: String,
         component: String,
         metric: f64,
+        /// Value `metric` crossed (a maximum or a minimum, per component)
+        limit: f64,
         severity: Severity,
     },
     /// Generic anomaly
@@ -161,6 +203,63 @@ impl Threat {
             Threat::Anomaly { .. } => "Anomaly",
         }
     }
+
+    /// Measured value and the configured threshold it crossed
+    pub fn reading(&self) -> (f64, f64) {
+        match self {
+            Threat::Chatter { amplitude_mm_s, limit, .. } => (*amplitude_mm_s, *limit),
+            Threat::ThermalDrift { drift_mm, limit, .. } => (*drift_mm, *limit),
+            Threat::ToolWear { wear_percent, limit, .. } => (*wear_percent, *limit),
+            Threat::ThermalRunaway { temperature, limit, .. } => (*temperature, *limit),
+            Threat::BearingFailure { vibration_mm_s, limit, .. } => (*vibration_mm_s, *limit),
+            Threat::CoolantFailure { flow_rate, limit, .. } => (*flow_rate, *limit),
+            Threat::FacilityIntegrity { metric, limit, .. } => (*metric, *limit),
+            Threat::Contamination { particle_count, limit, .. } => (*particle_count, *limit),
+            Threat::ChemicalLeak { concentration_ppm, limit, .. } => (*concentration_ppm, *limit),
+            Threat::QualityDefect { confidence, .. } => (*confidence, 0.95),
+            Threat::ThroughputDegradation { .. } => (0.0, 0.0),
+            Threat::EquipmentDegradation { metric, limit, .. } => (*metric, *limit),
+            Threat::Anomaly { .. } => (0.0, 0.0),
+        }
+    }
+
+    /// Detection confidence in [0, 1]
+    ///
+    /// Grows with how far the reading is past its threshold: just over the
+    /// line is 0.5, twice the threshold is ~0.93. Threats without a
+    /// comparable reading fall back to a prior based on severity.
+    pub fn confidence(&self) -> f64 {
+        let (value, threshold) = self.reading();
+        let exceedance = match self {
+            Threat::QualityDefect { confidence, .. } => return confidence.clamp(0.0, 1.0),
+            // Minimums as well as maximums: measure past the limit either way
+            Threat::CoolantFailure { .. }
+            | Threat::FacilityIntegrity { .. }
+            | Threat::EquipmentDegradation { .. } if threshold > 0.0 => {
+                let value = value.abs();
+                Some(if value >= threshold {
+                    value / threshold
+                } else if value > 0.0 {
+                    threshold / value
+                } else {
+                    f64::INFINITY
+                })
+            }
+            Threat::ThroughputDegradation { .. } | Threat::Anomaly { .. } => None,
+            _ if threshold > 0.0 => Some(value.abs() / threshold),
+            _ => None,
+        };
+
+        match exceedance {
+            Some(ratio) => (1.0 - 0.5 * (-2.0 * (ratio - 1.0).max(0.0)).exp()).min(0.99),
+            None => match self.severity() {
+                Severity::Low => 0.5,
+                Severity::Medium => 0.65,
+                Severity::High => 0.8,
+                Severity::Critical => 0.9,
+            },
+        }
+    }
 }
 
 /// Threat severity levels
@@ -237,6 +336,19 @@ impl Action {
             Action::LogOnly => "LogOnly",
         }
     }
+
+    /// How strongly the action restricts the machine, for actions that
+    /// command it (higher is more conservative); `None` for notifications
+    /// and maintenance requests, which never conflict
+    pub fn restrictiveness(&self) -> Option<u8> {
+        match self {
+            Action::EmergencyStop => Some(3),
+            Action::FeedHold { .. } => Some(2),
+            Action::ReduceSpeed { .. } => Some(1),
+            Action::AdjustParameter { .. } => Some(0),
+            _ => None,
+        }
+    }
 }
 
 /// Command sent to machines
@@ -262,6 +374,7 @@ pub struct Incident {
     pub message: String,
     pub value: f64,
     pub threshold: f64,
+    pub confidence: f64,
     pub action: String,
     pub action_status: String,
     pub zone: String,
@@ -269,21 +382,7 @@ pub struct Incident {
 
 impl Incident {
     pub fn from_threat(threat: &Threat, action: &Action, status: &str) -> Self {
-        let (value, threshold) = match threat {
-            Threat::Chatter { amplitude_mm_s, .. } => (*amplitude_mm_s, 0.01),
-            Threat::ThermalDrift { drift_mm, .. } => (*drift_mm, 0.05),
-            Threat::ToolWear { wear_percent, .. } => (*wear_percent, 15.0),
-            Threat::ThermalRunaway { temperature, .. } => (*temperature, 80.0),
-            Threat::BearingFailure { vibration_mm_s, .. } => (*vibration_mm_s, 0.02),
-            Threat::CoolantFailure { flow_rate, .. } => (*flow_rate, 0.8),
-            Threat::FacilityIntegrity { metric, .. } => (*metric, 250.0),
-            Threat::Contamination { particle_count, limit, .. } => (*particle_count, *limit),
-            Threat::ChemicalLeak { concentration_ppm, .. } => (*concentration_ppm, 10.0),
-            Threat::QualityDefect { confidence, .. } => (*confidence, 0.95),
-            Threat::ThroughputDegradation { .. } => (0.0, 0.0),
-            Threat::EquipmentDegradation { metric, .. } => (*metric, 0.0),
-            Threat::Anomaly { .. } => (0.0, 0.0),
-        };
+        let (value, threshold) = threat.reading();
 
         Self {
             incident_id: format!("INC-{}", uuid::Uuid::new_v4().to_string()[..8].to_uppercase()),
@@ -294,6 +393,7 @@ impl Incident {
             message: format!("{:?} detected on {}", threat.threat_type(), threat.machine_id()),
             value,
             threshold,
+            confidence: threat.confidence(),
             action: action.name().to_string(),
             action_status: status.to_string(),
             zone: match threat.severity() {
@@ -314,10 +414,37 @@ pub struct AgentMetadata {
     pub capabilities: Vec<String>,
 }
 
+/// Agent health reported with each heartbeat
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentHeartbeat {
+    pub machine_id: String,
+    /// Telemetry messages analyzed since startup
+    pub samples_processed: u64,
+    /// Detections dropped by an operator suppression since startup
+    pub suppressed_threats: u64,
+    /// Warm-up finished; detections are being emitted
+    pub baseline_ready: bool,
+    /// Last reported (not suppressed) threat
+    pub last_threat_at: Option<DateTime<Utc>>,
+    /// 1.0 with no active threats, falling with the worst active severity
+    pub health_score: f64,
+}
+
 /// Aegis configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct AegisConfig {
     pub agents: Vec<AgentConfig>,
+    /// (machine_id, threat_type) pairs to mute, e.g. during a planned experiment
+    #[serde(default)]
+    pub suppressions: Vec<(String, String)>,
+    /// Telemetry analysis tasks; each machine is pinned to one of them so its
+    /// readings stay in order while different machines run concurrently
+    #[serde(default = "default_analysis_workers")]
+    pub analysis_workers: usize,
+}
+
+fn default_analysis_workers() -> usize {
+    4
 }
 
 /// Individual agent configuration
@@ -328,6 +455,45 @@ pub struct AgentConfig {
     pub config: serde_yaml::Value,
 }
 
+/// S2F42 Host Command Acknowledge, as relayed by the Python bridge
+#[derive(Debug, Clone, Deserialize)]
+pub struct SecsCommandAck {
+    pub cmd: String,
+    pub machine_id: String,
+    /// HCACK code (0 = acknowledged, command performed)
+    pub hcack: u8,
+}
+
+impl SecsCommandAck {
+    /// Map the HCACK code to a command outcome
+    pub fn to_result(&self) -> Result<(), AgentError> {
+        if self.hcack == 0 {
+            return Ok(());
+        }
+        Err(AgentError::ExecutionError(format!(
+            "{} rejected by {}: HCACK={} ({})",
+            self.cmd,
+            self.machine_id,
+            self.hcack,
+            hcack_description(self.hcack)
+        )))
+    }
+}
+
+/// SEMI E5 HCACK meanings
+fn hcack_description(hcack: u8) -> &'static str {
+    match hcack {
+        0 => "acknowledged",
+        1 => "invalid command",
+        2 => "cannot perform now",
+        3 => "invalid parameter",
+        4 => "acknowledged, will complete later",
+        5 => "rejected, already in desired condition",
+        6 => "no such object exists",
+        _ => "unknown code",
+    }
+}
+
 /// Agent errors
 #[derive(Debug, thiserror::Error)]
 pub enum AgentError {
@@ -338,3 +504,49 @@ pub enum AgentError {
     #[error("MQTT error: {0}")]
     MqttError(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runaway(temperature: f64, limit: f64) -> Threat {
+        Threat::ThermalRunaway {
+            machine_id: "CNC-001".to_string(),
+            temperature,
+            rate_of_change: 0.0,
+            limit,
+            severity: Severity::High,
+        }
+    }
+
+    #[test]
+    fn test_confidence_grows_with_margin() {
+        let marginal = runaway(81.0, 80.0);
+        let extreme = runaway(160.0, 80.0);
+
+        assert!(marginal.confidence() < extreme.confidence());
+        assert!((0.5..0.6).contains(&marginal.confidence()));
+        assert!(extreme.confidence() <= 0.99);
+
+        let incident = Incident::from_threat(&extreme, &Action::EmergencyStop, "alert_only");
+        assert_eq!(incident.confidence, extreme.confidence());
+        assert_eq!(incident.threshold, 80.0);
+    }
+
+    #[test]
+    fn test_confidence_judged_against_configured_limit() {
+        // Same reading, tighter configured limit: further past it
+        assert!(runaway(95.0, 60.0).confidence() > runaway(95.0, 90.0).confidence());
+
+        // Minimums count too: airflow well below its floor
+        let airflow = |metric: f64| Threat::FacilityIntegrity {
+            unit_id: "FAC-001".to_string(),
+            issue: "FFU Airflow Failure".to_string(),
+            severity: Severity::Critical,
+            metric,
+            limit: 0.36,
+        };
+        assert!(airflow(0.1).confidence() > airflow(0.35).confidence());
+        assert!(airflow(0.35).confidence() >= 0.5);
+    }
+}
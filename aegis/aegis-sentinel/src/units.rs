@@ -0,0 +1,104 @@
+//! Unit metadata validation for telemetry
+//!
+//! Detectors assume each metric arrives in a canonical unit (°C, mm/s, Pa, ...).
+//! Telemetry may declare the unit of each metric; this module converts known
+//! alternatives to the canonical unit and reports anything it cannot reconcile,
+//! so a sensor misconfigured to Fahrenheit doesn't silently break thresholds.
+//! The conversion table is shared with the Supabase bridge (`aegis_common::units`).
+
+use aegis_common::units::{check_unit, UnitCheck};
+
+use crate::types::Telemetry;
+
+/// Outcome of validating a single metric's declared unit
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitIssue {
+    /// Value was converted from a known alternative unit
+    Converted {
+        metric: String,
+        from: String,
+        to: &'static str,
+    },
+    /// Declared unit is not convertible to what the detectors expect
+    Mismatch {
+        metric: String,
+        unit: String,
+        expected: &'static str,
+    },
+}
+
+/// Validate declared units and convert metrics to their canonical units
+///
+/// Metrics without a declared unit, or without a known canonical unit, are
+/// assumed to already be correct. Mismatched metrics are left untouched.
+pub fn normalize_units(telemetry: &mut Telemetry) -> Vec<UnitIssue> {
+    let mut issues = Vec::new();
+
+    for (metric, unit) in &telemetry.units {
+        let Some(value) = telemetry.metrics.get_mut(metric) else {
+            continue;
+        };
+
+        match check_unit(metric, unit, *value) {
+            UnitCheck::Canonical => {}
+            UnitCheck::Converted { value: converted, to } => {
+                *value = converted;
+                issues.push(UnitIssue::Converted {
+                    metric: metric.clone(),
+                    from: unit.clone(),
+                    to,
+                });
+            }
+            UnitCheck::Mismatch { expected } => issues.push(UnitIssue::Mismatch {
+                metric: metric.clone(),
+                unit: unit.clone(),
+                expected,
+            }),
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn telemetry_with(metric: &str, value: f64, unit: &str) -> Telemetry {
+        let mut telemetry = Telemetry {
+            timestamp: "2026-02-07T00:00:00Z".to_string(),
+            machine_id: "CNC-001".to_string(),
+            metrics: HashMap::new(),
+            states: HashMap::new(),
+            units: HashMap::new(),
+        };
+        telemetry.metrics.insert(metric.to_string(), value);
+        telemetry.units.insert(metric.to_string(), unit.to_string());
+        telemetry
+    }
+
+    #[test]
+    fn test_fahrenheit_temperature_is_converted() {
+        let mut telemetry = telemetry_with("temperature", 149.0, "°F");
+
+        let issues = normalize_units(&mut telemetry);
+
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0], UnitIssue::Converted { .. }));
+        let celsius = telemetry.metrics["temperature"];
+        assert!((celsius - 65.0).abs() < 1e-9, "149°F should be 65°C, got {}", celsius);
+    }
+
+    #[test]
+    fn test_canonical_and_unknown_units() {
+        let mut telemetry = telemetry_with("temperature", 65.0, "degC");
+        assert!(normalize_units(&mut telemetry).is_empty());
+        assert_eq!(telemetry.metrics["temperature"], 65.0);
+
+        let mut telemetry = telemetry_with("vibration", 0.5, "g");
+        let issues = normalize_units(&mut telemetry);
+        assert!(matches!(issues[0], UnitIssue::Mismatch { expected: "mm/s", .. }));
+        assert_eq!(telemetry.metrics["vibration"], 0.5);
+    }
+}
@@ -0,0 +1,149 @@
+//! Golden-file regression vectors for the detectors
+//!
+//! A vector file is a JSON list of cases. Each case builds one agent (with
+//! optional threshold overrides, applied like a config hot-reload), feeds it
+//! a telemetry sequence and compares the distinct threat types it raised
+//! against the expected list. Run from CI with
+//! `aegis-sentinel --run-vectors fixtures/detection_vectors.json`.
+
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::agents::assembly::{AssemblyConfig, AssemblySentinel};
+use crate::agents::facility::FacilitySentinel;
+use crate::agents::precision::PrecisionSentinel;
+use crate::agents::SentinelAgent;
+use crate::types::Telemetry;
+
+/// One labeled telemetry sequence
+#[derive(Debug, Clone, Deserialize)]
+pub struct VectorCase {
+    pub name: String,
+    /// "precision", "facility" or "assembly"
+    pub agent_type: String,
+    pub machine_id: String,
+    /// Partial agent config, e.g. `{"min_ultrasonic_impedance": 30.0}`
+    #[serde(default)]
+    pub config: Option<serde_json::Value>,
+    pub steps: Vec<VectorStep>,
+    /// Threat types (`Threat::threat_type`) the sequence must raise, no more
+    pub expected: Vec<String>,
+}
+
+/// Telemetry sent `repeat` times in a row
+#[derive(Debug, Clone, Deserialize)]
+pub struct VectorStep {
+    pub metrics: HashMap<String, f64>,
+    #[serde(default)]
+    pub states: HashMap<String, String>,
+    #[serde(default = "default_repeat")]
+    pub repeat: usize,
+}
+
+fn default_repeat() -> usize {
+    1
+}
+
+/// Vector runner errors
+#[derive(Debug, thiserror::Error)]
+pub enum VectorError {
+    #[error("Failed to read vectors: {0}")]
+    Io(String),
+    #[error("Invalid vector file: {0}")]
+    Parse(String),
+    #[error("{} vector(s) failed:\n{}", .0.len(), .0.join("\n"))]
+    Failed(Vec<String>),
+}
+
+/// Run every case in a vector file; returns the number of cases checked
+pub fn run_vectors(path: &Path) -> Result<usize, VectorError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| VectorError::Io(format!("{}: {}", path.display(), e)))?;
+    let cases: Vec<VectorCase> =
+        serde_json::from_str(&text).map_err(|e| VectorError::Parse(e.to_string()))?;
+    run_cases(&cases)
+}
+
+/// Run the cases, collecting every mismatch rather than stopping at the first
+pub fn run_cases(cases: &[VectorCase]) -> Result<usize, VectorError> {
+    let failures: Vec<String> = cases
+        .iter()
+        .filter_map(|case| run_case(case).err().map(|e| format!("  {}: {}", case.name, e)))
+        .collect();
+
+    if failures.is_empty() {
+        Ok(cases.len())
+    } else {
+        Err(VectorError::Failed(failures))
+    }
+}
+
+fn run_case(case: &VectorCase) -> Result<(), String> {
+    let mut agent = build_agent(case)?;
+    if let Some(patch) = &case.config {
+        agent.reconfigure(patch).map_err(|e| e.to_string())?;
+    }
+
+    let mut raised = BTreeSet::new();
+    for step in &case.steps {
+        let telemetry = Telemetry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            machine_id: case.machine_id.clone(),
+            metrics: step.metrics.clone(),
+            states: step.states.clone(),
+            units: HashMap::new(),
+        };
+        for _ in 0..step.repeat {
+            for threat in agent.analyze(&telemetry) {
+                raised.insert(threat.threat_type().to_string());
+            }
+        }
+    }
+
+    let expected: BTreeSet<String> = case.expected.iter().cloned().collect();
+    if raised == expected {
+        Ok(())
+    } else {
+        Err(format!("expected {:?}, got {:?}", expected, raised))
+    }
+}
+
+fn build_agent(case: &VectorCase) -> Result<Box<dyn SentinelAgent>, String> {
+    let machine_id = case.machine_id.clone();
+    match case.agent_type.as_str() {
+        "precision" => Ok(Box::new(PrecisionSentinel::new(machine_id, Default::default()))),
+        "facility" => Ok(Box::new(FacilitySentinel::new(machine_id, Default::default()))),
+        "assembly" => Ok(Box::new(AssemblySentinel::new(AssemblyConfig {
+            machine_id,
+            ..Default::default()
+        }))),
+        other => Err(format!("unknown agent type '{}'", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/detection_vectors.json")
+    }
+
+    #[test]
+    fn test_golden_vectors_pass_and_catch_drift() {
+        assert_eq!(run_vectors(&fixture()).unwrap(), 3);
+
+        // A changed expectation must be reported, not silently accepted
+        let text = std::fs::read_to_string(fixture()).unwrap();
+        let mut cases: Vec<VectorCase> = serde_json::from_str(&text).unwrap();
+        cases[0].expected = vec!["Anomaly".to_string()];
+        match run_cases(&cases) {
+            Err(VectorError::Failed(failures)) => {
+                assert_eq!(failures.len(), 1);
+                assert!(failures[0].contains(&cases[0].name));
+            }
+            other => panic!("expected a failed vector, got {:?}", other),
+        }
+    }
+}
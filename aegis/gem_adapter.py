@@ -63,6 +63,7 @@ class SECSAck:
     timestamp: str
     secs_stream: int
     secs_function: int
+    hcack: int  # 0 = OK, non-zero = rejected (SEMI E5)
 
 
 class SentinelGEMHandler(secsgem.gem.GemEquipmentHandler):
@@ -116,7 +117,13 @@ class SentinelGEMHandler(secsgem.gem.GemEquipmentHandler):
         success = handler(params)
         
         # Send acknowledgment via MQTT to Rust Agent
-        self._acknowledge_to_rust(rcmd, "EXECUTED" if success else "FAILED")
+        if success:
+            hcack = 0
+        elif rcmd not in self.command_handlers:
+            hcack = 1  # Invalid command
+        else:
+            hcack = 2  # Cannot perform now
+        self._acknowledge_to_rust(rcmd, "EXECUTED" if success else "FAILED", hcack)
         
         return success
     
@@ -169,7 +176,7 @@ class SentinelGEMHandler(secsgem.gem.GemEquipmentHandler):
         }
         print(f"[AUDIT] {json.dumps(log_entry)}")
     
-    def _acknowledge_to_rust(self, rcmd: str, status: str):
+    def _acknowledge_to_rust(self, rcmd: str, status: str, hcack: int):
         """Send acknowledgment back to Rust Agent via MQTT"""
         if self.mqtt_client and self.mqtt_client.is_connected():
             ack = SECSAck(
@@ -178,7 +185,8 @@ class SentinelGEMHandler(secsgem.gem.GemEquipmentHandler):
                 machine_id=self.machine_id,
                 timestamp=datetime.utcnow().isoformat(),
                 secs_stream=2,
-                secs_function=42  # S2F42 is the acknowledgment
+                secs_function=42,  # S2F42 is the acknowledgment
+                hcack=hcack
             )
             payload = json.dumps(asdict(ack))
             self.mqtt_client.publish("sentinel/bridge/ack", payload)
@@ -0,0 +1,101 @@
+//! Rolling z-score scoring for ingested telemetry
+//!
+//! Machines rarely send an `anomaly_score` themselves, so the bridge scores
+//! each reading the same way the Sentinel's `StatisticalAnalyzer` does: every
+//! (machine, metric) pair keeps a window of recent values, and a reading's
+//! score is its distance from the window mean in standard deviations.
+
+use std::collections::{HashMap, VecDeque};
+
+/// Readings kept per (machine, metric)
+pub const DEFAULT_WINDOW: usize = 60;
+
+/// |z| at or above this marks the reading as anomalous
+pub const ANOMALY_Z_THRESHOLD: f64 = 3.0;
+
+/// Readings needed before a metric is scored at all
+const MIN_SAMPLES: usize = 10;
+
+pub struct AnomalyScorer {
+    window: usize,
+    history: HashMap<(String, String), VecDeque<f64>>,
+}
+
+impl AnomalyScorer {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(MIN_SAMPLES),
+            history: HashMap::new(),
+        }
+    }
+
+    /// Largest |z| across the metrics, each against its history before this
+    /// reading; `None` until some metric has enough history
+    ///
+    /// NaN/Inf values are neither scored nor recorded.
+    pub fn score(&mut self, machine_id: &str, metrics: &HashMap<String, f64>) -> Option<f64> {
+        let mut worst: Option<f64> = None;
+        for (name, &value) in metrics {
+            if !value.is_finite() {
+                continue;
+            }
+            let history = self
+                .history
+                .entry((machine_id.to_string(), name.clone()))
+                .or_default();
+
+            if history.len() >= MIN_SAMPLES {
+                let n = history.len() as f64;
+                let mean = history.iter().sum::<f64>() / n;
+                let std_dev = (history.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt();
+                let z = if std_dev == 0.0 { 0.0 } else { ((value - mean) / std_dev).abs() };
+                worst = Some(worst.map_or(z, |w| w.max(z)));
+            }
+
+            history.push_back(value);
+            if history.len() > self.window {
+                history.pop_front();
+            }
+        }
+        worst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SensorReading, TelemetryPayload};
+
+    fn telemetry(vibration: f64) -> TelemetryPayload {
+        TelemetryPayload {
+            timestamp: "2026-02-07T00:00:00Z".to_string(),
+            machine_id: "CNC-001".to_string(),
+            metrics: HashMap::from([("vibration".to_string(), vibration)]),
+            states: None,
+            status: None,
+            anomaly_detected: None,
+            anomaly_score: None,
+            units: None,
+        }
+    }
+
+    #[test]
+    fn test_four_sigma_reading_flagged_in_sensor_reading() {
+        let mut scorer = AnomalyScorer::new(DEFAULT_WINDOW);
+
+        // Alternating 0.9 / 1.1: mean 1.0, standard deviation 0.1
+        for i in 0..20 {
+            let payload = telemetry(if i % 2 == 0 { 0.9 } else { 1.1 });
+            let score = scorer.score(&payload.machine_id, &payload.metrics);
+            let reading = SensorReading::from_telemetry(&payload, "precision", score);
+            assert!(!reading.is_anomaly);
+        }
+
+        let payload = telemetry(1.4);
+        let score = scorer.score(&payload.machine_id, &payload.metrics);
+        let reading = SensorReading::from_telemetry(&payload, "precision", score);
+
+        assert!(reading.is_anomaly);
+        assert!((reading.anomaly_score.unwrap() - 4.0).abs() < 1e-6);
+    }
+}
@@ -4,9 +4,17 @@
 //! enabling real-time data sharing between YieldOps and Sentinel.
 //!
 //! # Architecture
-//! - Subscribes to factory/+/telemetry (machine telemetry)
+//! - Subscribes to factory/+/telemetry (machine telemetry), scoring each
+//!   reading with a rolling z-score when the machine sends no anomaly_score
 //! - Subscribes to aegis/incidents (Sentinel detections)
 //! - Publishes to Supabase for YieldOps dashboard
+//! - Forwards `aegis_agent_config` changes to aegis/config/{machine_id}
+//! - Forwards incidents resolved in the dashboard to aegis/{machine_id}/resolve,
+//!   so the Sentinel resets that machine's counters
+//! - With the `scheduler` feature, re-plans pending jobs against live machine
+//!   state and publishes the plan to yieldops/schedule
+//! - Publishes weekly incident counts by severity and type to
+//!   aegis/reports/incidents
 //!
 //! # Data Flow
 //! ```text
@@ -14,16 +22,22 @@
 //! ```
 
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
 use tracing::{info, error, debug, warn};
 use chrono::Utc;
 
+mod anomaly;
 mod supabase;
 mod mqtt;
+mod reporting;
+#[cfg(feature = "scheduler")]
+mod scheduling;
 mod types;
 
+use anomaly::{AnomalyScorer, DEFAULT_WINDOW};
 use supabase::SupabaseClient;
-use mqtt::MqttBridge;
+use mqtt::{MqttBridge, MqttSession};
 use types::*;
 
 #[tokio::main]
@@ -62,7 +76,7 @@ async fn main() -> anyhow::Result<()> {
 
     info!("Connecting to MQTT broker at {}...", mqtt_broker);
     let mqtt = Arc::new(Mutex::new(
-        MqttBridge::new(&mqtt_broker).await?
+        MqttBridge::new(&mqtt_broker, &MqttSession::from_env("aegis-supabase-bridge")).await?
     ));
     info!("✓ MQTT connected");
 
@@ -75,6 +89,40 @@ async fn main() -> anyhow::Result<()> {
         info!("✓ Subscribed to MQTT topics");
     }
 
+    // Threshold changes and resolutions arrive over Realtime on their own connection
+    let (update_tx, mut update_rx) = mpsc::channel::<SentinelUpdate>(32);
+    let realtime = SupabaseClient::new(&supabase_url, &supabase_key).await?;
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = realtime.subscribe_sentinel_updates(update_tx.clone()).await {
+                warn!("Realtime subscription dropped: {} - reconnecting in 5s", e);
+            }
+            if update_tx.is_closed() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    });
+
+    // Live scheduling passes hand their plans to the main loop to publish
+    let (plan_tx, mut plan_rx) = mpsc::channel::<MqttMessage>(4);
+    #[cfg(feature = "scheduler")]
+    {
+        let planner = SupabaseClient::new(&supabase_url, &supabase_key).await?;
+        tokio::spawn(scheduling::run_live_scheduling(planner, plan_tx));
+        info!("✓ Live scheduling enabled");
+    }
+    #[cfg(not(feature = "scheduler"))]
+    drop(plan_tx);
+
+    // Incident trend reports, also published from the main loop
+    let (report_tx, mut report_rx) = mpsc::channel::<MqttMessage>(4);
+    let reporter = SupabaseClient::new(&supabase_url, &supabase_key).await?;
+    tokio::spawn(reporting::run_incident_reports(reporter, report_tx));
+
+    // Rolling z-scores fill anomaly_score for machines that don't send one
+    let mut scorer = AnomalyScorer::new(DEFAULT_WINDOW);
+
     info!("Bridge is running - Press Ctrl+C to stop");
 
     // Main event loop
@@ -85,10 +133,36 @@ async fn main() -> anyhow::Result<()> {
                 let mut mqtt_guard = mqtt.lock().await;
                 mqtt_guard.receive().await
             } => {
-                if let Err(e) = handle_message(&supabase, message).await {
+                if let Err(e) = handle_message(&supabase, &mut scorer, message).await {
                     error!("Failed to handle message: {}", e);
                 }
             }
+
+            // Push threshold changes and resolutions to the Sentinel
+            Some(update) = update_rx.recv() => {
+                let message = update.to_mqtt();
+                let mut mqtt_guard = mqtt.lock().await;
+                match mqtt_guard.publish(&message.topic, &message.payload).await {
+                    Ok(()) => info!("✓ Published {} for {}", message.topic, update.machine_id()),
+                    Err(e) => error!("Failed to publish {}: {}", message.topic, e),
+                }
+            }
+
+            // Publish live scheduling plans
+            Some(plan) = plan_rx.recv(), if cfg!(feature = "scheduler") => {
+                let mut mqtt_guard = mqtt.lock().await;
+                if let Err(e) = mqtt_guard.publish(&plan.topic, &plan.payload).await {
+                    error!("Failed to publish {}: {}", plan.topic, e);
+                }
+            }
+
+            // Publish incident trend reports
+            Some(report) = report_rx.recv() => {
+                let mut mqtt_guard = mqtt.lock().await;
+                if let Err(e) = mqtt_guard.publish(&report.topic, &report.payload).await {
+                    error!("Failed to publish {}: {}", report.topic, e);
+                }
+            }
             
             // Handle shutdown signal
             _ = tokio::signal::ctrl_c() => {
@@ -104,11 +178,12 @@ async fn main() -> anyhow::Result<()> {
 
 async fn handle_message(
     supabase: &Arc<Mutex<SupabaseClient>>,
+    scorer: &mut AnomalyScorer,
     message: MqttMessage,
 ) -> anyhow::Result<()> {
     match message.topic.as_str() {
         topic if topic.contains("/telemetry") => {
-            handle_telemetry(supabase, &message.payload).await?;
+            handle_telemetry(supabase, scorer, &message.payload).await?;
         }
         topic if topic == "aegis/incidents" => {
             handle_incident(supabase, &message.payload).await?;
@@ -125,6 +200,7 @@ async fn handle_message(
 
 async fn handle_telemetry(
     supabase: &Arc<Mutex<SupabaseClient>>,
+    scorer: &mut AnomalyScorer,
     payload: &str,
 ) -> anyhow::Result<()> {
     let telemetry: TelemetryPayload = serde_json::from_str(payload)?;
@@ -139,25 +215,10 @@ async fn handle_telemetry(
 
     // Determine agent type from machine_id prefix
     let agent_type = detect_agent_type(&telemetry.machine_id);
-    
-    // Insert sensor readings
-    let reading = SensorReading {
-        reading_id: uuid::Uuid::new_v4().to_string(),
-        machine_id: telemetry.machine_id.clone(),
-        temperature: telemetry.metrics.get("temperature").copied(),
-        vibration: telemetry.metrics.get("vibration").copied(),
-        pressure: telemetry.metrics.get("pressure").copied(),
-        airflow: telemetry.metrics.get("airflow_mps").copied(),
-        particles_0_5um: telemetry.metrics.get("particles_0_5um").copied(),
-        usg_impedance: telemetry.metrics.get("usg_impedance").copied(),
-        bond_time_ms: telemetry.metrics.get("bond_time_ms").copied(),
-        shear_strength: telemetry.metrics.get("shear_strength_g").copied(),
-        pressure_diff_pa: telemetry.metrics.get("pressure_diff_pa").copied(),
-        is_anomaly: telemetry.anomaly_detected.unwrap_or(false),
-        anomaly_score: telemetry.anomaly_score,
-        agent_type: agent_type.clone(),
-        recorded_at: Utc::now().to_rfc3339(),
-    };
+
+    // Insert sensor readings, scored against the machine's recent history
+    let z_score = scorer.score(&telemetry.machine_id, &telemetry.metrics);
+    let reading = SensorReading::from_telemetry(&telemetry, &agent_type, z_score);
 
     supabase_guard.insert_sensor_reading(reading).await?;
 
@@ -1,8 +1,10 @@
 //! MQTT client for the Supabase Bridge
 
-use rumqttc::{AsyncClient, EventLoop, MqttOptions, Packet, Publish, QoS};
+use rumqttc::{AsyncClient, EventLoop, Packet, Publish, QoS};
 use tracing::{debug, error, info};
 
+pub use aegis_common::mqtt::MqttSession;
+
 use crate::types::MqttMessage;
 
 pub struct MqttBridge {
@@ -11,23 +13,24 @@ pub struct MqttBridge {
 }
 
 impl MqttBridge {
-    pub async fn new(broker: &str) -> anyhow::Result<Self> {
+    pub async fn new(broker: &str, session: &MqttSession) -> anyhow::Result<Self> {
         let parts: Vec<&str> = broker.split(':').collect();
         let host = parts[0];
         let port = parts.get(1)
             .and_then(|p| p.parse().ok())
             .unwrap_or(1883u16);
 
-        let mut mqttoptions = MqttOptions::new(
-            "aegis-supabase-bridge",
-            host,
-            port,
-        );
-        mqttoptions.set_keep_alive(std::time::Duration::from_secs(5));
+        let mqttoptions = session.options(host, port);
         
         let (client, eventloop) = AsyncClient::new(mqttoptions, 10);
         
-        info!("MQTT client initialized for {}:{}", host, port);
+        info!(
+            "MQTT client {} initialized for {}:{} ({} session)",
+            session.client_id,
+            host,
+            port,
+            if session.clean_session { "clean" } else { "persistent" }
+        );
         Ok(Self { client, eventloop })
     }
     
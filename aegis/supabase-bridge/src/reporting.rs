@@ -0,0 +1,93 @@
+//! Periodic incident trend reports
+//!
+//! Counts the `aegis_incidents` raised over the last reporting period by
+//! severity and type, logs the totals and publishes the full histogram on
+//! `aegis/reports/incidents`.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::supabase::SupabaseClient;
+use crate::types::{IncidentHistogram, MqttMessage};
+
+/// Topic each report is published on
+pub const INCIDENT_REPORT_TOPIC: &str = "aegis/reports/incidents";
+
+/// Seconds per report (and per reporting period) unless
+/// `INCIDENT_REPORT_INTERVAL_SECS` is set: one week
+const DEFAULT_INTERVAL_SECS: u64 = 7 * 24 * 3600;
+
+/// Report for the incidents raised since `since`
+pub fn report_message(since: DateTime<Utc>, histogram: &IncidentHistogram) -> MqttMessage {
+    MqttMessage {
+        topic: INCIDENT_REPORT_TOPIC.to_string(),
+        payload: json!({ "since": since.to_rfc3339(), "histogram": histogram }).to_string(),
+    }
+}
+
+/// Report every `INCIDENT_REPORT_INTERVAL_SECS` and send each report to `tx`
+///
+/// Each report covers the interval just ended. Runs until the receiving end
+/// is dropped; a failed query is logged and retried on the next tick.
+pub async fn run_incident_reports(client: SupabaseClient, tx: mpsc::Sender<MqttMessage>) {
+    let interval_secs = std::env::var("INCIDENT_REPORT_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+    let period = Duration::from_secs(interval_secs);
+    let mut ticker = tokio::time::interval(period);
+
+    loop {
+        ticker.tick().await;
+        let since = Utc::now() - chrono::Duration::seconds(interval_secs as i64);
+        match client.incident_histogram(since).await {
+            Ok(histogram) => {
+                info!(
+                    "Incident report since {}: {} total, by severity {:?}",
+                    since.to_rfc3339(),
+                    histogram.total,
+                    histogram.by_severity
+                );
+                if tx.send(report_message(since, &histogram)).await.is_err() {
+                    break;
+                }
+            }
+            Err(e) => warn!("Incident report failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::IncidentSummaryRow;
+
+    #[test]
+    fn test_report_carries_period_and_counts() {
+        let since = DateTime::parse_from_rfc3339("2026-10-09T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let row = |severity: &str, incident_type: &str| IncidentSummaryRow {
+            severity: severity.to_string(),
+            incident_type: incident_type.to_string(),
+        };
+        let histogram = IncidentHistogram::from_rows(&[
+            row("critical", "ThermalRunaway"),
+            row("high", "Chatter"),
+            row("high", "Chatter"),
+        ]);
+
+        let message = report_message(since, &histogram);
+        assert_eq!(message.topic, INCIDENT_REPORT_TOPIC);
+        let payload: serde_json::Value = serde_json::from_str(&message.payload).unwrap();
+        assert_eq!(payload["since"], "2026-10-09T00:00:00+00:00");
+        assert_eq!(payload["histogram"]["total"], 3);
+        assert_eq!(payload["histogram"]["by_severity"]["high"], 2);
+        assert_eq!(payload["histogram"]["by_severity_and_type"]["critical"]["ThermalRunaway"], 1);
+    }
+}
@@ -0,0 +1,280 @@
+//! Live scheduling from Supabase machine state
+//!
+//! Fetches the `machines` table and the active `production_jobs`, maps them
+//! into scheduler machines and jobs, and periodically re-plans the pending
+//! jobs against the live fleet. Plans are published on `yieldops/schedule`.
+//! Built with the `scheduler` feature.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+use yieldops_scheduler::{OptimizationResult, SchedulerJob, SchedulerMachine, SchedulerOptimizer};
+
+use crate::supabase::SupabaseClient;
+use crate::types::MqttMessage;
+
+/// Topic each live plan is published on, as `OptimizationResult` JSON
+pub const SCHEDULE_TOPIC: &str = "yieldops/schedule";
+
+/// Seconds between scheduling passes unless `SCHEDULER_INTERVAL_SECS` is set
+const DEFAULT_INTERVAL_SECS: u64 = 300;
+
+/// Work already committed to a machine
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MachineLoad {
+    /// Jobs waiting in the machine's queue
+    pub queued: i32,
+    /// Hours left on the job the machine is running
+    pub busy_hours: f64,
+}
+
+/// Per-machine load from `QUEUED` and `RUNNING` job rows
+///
+/// A running job's remaining time is its start plus its estimated duration,
+/// less `now`; jobs without either count as finishing now.
+pub fn machine_loads(job_rows: &[Value], now: DateTime<Utc>) -> HashMap<String, MachineLoad> {
+    let mut loads: HashMap<String, MachineLoad> = HashMap::new();
+    for row in job_rows {
+        let Some(machine_id) = row.get("assigned_machine_id").and_then(Value::as_str) else {
+            continue;
+        };
+        let load = loads.entry(machine_id.to_string()).or_default();
+        match row.get("status").and_then(Value::as_str) {
+            Some("QUEUED") => load.queued += 1,
+            Some("RUNNING") => {
+                let started = row
+                    .get("actual_start_time")
+                    .and_then(Value::as_str)
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+                let minutes = row.get("estimated_duration_minutes").and_then(Value::as_f64);
+                if let (Some(started), Some(minutes)) = (started, minutes) {
+                    let elapsed = (now - started.with_timezone(&Utc)).num_seconds() as f64 / 3600.0;
+                    load.busy_hours = load.busy_hours.max(minutes / 60.0 - elapsed);
+                }
+            }
+            _ => {}
+        }
+    }
+    loads
+}
+
+/// Map one `machines` row into a scheduler machine
+///
+/// Rows without a `machine_id` are skipped. PostgREST may send DECIMAL
+/// columns (`efficiency_rating`) as strings, so both forms are accepted.
+/// Queue depth and availability come from the machine's entry in `loads`.
+pub fn machine_from_row(
+    row: &Value,
+    loads: &HashMap<String, MachineLoad>,
+) -> Option<SchedulerMachine> {
+    let machine_id = row.get("machine_id")?.as_str()?.to_string();
+    let text = |key: &str, default: &str| {
+        row.get(key)
+            .and_then(Value::as_str)
+            .unwrap_or(default)
+            .to_string()
+    };
+    let efficiency = row
+        .get("efficiency_rating")
+        .and_then(|v| v.as_f64().or_else(|| v.as_str()?.parse().ok()))
+        .unwrap_or(0.9);
+    let load = loads.get(&machine_id).copied().unwrap_or_default();
+
+    Some(SchedulerMachine::new(
+        machine_id.clone(),
+        text("name", &machine_id),
+        text("type", "unknown"),
+        text("status", "IDLE").to_uppercase(),
+        efficiency,
+        load.queued,
+        load.busy_hours,
+    ))
+}
+
+/// Map one `production_jobs` row into a scheduler job
+///
+/// `deadline` becomes hours from `now` (negative once overdue) and
+/// `estimated_duration_minutes` the job's processing time.
+pub fn job_from_row(row: &Value, now: DateTime<Utc>) -> Option<SchedulerJob> {
+    let job_id = row.get("job_id")?.as_str()?.to_string();
+    let int = |key: &str, default: i64| row.get(key).and_then(Value::as_i64).unwrap_or(default);
+    let deadline_hours = row
+        .get("deadline")
+        .and_then(Value::as_str)
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|deadline| (deadline.with_timezone(&Utc) - now).num_seconds() as f64 / 3600.0);
+
+    let mut job = SchedulerJob::new(
+        job_id.clone(),
+        row.get("job_name")
+            .and_then(Value::as_str)
+            .unwrap_or(&job_id)
+            .to_string(),
+        int("priority_level", 3) as i32,
+        int("wafer_count", 25) as i32,
+        row.get("is_hot_lot").and_then(Value::as_bool).unwrap_or(false),
+        row.get("recipe_type")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown")
+            .to_string(),
+        deadline_hours,
+    );
+    job.processing_hours = row
+        .get("estimated_duration_minutes")
+        .and_then(Value::as_f64)
+        .map(|minutes| minutes / 60.0);
+    Some(job)
+}
+
+/// Current machines from Supabase, ready for the optimizer
+pub async fn fetch_machines(client: &SupabaseClient) -> anyhow::Result<Vec<SchedulerMachine>> {
+    let rows = client.list_machines().await?;
+    let loads = machine_loads(&client.list_jobs(&["QUEUED", "RUNNING"]).await?, Utc::now());
+    Ok(rows.iter().filter_map(|row| machine_from_row(row, &loads)).collect())
+}
+
+/// Jobs waiting for a machine
+pub async fn fetch_pending_jobs(client: &SupabaseClient) -> anyhow::Result<Vec<SchedulerJob>> {
+    let rows = client.list_jobs(&["PENDING"]).await?;
+    let now = Utc::now();
+    Ok(rows.iter().filter_map(|row| job_from_row(row, now)).collect())
+}
+
+/// Optimize the pending jobs against the live fleet
+///
+/// Machines that are down or in maintenance come through as such and are
+/// excluded by the optimizer's availability check.
+pub async fn optimize_live(
+    client: &SupabaseClient,
+    optimizer: &SchedulerOptimizer,
+) -> anyhow::Result<OptimizationResult> {
+    let jobs = fetch_pending_jobs(client).await?;
+    let machines = fetch_machines(client).await?;
+    Ok(optimizer.optimize_native(&jobs, &machines, jobs.len()))
+}
+
+/// Re-plan every `SCHEDULER_INTERVAL_SECS` and send each plan to `tx`
+///
+/// Runs until the receiving end is dropped; a failed pass is logged and
+/// retried on the next tick.
+pub async fn run_live_scheduling(client: SupabaseClient, tx: mpsc::Sender<MqttMessage>) {
+    let interval_secs = std::env::var("SCHEDULER_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+    let optimizer = SchedulerOptimizer::new(None);
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+
+    loop {
+        ticker.tick().await;
+        match optimize_live(&client, &optimizer).await {
+            Ok(plan) => {
+                info!(
+                    "Live schedule: {} assigned, {} unassigned, score {:.2}",
+                    plan.assignments.len(),
+                    plan.unassigned_jobs.len(),
+                    plan.total_score
+                );
+                let message = MqttMessage {
+                    topic: SCHEDULE_TOPIC.to_string(),
+                    payload: plan.to_json(),
+                };
+                if tx.send(message).await.is_err() {
+                    break;
+                }
+            }
+            Err(e) => warn!("Live scheduling pass failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_supabase_rows_map_to_scheduler_machines() {
+        let now = DateTime::parse_from_rfc3339("2026-10-16T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let litho_01 = "6f1c2a9e-0000-4000-8000-000000000001";
+        let rows = json!([
+            {
+                "machine_id": litho_01,
+                "name": "LITHO-01",
+                "type": "lithography",
+                "status": "RUNNING",
+                "efficiency_rating": "0.95",
+                "location_zone": "ZONE-A",
+                "current_wafer_count": 25
+            },
+            {
+                "machine_id": "6f1c2a9e-0000-4000-8000-000000000002",
+                "name": "LITHO-02",
+                "type": "lithography",
+                "status": "down",
+                "efficiency_rating": 0.99,
+                "current_wafer_count": 0
+            },
+            { "name": "no id" }
+        ]);
+        // LITHO-01 is 30 min into a 90 min job with two more queued behind it
+        let active = json!([
+            {
+                "job_id": "job-0",
+                "status": "RUNNING",
+                "assigned_machine_id": litho_01,
+                "actual_start_time": "2026-10-16T11:30:00+00:00",
+                "estimated_duration_minutes": 90
+            },
+            { "job_id": "job-a", "status": "QUEUED", "assigned_machine_id": litho_01 },
+            { "job_id": "job-b", "status": "QUEUED", "assigned_machine_id": litho_01 }
+        ]);
+        let loads = machine_loads(active.as_array().unwrap(), now);
+        let machines: Vec<SchedulerMachine> = rows
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|row| machine_from_row(row, &loads))
+            .collect();
+
+        assert_eq!(machines.len(), 2);
+        let litho = &machines[0];
+        assert_eq!(litho.name, "LITHO-01");
+        assert_eq!(litho.machine_type, "lithography");
+        assert_eq!(litho.status, "RUNNING");
+        assert_eq!(litho.efficiency_rating, 0.95);
+        assert_eq!(litho.current_queue_depth, 2);
+        assert!((litho.estimated_available_hours - 1.0).abs() < 1e-9);
+        assert_eq!(machines[1].status, "DOWN");
+        assert_eq!(machines[1].current_queue_depth, 0);
+
+        // The better-rated tool is down, so the job goes to LITHO-01
+        let row = json!({
+            "job_id": "job-1",
+            "job_name": "LOT-1",
+            "priority_level": 2,
+            "wafer_count": 25,
+            "status": "PENDING",
+            "recipe_type": "lithography",
+            "estimated_duration_minutes": 120,
+            "deadline": "2026-10-16T18:00:00+00:00",
+            "is_hot_lot": false
+        });
+        let job = job_from_row(&row, now).unwrap();
+        assert_eq!(job.deadline_hours, Some(6.0));
+        assert_eq!(job.processing_hours, Some(2.0));
+
+        let result = SchedulerOptimizer::new(None).optimize_native(&[job], &machines, 10);
+        assert_eq!(result.assignments.len(), 1);
+        assert_eq!(result.assignments[0].machine_name, "LITHO-01");
+        // Starts once the running job is done
+        assert!((result.assignments[0].estimated_start_hours - 1.0).abs() < 1e-9);
+    }
+}
@@ -1,13 +1,22 @@
 //! Supabase client for the Aegis Bridge
 //!
-//! Handles all interactions with Supabase REST API
+//! Handles all interactions with Supabase REST API, plus the Realtime
+//! websocket used for threshold changes
 
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
 use reqwest::{Client, header};
 use serde_json::json;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tracing::{debug, error, info};
 
 use crate::types::*;
 
+/// Realtime drops sockets that stay silent for more than ~30 s
+const REALTIME_HEARTBEAT: Duration = Duration::from_secs(25);
+
 pub struct SupabaseClient {
     client: Client,
     url: String,
@@ -165,6 +174,149 @@ impl SupabaseClient {
         Ok(machines)
     }
 
+    /// List production jobs in any of `statuses` (e.g. "PENDING")
+    #[cfg(feature = "scheduler")]
+    pub async fn list_jobs(&self, statuses: &[&str]) -> anyhow::Result<Vec<serde_json::Value>> {
+        let url = format!("{}/rest/v1/production_jobs", self.url);
+
+        let response = self.client
+            .get(&url)
+            .query(&[
+                ("select", "*".to_string()),
+                ("status", format!("in.({})", statuses.join(","))),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            error!("Failed to list jobs: {}", error_text);
+            return Err(anyhow::anyhow!("Supabase query failed: {}", error_text));
+        }
+
+        let jobs: Vec<serde_json::Value> = response.json().await?;
+        Ok(jobs)
+    }
+
+    /// Count incidents since `since`, grouped by severity and type
+    pub async fn incident_histogram(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> anyhow::Result<IncidentHistogram> {
+        let url = format!("{}/rest/v1/aegis_incidents", self.url);
+        
+        let response = self.client
+            .get(&url)
+            .query(&[
+                ("select", "severity,incident_type".to_string()),
+                ("timestamp", format!("gte.{}", since.to_rfc3339())),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            error!("Failed to fetch incidents: {}", error_text);
+            return Err(anyhow::anyhow!("Supabase query failed: {}", error_text));
+        }
+
+        let rows: Vec<IncidentSummaryRow> = response.json().await?;
+        Ok(IncidentHistogram::from_rows(&rows))
+    }
+
+    /// Stream threshold changes (`aegis_agent_config`) and incident
+    /// resolutions (`aegis_incidents`) over Supabase Realtime
+    ///
+    /// Runs until the websocket closes or `tx` is dropped; the caller is
+    /// expected to reconnect.
+    pub async fn subscribe_sentinel_updates(
+        &self,
+        tx: mpsc::Sender<SentinelUpdate>,
+    ) -> anyhow::Result<()> {
+        let ws_url = format!(
+            "{}/realtime/v1/websocket?apikey={}&vsn=1.0.0",
+            self.url
+                .replacen("https://", "wss://", 1)
+                .replacen("http://", "ws://", 1),
+            self.api_key
+        );
+        let (socket, _) = tokio_tungstenite::connect_async(ws_url).await?;
+        let (mut write, mut read) = socket.split();
+
+        let join = json!({
+            "topic": "realtime:aegis-sentinel",
+            "event": "phx_join",
+            "payload": {
+                "config": {
+                    "postgres_changes": [
+                        { "event": "*", "schema": "public", "table": "aegis_agent_config" },
+                        { "event": "UPDATE", "schema": "public", "table": "aegis_incidents" }
+                    ]
+                },
+                "access_token": self.api_key,
+            },
+            "ref": "1",
+        });
+        write.send(WsMessage::Text(join.to_string())).await?;
+        info!("Subscribed to aegis_agent_config and aegis_incidents changes");
+
+        let mut heartbeat = tokio::time::interval(REALTIME_HEARTBEAT);
+        let mut next_ref: u64 = 2;
+        loop {
+            tokio::select! {
+                _ = heartbeat.tick() => {
+                    let beat = json!({
+                        "topic": "phoenix",
+                        "event": "heartbeat",
+                        "payload": {},
+                        "ref": next_ref.to_string(),
+                    });
+                    next_ref += 1;
+                    write.send(WsMessage::Text(beat.to_string())).await?;
+                }
+                message = read.next() => {
+                    match message {
+                        Some(Ok(WsMessage::Text(text))) => {
+                            if let Some(update) = SentinelUpdate::from_realtime(&text) {
+                                debug!("Sentinel update for {}", update.machine_id());
+                                if tx.send(update).await.is_err() {
+                                    return Ok(());
+                                }
+                            }
+                        }
+                        Some(Ok(WsMessage::Close(_))) | None => {
+                            return Err(anyhow::anyhow!("Realtime connection closed"));
+                        }
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => return Err(e.into()),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stream threshold changes (`aegis_agent_config`) only
+    ///
+    /// Same connection and lifetime as [`Self::subscribe_sentinel_updates`],
+    /// with incident resolutions left out.
+    pub async fn subscribe_config_changes(
+        &self,
+        tx: mpsc::Sender<ConfigChange>,
+    ) -> anyhow::Result<()> {
+        let (update_tx, mut updates) = mpsc::channel(32);
+        let forward = async move {
+            while let Some(update) = updates.recv().await {
+                if let SentinelUpdate::Config(change) = update {
+                    if tx.send(change).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        };
+        let (subscribed, ()) = tokio::join!(self.subscribe_sentinel_updates(update_tx), forward);
+        subscribed
+    }
+
     /// Check database health
     pub async fn health_check(&self) -> anyhow::Result<bool> {
         let url = format!("{}/rest/v1/machines?select=count&limit=1", self.url);
@@ -182,6 +334,28 @@ impl SupabaseClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_incident_histogram_groups_rows() {
+        let rows: Vec<IncidentSummaryRow> = serde_json::from_value(json!([
+            { "severity": "critical", "incident_type": "ThermalRunaway" },
+            { "severity": "high", "incident_type": "Chatter" },
+            { "severity": "high", "incident_type": "ThermalRunaway" },
+            { "severity": "critical", "incident_type": "ThermalRunaway" },
+            { "severity": "medium", "incident_type": "Contamination" },
+        ])).unwrap();
+
+        let histogram = IncidentHistogram::from_rows(&rows);
+
+        assert_eq!(histogram.total, 5);
+        assert_eq!(histogram.by_severity["critical"], 2);
+        assert_eq!(histogram.by_severity["high"], 2);
+        assert_eq!(histogram.by_severity["medium"], 1);
+        assert!(!histogram.by_severity.contains_key("low"));
+        assert_eq!(histogram.by_type["ThermalRunaway"], 3);
+        assert_eq!(histogram.by_severity_and_type["high"]["Chatter"], 1);
+        assert_eq!(histogram.by_severity_and_type["critical"]["ThermalRunaway"], 2);
+    }
+
     // Note: These tests require a running Supabase instance
     // Run with: cargo test -- --ignored
     
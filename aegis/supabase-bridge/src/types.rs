@@ -1,6 +1,11 @@
 //! Type definitions for the Supabase Bridge
 
+use aegis_common::units::{check_unit, UnitCheck};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::warn;
+
+use crate::anomaly::ANOMALY_Z_THRESHOLD;
 
 /// MQTT message structure
 #[derive(Debug, Clone)]
@@ -19,6 +24,34 @@ pub struct TelemetryPayload {
     pub status: Option<MachineStatusUpdate>,
     pub anomaly_detected: Option<bool>,
     pub anomaly_score: Option<f64>,
+    /// Declared unit per metric (e.g. "temperature" -> "F")
+    #[serde(default)]
+    pub units: Option<std::collections::HashMap<String, String>>,
+}
+
+impl TelemetryPayload {
+    /// Read a metric converted to the unit the `sensor_readings` columns use
+    ///
+    /// Same rules as the Sentinel: a value whose declared unit can't be
+    /// converted is logged and kept as sent.
+    pub fn metric(&self, name: &str) -> Option<f64> {
+        let value = self.metrics.get(name).copied()?;
+        let Some(unit) = self.units.as_ref().and_then(|u| u.get(name)) else {
+            return Some(value);
+        };
+
+        match check_unit(name, unit, value) {
+            UnitCheck::Canonical => Some(value),
+            UnitCheck::Converted { value, .. } => Some(value),
+            UnitCheck::Mismatch { expected } => {
+                warn!(
+                    "{}: {} reported in {} but expected {} - stored as sent",
+                    self.machine_id, name, unit, expected
+                );
+                Some(value)
+            }
+        }
+    }
 }
 
 /// Incident payload from Sentinel agents
@@ -68,6 +101,37 @@ pub struct SensorReading {
     pub recorded_at: String,
 }
 
+impl SensorReading {
+    /// Build the row for one telemetry message
+    ///
+    /// `z_score` is the bridge's own rolling score; a score or anomaly flag
+    /// sent by the machine takes precedence.
+    pub fn from_telemetry(telemetry: &TelemetryPayload, agent_type: &str, z_score: Option<f64>) -> Self {
+        // Every column in its canonical unit
+        let metric = |name: &str| telemetry.metric(name);
+
+        Self {
+            reading_id: uuid::Uuid::new_v4().to_string(),
+            machine_id: telemetry.machine_id.clone(),
+            temperature: metric("temperature"),
+            vibration: metric("vibration"),
+            pressure: metric("pressure"),
+            airflow: metric("airflow_mps"),
+            particles_0_5um: metric("particles_0_5um"),
+            usg_impedance: metric("usg_impedance"),
+            bond_time_ms: metric("bond_time_ms"),
+            shear_strength: metric("shear_strength_g"),
+            pressure_diff_pa: metric("pressure_diff_pa"),
+            is_anomaly: telemetry.anomaly_detected.unwrap_or_else(|| {
+                z_score.is_some_and(|z| z >= ANOMALY_Z_THRESHOLD)
+            }),
+            anomaly_score: telemetry.anomaly_score.or(z_score),
+            agent_type: agent_type.to_string(),
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
 /// Aegis incident for Supabase
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AegisIncident {
@@ -90,6 +154,154 @@ pub struct AegisIncident {
     pub operator_notes: Option<String>,
 }
 
+/// Incident row as fetched for reporting (only the grouped columns)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncidentSummaryRow {
+    pub severity: String,
+    pub incident_type: String,
+}
+
+/// Incident counts for trend reporting
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct IncidentHistogram {
+    pub total: usize,
+    pub by_severity: BTreeMap<String, usize>,
+    pub by_type: BTreeMap<String, usize>,
+    /// severity -> incident_type -> count
+    pub by_severity_and_type: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+impl IncidentHistogram {
+    /// Group incident rows by severity and type
+    pub fn from_rows(rows: &[IncidentSummaryRow]) -> Self {
+        let mut histogram = Self::default();
+        for row in rows {
+            histogram.total += 1;
+            *histogram.by_severity.entry(row.severity.clone()).or_insert(0) += 1;
+            *histogram.by_type.entry(row.incident_type.clone()).or_insert(0) += 1;
+            *histogram
+                .by_severity_and_type
+                .entry(row.severity.clone())
+                .or_default()
+                .entry(row.incident_type.clone())
+                .or_insert(0) += 1;
+        }
+        histogram
+    }
+}
+
+/// Threshold change for one machine, read from `aegis_agent_config`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub machine_id: String,
+    /// Partial agent config; only the keys present are changed
+    pub thresholds: serde_json::Value,
+}
+
+impl ConfigChange {
+    /// Extract a change from a Supabase Realtime `postgres_changes` message
+    ///
+    /// Returns `None` for heartbeats, join replies, deletes and rows
+    /// without a threshold object.
+    pub fn from_realtime(message: &str) -> Option<Self> {
+        let message: serde_json::Value = serde_json::from_str(message).ok()?;
+        if message.get("event")?.as_str()? != "postgres_changes" {
+            return None;
+        }
+        let data = message.get("payload")?.get("data")?;
+        if !matches!(data.get("type")?.as_str()?, "INSERT" | "UPDATE") {
+            return None;
+        }
+        let record = data.get("record")?;
+        let thresholds = record.get("thresholds")?;
+        if !thresholds.is_object() {
+            return None;
+        }
+        Some(Self {
+            machine_id: record.get("machine_id")?.as_str()?.to_string(),
+            thresholds: thresholds.clone(),
+        })
+    }
+
+    /// MQTT message the Sentinel hot-reloads from
+    pub fn to_mqtt(&self) -> MqttMessage {
+        MqttMessage {
+            topic: format!("aegis/config/{}", self.machine_id),
+            payload: self.thresholds.to_string(),
+        }
+    }
+}
+
+/// Incident an operator resolved, read from `aegis_incidents`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncidentResolved {
+    pub machine_id: String,
+}
+
+impl IncidentResolved {
+    /// Extract a resolution from a Supabase Realtime `postgres_changes` message
+    ///
+    /// Only an update that marks the incident resolved counts; later edits
+    /// to an already resolved row (when the old row is known) are ignored.
+    pub fn from_realtime(message: &str) -> Option<Self> {
+        let message: serde_json::Value = serde_json::from_str(message).ok()?;
+        if message.get("event")?.as_str()? != "postgres_changes" {
+            return None;
+        }
+        let data = message.get("payload")?.get("data")?;
+        if data.get("table")?.as_str()? != "aegis_incidents" || data.get("type")?.as_str()? != "UPDATE" {
+            return None;
+        }
+        let resolved = |row: Option<&serde_json::Value>| {
+            row.and_then(|row| row.get("resolved")).and_then(|v| v.as_bool()) == Some(true)
+        };
+        if !resolved(data.get("record")) || resolved(data.get("old_record")) {
+            return None;
+        }
+        Some(Self {
+            machine_id: data.get("record")?.get("machine_id")?.as_str()?.to_string(),
+        })
+    }
+
+    /// MQTT message that makes the Sentinel reset the machine's counters
+    pub fn to_mqtt(&self) -> MqttMessage {
+        MqttMessage {
+            topic: format!("aegis/{}/resolve", self.machine_id),
+            payload: "{}".to_string(),
+        }
+    }
+}
+
+/// Dashboard-side change relayed to the Sentinel
+#[derive(Debug, Clone, PartialEq)]
+pub enum SentinelUpdate {
+    Config(ConfigChange),
+    Resolved(IncidentResolved),
+}
+
+impl SentinelUpdate {
+    /// Extract either kind of change from a Supabase Realtime message
+    pub fn from_realtime(message: &str) -> Option<Self> {
+        ConfigChange::from_realtime(message)
+            .map(Self::Config)
+            .or_else(|| IncidentResolved::from_realtime(message).map(Self::Resolved))
+    }
+
+    pub fn machine_id(&self) -> &str {
+        match self {
+            Self::Config(change) => &change.machine_id,
+            Self::Resolved(resolved) => &resolved.machine_id,
+        }
+    }
+
+    pub fn to_mqtt(&self) -> MqttMessage {
+        match self {
+            Self::Config(change) => change.to_mqtt(),
+            Self::Resolved(resolved) => resolved.to_mqtt(),
+        }
+    }
+}
+
 /// Agent types for the Sand-to-Package platform
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AgentType {
@@ -116,3 +328,92 @@ impl std::fmt::Display for AgentType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fahrenheit_temperature_converted() {
+        let payload: TelemetryPayload = serde_json::from_str(
+            r#"{
+                "timestamp": "2026-02-07T00:00:00Z",
+                "machine_id": "CNC-001",
+                "metrics": {"temperature": 149.0, "vibration": 0.5},
+                "units": {"temperature": "°F", "vibration": "g"}
+            }"#,
+        )
+        .unwrap();
+
+        let celsius = payload.metric("temperature").unwrap();
+        assert!((celsius - 65.0).abs() < 1e-9);
+        // Unconvertible: kept as sent, like the Sentinel does
+        assert_eq!(payload.metric("vibration"), Some(0.5));
+        assert_eq!(payload.metric("pressure"), None);
+    }
+
+    #[test]
+    fn test_config_change_published_to_machine_topic() {
+        let event = r#"{
+            "topic": "realtime:aegis-config",
+            "event": "postgres_changes",
+            "payload": {
+                "data": {
+                    "type": "UPDATE",
+                    "schema": "public",
+                    "table": "aegis_agent_config",
+                    "record": {
+                        "machine_id": "CNC-001",
+                        "thresholds": {"vibration_critical": 9.0},
+                        "updated_at": "2026-02-07T00:00:00Z"
+                    }
+                },
+                "ids": [1]
+            },
+            "ref": null
+        }"#;
+
+        let change = ConfigChange::from_realtime(event).unwrap();
+        let message = change.to_mqtt();
+        assert_eq!(message.topic, "aegis/config/CNC-001");
+        let payload: serde_json::Value = serde_json::from_str(&message.payload).unwrap();
+        assert_eq!(payload, serde_json::json!({"vibration_critical": 9.0}));
+
+        let heartbeat = r#"{"topic":"phoenix","event":"phx_reply","payload":{"status":"ok","response":{}},"ref":"2"}"#;
+        assert!(ConfigChange::from_realtime(heartbeat).is_none());
+    }
+
+    #[test]
+    fn test_resolved_incident_published_to_sentinel() {
+        let event = |old_resolved: bool, resolved: bool| {
+            serde_json::json!({
+                "topic": "realtime:aegis-sentinel",
+                "event": "postgres_changes",
+                "payload": {
+                    "data": {
+                        "type": "UPDATE",
+                        "schema": "public",
+                        "table": "aegis_incidents",
+                        "record": {"id": "i-1", "machine_id": "BOND-02", "resolved": resolved},
+                        "old_record": {"id": "i-1", "resolved": old_resolved}
+                    },
+                    "ids": [1]
+                },
+                "ref": null
+            })
+            .to_string()
+        };
+
+        match SentinelUpdate::from_realtime(&event(false, true)) {
+            Some(update @ SentinelUpdate::Resolved(_)) => {
+                assert_eq!(update.machine_id(), "BOND-02");
+                assert_eq!(update.to_mqtt().topic, "aegis/BOND-02/resolve");
+            }
+            other => panic!("Expected a resolution, got {:?}", other),
+        }
+
+        // Still open, or already resolved before this edit
+        assert!(SentinelUpdate::from_realtime(&event(false, false)).is_none());
+        assert!(SentinelUpdate::from_realtime(&event(true, true)).is_none());
+    }
+}
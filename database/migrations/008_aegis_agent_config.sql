@@ -0,0 +1,33 @@
+-- =====================================================
+-- Aegis Agent Threshold Configuration
+-- Per-machine detection thresholds, pushed live to the
+-- Sentinel through the Supabase bridge (Realtime -> MQTT
+-- topic aegis/config/{machine_id})
+-- =====================================================
+CREATE TABLE IF NOT EXISTS aegis_agent_config (
+    machine_id VARCHAR(100) PRIMARY KEY REFERENCES aegis_agents(machine_id) ON DELETE CASCADE,
+    -- Partial agent config, e.g. {"vibration_critical": 9.0}
+    thresholds JSONB NOT NULL DEFAULT '{}'::jsonb,
+    updated_by VARCHAR(100),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+ALTER TABLE aegis_agent_config ENABLE ROW LEVEL SECURITY;
+CREATE POLICY "Allow public read" ON aegis_agent_config FOR
+SELECT USING (true);
+-- Thresholds drive live equipment decisions: only the service role writes them
+CREATE POLICY "Allow service insert" ON aegis_agent_config FOR
+INSERT WITH CHECK (auth.role() = 'service_role');
+CREATE POLICY "Allow service update" ON aegis_agent_config FOR
+UPDATE USING (auth.role() = 'service_role') WITH CHECK (auth.role() = 'service_role');
+CREATE OR REPLACE FUNCTION touch_aegis_agent_config() RETURNS TRIGGER AS $$ BEGIN NEW.updated_at := NOW();
+RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+DROP TRIGGER IF EXISTS trg_touch_aegis_agent_config ON aegis_agent_config;
+CREATE TRIGGER trg_touch_aegis_agent_config BEFORE
+UPDATE ON aegis_agent_config FOR EACH ROW EXECUTE FUNCTION touch_aegis_agent_config();
+DO $$ BEGIN ALTER PUBLICATION supabase_realtime
+ADD TABLE aegis_agent_config;
+EXCEPTION
+WHEN duplicate_object THEN RAISE NOTICE 'aegis_agent_config already in publication';
+END $$;
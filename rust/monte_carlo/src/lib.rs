@@ -3,9 +3,10 @@
 //! High-performance parallel simulation using rayon.
 //! Provides 10-50x speedup over Python/NumPy implementation.
 
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use rand::prelude::*;
-use rand_distr::Normal;
+use rand_distr::{Exp, LogNormal, Normal, Triangular};
 use rayon::prelude::*;
 
 /// Machine configuration for simulation
@@ -26,6 +27,59 @@ pub struct MachineConfig {
     pub downtime_prob: f64,
     #[pyo3(get, set)]
     pub repair_time_hours: f64,
+    /// Burn-in period at the start of the horizon with elevated downtime
+    #[pyo3(get, set)]
+    pub infant_mortality_days: Option<usize>,
+    /// Day from which the machine is wearing out, with elevated downtime
+    #[pyo3(get, set)]
+    pub wearout_start_day: Option<usize>,
+    /// Downtime probability multiplier in the burn-in and wear-out periods
+    #[pyo3(get, set)]
+    pub bathtub_factor: f64,
+    /// Markov stickiness of the daily downtime state, in [0, 1): 0 draws each
+    /// day independently, higher values turn downtime into multi-day runs
+    /// while keeping `downtime_prob` as the long-run fraction of days down
+    #[pyo3(get, set)]
+    pub downtime_persistence: f64,
+    /// Mean time between failures; when set, failures arrive as a Poisson
+    /// process in continuous time instead of daily `downtime_prob` draws
+    #[pyo3(get, set)]
+    pub mtbf_hours: Option<f64>,
+    /// Mean repair time under the MTBF model (defaults to `repair_time_hours`)
+    #[pyo3(get, set)]
+    pub mttr_hours: Option<f64>,
+    /// AR(1) coefficient of daily efficiency, in [0, 1): 0 draws each day
+    /// independently, higher values make efficiency drift smoothly while
+    /// keeping `efficiency_std` as its spread
+    #[pyo3(get, set)]
+    pub efficiency_phi: f64,
+    /// Efficiency distribution family, matched to `efficiency_mean` and
+    /// `efficiency_std`: "normal", "lognormal" (right-skewed, never negative)
+    /// or "triangular" (symmetric, bounded)
+    #[pyo3(get, set)]
+    pub distribution: String,
+}
+
+/// Distribution families accepted in `MachineConfig.distribution`
+const EFFICIENCY_DISTRIBUTIONS: [&str; 3] = ["normal", "lognormal", "triangular"];
+
+/// State of one machine carried across the days of a simulation
+#[derive(Clone, Copy, Debug, Default)]
+struct MachineState {
+    downtime: DowntimeState,
+    /// Previous day's efficiency deviation from the mean (AR(1) model)
+    efficiency_deviation: Option<f64>,
+}
+
+/// Downtime state of one machine, carried across the days of a simulation
+#[derive(Clone, Copy, Debug, Default)]
+struct DowntimeState {
+    /// Down on the previous day (daily `downtime_prob` model)
+    down: bool,
+    /// Hour of the next failure, drawn on first use (MTBF model)
+    next_failure: Option<f64>,
+    /// Hour the current repair finishes (MTBF model)
+    repair_end: f64,
 }
 
 #[pymethods]
@@ -48,8 +102,207 @@ impl MachineConfig {
             efficiency_std,
             downtime_prob,
             repair_time_hours,
+            infant_mortality_days: None,
+            wearout_start_day: None,
+            bathtub_factor: 3.0,
+            downtime_persistence: 0.0,
+            mtbf_hours: None,
+            mttr_hours: None,
+            efficiency_phi: 0.0,
+            distribution: "normal".to_string(),
+        }
+    }
+}
+
+impl MachineConfig {
+    /// Reject NaN/Inf parameters, which would silently poison every statistic,
+    /// a persistence or efficiency coefficient outside [0, 1), and an unknown
+    /// distribution family
+    fn check_finite(&self) -> PyResult<()> {
+        if !EFFICIENCY_DISTRIBUTIONS.contains(&self.distribution.as_str()) {
+            return Err(PyValueError::new_err(format!(
+                "{}: distribution must be one of {} (got {:?})",
+                self.name,
+                EFFICIENCY_DISTRIBUTIONS.join(", "),
+                self.distribution
+            )));
+        }
+        if !(0.0..1.0).contains(&self.downtime_persistence) {
+            return Err(PyValueError::new_err(format!(
+                "{}: downtime_persistence must be in [0, 1) (got {})",
+                self.name, self.downtime_persistence
+            )));
+        }
+        if !(0.0..1.0).contains(&self.efficiency_phi) {
+            return Err(PyValueError::new_err(format!(
+                "{}: efficiency_phi must be in [0, 1) (got {})",
+                self.name, self.efficiency_phi
+            )));
+        }
+        if self.mtbf_hours.is_some_and(|h| !(h.is_finite() && h > 0.0)) {
+            return Err(PyValueError::new_err(format!(
+                "{}: mtbf_hours must be positive (got {:?})",
+                self.name, self.mtbf_hours
+            )));
+        }
+        if self
+            .mttr_hours
+            .is_some_and(|h| !(h.is_finite() && h >= 0.0))
+        {
+            return Err(PyValueError::new_err(format!(
+                "{}: mttr_hours must be non-negative (got {:?})",
+                self.name, self.mttr_hours
+            )));
+        }
+        let fields = [
+            ("base_throughput", self.base_throughput),
+            ("efficiency_mean", self.efficiency_mean),
+            ("efficiency_std", self.efficiency_std),
+            ("downtime_prob", self.downtime_prob),
+            ("repair_time_hours", self.repair_time_hours),
+            ("bathtub_factor", self.bathtub_factor),
+        ];
+        match fields.iter().find(|(_, value)| !value.is_finite()) {
+            Some((field, value)) => Err(PyValueError::new_err(format!(
+                "{}: {} must be finite (got {})",
+                self.name, field, value
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Downtime probability on a given day (bathtub curve)
+    fn downtime_prob_on(&self, day: usize) -> f64 {
+        let burn_in = self.infant_mortality_days.is_some_and(|days| day < days);
+        let wearout = self.wearout_start_day.is_some_and(|start| day >= start);
+        if burn_in || wearout {
+            (self.downtime_prob * self.bathtub_factor).min(1.0)
+        } else {
+            self.downtime_prob
+        }
+    }
+
+    /// Draw whether the machine is down on `day`, given the previous day's state
+    ///
+    /// With persistence ρ and marginal p, a down machine stays down with
+    /// p + ρ(1 - p) and an up machine goes down with p(1 - ρ), so p stays the
+    /// stationary probability and only the run lengths change.
+    fn draw_down<R: Rng>(&self, day: usize, was_down: bool, rng: &mut R) -> bool {
+        let p = self.downtime_prob_on(day);
+        let rho = self.downtime_persistence;
+        let prob = if was_down {
+            p + rho * (1.0 - p)
+        } else {
+            p * (1.0 - rho)
+        };
+        rng.gen::<f64>() < prob
+    }
+
+    /// Draw a day's efficiency around `mean`
+    ///
+    /// The deviation from the mean follows an AR(1) process: φ times the
+    /// previous deviation plus an innovation scaled by √(1 − φ²), so the
+    /// long-run spread stays `efficiency_std`. The first draw (`deviation` of
+    /// `None`) takes the full spread. The innovation comes from the machine's
+    /// distribution family; the unclamped deviation is carried over.
+    fn sample_efficiency<R: Rng>(
+        &self,
+        mean: f64,
+        deviation: &mut Option<f64>,
+        rng: &mut R,
+    ) -> f64 {
+        let phi = self.efficiency_phi;
+        let (carried, scale) = match *deviation {
+            Some(previous) => (phi * previous, (1.0 - phi * phi).sqrt()),
+            None => (0.0, 1.0),
+        };
+        let efficiency = self.draw_efficiency(mean, self.efficiency_std * scale, rng) + carried;
+        *deviation = Some(efficiency - mean);
+        efficiency.clamp(0.3, 1.0)
+    }
+
+    /// Unclamped efficiency draw with the given mean and standard deviation
+    ///
+    /// Lognormal matches the mean and coefficient of variation; triangular is
+    /// symmetric around the mean with half-width std × √6. Parameters the
+    /// family can't take fall back to N(0.9, 0.05).
+    fn draw_efficiency<R: Rng>(&self, mean: f64, std: f64, rng: &mut R) -> f64 {
+        let sample = match self.distribution.as_str() {
+            "lognormal" if mean > 0.0 => LogNormal::from_mean_cv(mean, std / mean)
+                .ok()
+                .map(|d| d.sample(rng)),
+            "lognormal" => None,
+            "triangular" => {
+                let half_width = std * 6f64.sqrt();
+                Triangular::new(mean - half_width, mean + half_width, mean)
+                    .ok()
+                    .map(|d| d.sample(rng))
+            }
+            _ => Normal::new(mean, std).ok().map(|d| d.sample(rng)),
+        };
+        sample.unwrap_or_else(|| Normal::new(0.9, 0.05).unwrap().sample(rng))
+    }
+
+    /// Mean repair time under the MTBF model
+    fn mean_repair_hours(&self) -> f64 {
+        self.mttr_hours.unwrap_or(self.repair_time_hours)
+    }
+
+    /// Long-run fraction of time down
+    fn unavailability(&self) -> f64 {
+        match self.mtbf_hours {
+            Some(mtbf) => {
+                let mttr = self.mean_repair_hours();
+                mttr / (mtbf + mttr)
+            }
+            None => self.downtime_prob,
         }
     }
+
+    /// Fraction of `day` the machine is up
+    ///
+    /// Under the MTBF model, failures arrive with exponential inter-arrival
+    /// times and each takes an exponential repair time, so a long repair
+    /// spills into the following days. Without MTBF, falls back to the daily
+    /// `downtime_prob` draw (whole day up or down).
+    fn uptime_fraction<R: Rng>(&self, day: usize, state: &mut DowntimeState, rng: &mut R) -> f64 {
+        let Some(mtbf) = self.mtbf_hours else {
+            state.down = self.draw_down(day, state.down, rng);
+            return if state.down { 0.0 } else { 1.0 };
+        };
+        let exp_hours = |mean: f64, rng: &mut R| {
+            if mean > 0.0 {
+                Exp::new(1.0 / mean).map_or(0.0, |exp| exp.sample(rng))
+            } else {
+                0.0
+            }
+        };
+        let mttr = self.mean_repair_hours();
+
+        let end = (day + 1) as f64 * 24.0;
+        let mut t = day as f64 * 24.0;
+        let mut next_failure = match state.next_failure {
+            Some(hour) => hour,
+            None => t + exp_hours(mtbf, rng),
+        };
+        let mut up_hours = 0.0;
+        loop {
+            t = t.max(state.repair_end);
+            if t >= end {
+                break;
+            }
+            if next_failure >= end {
+                up_hours += end - t;
+                break;
+            }
+            up_hours += next_failure - t;
+            state.repair_end = next_failure + exp_hours(mttr, rng);
+            t = next_failure;
+            next_failure = state.repair_end + exp_hours(mtbf, rng);
+        }
+        state.next_failure = Some(next_failure);
+        up_hours / 24.0
+    }
 }
 
 /// Simulation result with statistics
@@ -74,10 +327,43 @@ pub struct SimulationResult {
     pub confidence_upper: f64,
     #[pyo3(get)]
     pub daily_throughputs: Vec<f64>,
+    /// Per-day percentile bands (only with `SimulationConfig.daily_percentiles`)
+    #[pyo3(get)]
+    pub daily_p5: Option<Vec<f64>>,
+    #[pyo3(get)]
+    pub daily_p50: Option<Vec<f64>>,
+    #[pyo3(get)]
+    pub daily_p95: Option<Vec<f64>>,
+    /// Machine with the lowest simulated contribution (`machine_stats`)
     #[pyo3(get)]
     pub bottleneck_machine: String,
+    /// Machine with the largest `contribution × (efficiency_std +
+    /// unavailability)`: the output most at risk, not the smallest
+    /// (unavailability is `downtime_prob`, or mttr / (mtbf + mttr))
+    #[pyo3(get)]
+    pub risk_weighted_bottleneck: String,
     #[pyo3(get)]
     pub machine_contributions: Vec<(String, String, f64)>, // (id, name, contribution)
+    /// (id, mean fraction of the horizon up, mean simulated output) per
+    /// machine, before any demand cap
+    #[pyo3(get)]
+    pub machine_stats: Vec<(String, f64, f64)>,
+    /// Total output of every simulation, sorted ascending (for `combine`)
+    #[pyo3(get)]
+    pub totals: Vec<f64>,
+    /// Lot size the lot percentiles use (from `SimulationConfig`)
+    #[pyo3(get)]
+    pub wafers_per_lot: Option<f64>,
+    /// p5/p50/p95/p99 in lots, rounded to `LOT_DECIMALS` (only with
+    /// `SimulationConfig.wafers_per_lot`)
+    #[pyo3(get)]
+    pub lots_p5: Option<f64>,
+    #[pyo3(get)]
+    pub lots_p50: Option<f64>,
+    #[pyo3(get)]
+    pub lots_p95: Option<f64>,
+    #[pyo3(get)]
+    pub lots_p99: Option<f64>,
 }
 
 #[pymethods]
@@ -88,20 +374,237 @@ impl SimulationResult {
             self.mean_throughput, self.p50, self.p95
         )
     }
+
+    /// Pool independent batches (e.g. different seeds) into one result
+    ///
+    /// Statistics are recomputed from the pooled totals, so the result
+    /// matches a single run over all the batches' simulations. Daily means
+    /// are weighted by batch size; per-day percentile bands can't be pooled
+    /// and are dropped, as are lot percentiles unless every batch used the
+    /// same lot size.
+    #[staticmethod]
+    pub fn combine(results: Vec<SimulationResult>) -> PyResult<SimulationResult> {
+        let Some(first) = results.first() else {
+            return Err(PyValueError::new_err("no results to combine"));
+        };
+        let days = first.daily_throughputs.len();
+        if results.iter().any(|r| r.daily_throughputs.len() != days) {
+            return Err(PyValueError::new_err(
+                "results cover different time horizons",
+            ));
+        }
+        if results.iter().any(|r| r.totals.is_empty()) {
+            return Err(PyValueError::new_err("result has no simulation totals"));
+        }
+        let same_machines = |r: &SimulationResult| {
+            r.machine_stats.len() == first.machine_stats.len()
+                && r.machine_stats
+                    .iter()
+                    .zip(&first.machine_stats)
+                    .all(|(a, b)| a.0 == b.0)
+        };
+        if !results.iter().all(same_machines) {
+            return Err(PyValueError::new_err("results cover different machines"));
+        }
+
+        let count: usize = results.iter().map(|r| r.totals.len()).sum();
+        let mut daily_throughputs = vec![0.0; days];
+        let mut machine_stats: Vec<(String, f64, f64)> = first
+            .machine_stats
+            .iter()
+            .map(|(id, _, _)| (id.clone(), 0.0, 0.0))
+            .collect();
+        for result in &results {
+            let weight = result.totals.len() as f64 / count as f64;
+            for (pooled, mean) in daily_throughputs.iter_mut().zip(&result.daily_throughputs) {
+                *pooled += mean * weight;
+            }
+            for (pooled, (_, uptime, contribution)) in
+                machine_stats.iter_mut().zip(&result.machine_stats)
+            {
+                pooled.1 += uptime * weight;
+                pooled.2 += contribution * weight;
+            }
+        }
+
+        let mut totals: Vec<f64> = results
+            .iter()
+            .flat_map(|r| r.totals.iter().copied())
+            .collect();
+        totals.sort_by(f64::total_cmp);
+
+        let wafers_per_lot = first
+            .wafers_per_lot
+            .filter(|&w| results.iter().all(|r| r.wafers_per_lot == Some(w)));
+
+        Ok(SimulationResult::from_sorted_totals(
+            totals,
+            daily_throughputs,
+            (None, None, None),
+            first.risk_weighted_bottleneck.clone(),
+            first.machine_contributions.clone(),
+            machine_stats,
+            wafers_per_lot,
+        ))
+    }
+}
+
+/// One simulation: total output, daily output, and (days up, output) per machine
+type SimulationRun = (f64, Vec<f64>, Vec<(f64, f64)>);
+
+/// Optional per-day (p5, p50, p95) bands
+type DailyBands = (Option<Vec<f64>>, Option<Vec<f64>>, Option<Vec<f64>>);
+
+/// Decimal places kept in lot-based percentiles
+const LOT_DECIMALS: i32 = 2;
+
+impl SimulationResult {
+    /// Build a result, deriving the summary statistics from `totals`
+    /// (sorted ascending, non-empty) and the bottleneck from `machine_stats`
+    fn from_sorted_totals(
+        totals: Vec<f64>,
+        daily_throughputs: Vec<f64>,
+        (daily_p5, daily_p50, daily_p95): DailyBands,
+        risk_weighted_bottleneck: String,
+        machine_contributions: Vec<(String, String, f64)>,
+        machine_stats: Vec<(String, f64, f64)>,
+        wafers_per_lot: Option<f64>,
+    ) -> Self {
+        // Lowest simulated output, reported by name
+        let bottleneck_machine = machine_stats
+            .iter()
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(id, _, _)| {
+                machine_contributions
+                    .iter()
+                    .find(|(machine_id, _, _)| machine_id == id)
+                    .map_or_else(|| id.clone(), |(_, name, _)| name.clone())
+            })
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        let n = totals.len() as f64;
+        let mean_throughput = totals.iter().sum::<f64>() / n;
+        let variance = totals
+            .iter()
+            .map(|x| (x - mean_throughput).powi(2))
+            .sum::<f64>()
+            / n;
+        let percentile = |p: f64| -> f64 { sorted_percentile(&totals, p) };
+        let scale = 10f64.powi(LOT_DECIMALS);
+        let lots = |p: f64| -> Option<f64> {
+            wafers_per_lot.map(|w| (percentile(p) / w * scale).round() / scale)
+        };
+
+        Self {
+            mean_throughput,
+            std_throughput: variance.sqrt(),
+            p5: percentile(5.0),
+            p50: percentile(50.0),
+            p95: percentile(95.0),
+            p99: percentile(99.0),
+            confidence_lower: percentile(2.5),
+            confidence_upper: percentile(97.5),
+            daily_throughputs,
+            daily_p5,
+            daily_p50,
+            daily_p95,
+            bottleneck_machine,
+            risk_weighted_bottleneck,
+            machine_contributions,
+            machine_stats,
+            wafers_per_lot,
+            lots_p5: lots(5.0),
+            lots_p50: lots(50.0),
+            lots_p95: lots(95.0),
+            lots_p99: lots(99.0),
+            totals,
+        }
+    }
+}
+
+/// Completion-time distribution for a wafer target on one machine
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct CompletionTimeResult {
+    #[pyo3(get)]
+    pub mean_hours: f64,
+    #[pyo3(get)]
+    pub p50: f64,
+    #[pyo3(get)]
+    pub p95: f64,
+    #[pyo3(get)]
+    pub p99: f64,
+    #[pyo3(get)]
+    pub on_time_probability: f64,
+    #[pyo3(get)]
+    pub completion_hours: Vec<f64>, // sorted ascending
+}
+
+#[pymethods]
+impl CompletionTimeResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "CompletionTimeResult(p50={:.1}h, p95={:.1}h, on_time={:.1}%)",
+            self.p50,
+            self.p95,
+            self.on_time_probability * 100.0
+        )
+    }
+}
+
+/// Runs that haven't finished by then are reported at this horizon
+const MAX_COMPLETION_DAYS: usize = 3650;
+
+/// Simulation scenario configuration
+#[pyclass]
+#[derive(Clone, Debug, Default)]
+pub struct SimulationConfig {
+    /// Wafer demand per day; a day's output is capped at its demand.
+    /// Days beyond the end of the series are capacity-limited only.
+    #[pyo3(get, set)]
+    pub demand_wafers: Option<Vec<f64>>,
+    /// Also report per-day p5/p50/p95 output (fan chart)
+    #[pyo3(get, set)]
+    pub daily_percentiles: bool,
+    /// `(machine_id, day, new_efficiency_mean)`: the machine's efficiency
+    /// mean steps to the new value from that day on (resilience scenarios)
+    #[pyo3(get, set)]
+    pub degradation_events: Vec<(String, usize, f64)>,
+    /// Wafers per lot; when set, results also report percentiles in lots
+    #[pyo3(get, set)]
+    pub wafers_per_lot: Option<f64>,
+}
+
+#[pymethods]
+impl SimulationConfig {
+    #[new]
+    #[pyo3(signature = (demand_wafers=None))]
+    pub fn new(demand_wafers: Option<Vec<f64>>) -> Self {
+        Self {
+            demand_wafers,
+            daily_percentiles: false,
+            degradation_events: Vec::new(),
+            wafers_per_lot: None,
+        }
+    }
 }
 
 /// Monte Carlo Simulator with parallel execution
 #[pyclass]
 pub struct MonteCarloSimulator {
     random_seed: u64,
+    config: SimulationConfig,
 }
 
 #[pymethods]
 impl MonteCarloSimulator {
     #[new]
-    #[pyo3(signature = (random_seed=42))]
-    pub fn new(random_seed: u64) -> Self {
-        Self { random_seed }
+    #[pyo3(signature = (random_seed=42, config=None))]
+    pub fn new(random_seed: u64, config: Option<SimulationConfig>) -> Self {
+        Self {
+            random_seed,
+            config: config.unwrap_or_default(),
+        }
     }
 
     /// Run Monte Carlo simulation with parallel iterations
@@ -117,53 +620,85 @@ impl MonteCarloSimulator {
         time_horizon_days: usize,
         n_simulations: usize,
     ) -> PyResult<SimulationResult> {
+        if n_simulations == 0 {
+            return Err(PyValueError::new_err("n_simulations must be positive"));
+        }
+        for machine in &machines {
+            machine.check_finite()?;
+        }
+        self.check_degradation_events()?;
+        if let Some(wafers_per_lot) = self.config.wafers_per_lot {
+            if !(wafers_per_lot.is_finite() && wafers_per_lot > 0.0) {
+                return Err(PyValueError::new_err(format!(
+                    "wafers_per_lot must be positive, got {}",
+                    wafers_per_lot
+                )));
+            }
+        }
+
         // Run simulations in parallel using rayon
-        let all_simulations: Vec<(f64, Vec<f64>)> = (0..n_simulations)
+        let all_simulations: Vec<SimulationRun> = (0..n_simulations)
             .into_par_iter()
             .map(|sim_idx| {
                 let mut rng = StdRng::seed_from_u64(self.random_seed + sim_idx as u64);
                 let mut daily_outputs = Vec::with_capacity(time_horizon_days);
                 let mut simulation_total = 0.0;
+                let mut states = vec![MachineState::default(); machines.len()];
+                let mut machine_totals = vec![(0.0, 0.0); machines.len()];
 
-                for _day in 0..time_horizon_days {
+                for day in 0..time_horizon_days {
                     let mut day_output = 0.0;
 
-                    for machine in &machines {
+                    for ((machine, state), (uptime_days, output)) in machines
+                        .iter()
+                        .zip(states.iter_mut())
+                        .zip(machine_totals.iter_mut())
+                    {
                         // Check for downtime
-                        let is_down: f64 = rng.gen();
-                        if is_down < machine.downtime_prob {
+                        let uptime = machine.uptime_fraction(day, &mut state.downtime, &mut rng);
+                        *uptime_days += uptime;
+                        if uptime <= 0.0 {
                             continue;
                         }
 
                         // Normal operation with efficiency variation
-                        let normal = Normal::new(machine.efficiency_mean, machine.efficiency_std)
-                            .unwrap_or_else(|_| Normal::new(0.9, 0.05).unwrap());
-                        let efficiency: f64 = normal.sample(&mut rng).clamp(0.3, 1.0);
+                        let efficiency = machine.sample_efficiency(
+                            self.efficiency_mean_on(machine, day),
+                            &mut state.efficiency_deviation,
+                            &mut rng,
+                        );
 
                         // Daily output (24 hours)
-                        let mut daily_output = machine.base_throughput * efficiency * 24.0;
+                        let mut daily_output = machine.base_throughput * efficiency * 24.0 * uptime;
 
                         // Add small daily variation
                         let variation_normal = Normal::new(1.0, 0.02).unwrap();
                         daily_output *= variation_normal.sample(&mut rng);
 
+                        *output += daily_output;
                         day_output += daily_output;
                     }
 
+                    // Market-limited days: can't ship more than demand
+                    if let Some(demand) = self.daily_demand(day) {
+                        day_output = day_output.min(demand);
+                    }
+
                     simulation_total += day_output;
                     daily_outputs.push(day_output);
                 }
 
-                (simulation_total, daily_outputs)
+                (simulation_total, daily_outputs, machine_totals)
             })
             .collect();
 
         // Extract totals and compute daily means
-        let totals: Vec<f64> = all_simulations.iter().map(|(t, _)| *t).collect();
+        let mut totals: Vec<f64> = all_simulations.iter().map(|(t, _, _)| *t).collect();
+        totals.sort_by(f64::total_cmp);
 
         // Compute daily means across all simulations
         let mut daily_means = vec![0.0; time_horizon_days];
-        for (_, daily) in &all_simulations {
+        for (_, daily, _) in &all_simulations {
             for (day_idx, val) in daily.iter().enumerate() {
                 daily_means[day_idx] += val;
             }
@@ -172,25 +707,43 @@ impl MonteCarloSimulator {
             *mean /= n_simulations as f64;
         }
 
-        // Calculate statistics
-        let mut sorted_totals = totals.clone();
-        sorted_totals.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        // Per-day bands across simulations
+        let (daily_p5, daily_p50, daily_p95) = if self.config.daily_percentiles {
+            let mut p5 = Vec::with_capacity(time_horizon_days);
+            let mut p50 = Vec::with_capacity(time_horizon_days);
+            let mut p95 = Vec::with_capacity(time_horizon_days);
+            for day_idx in 0..time_horizon_days {
+                let mut day: Vec<f64> = all_simulations
+                    .iter()
+                    .map(|(_, daily, _)| daily[day_idx])
+                    .collect();
+                day.sort_by(f64::total_cmp);
+                p5.push(sorted_percentile(&day, 5.0));
+                p50.push(sorted_percentile(&day, 50.0));
+                p95.push(sorted_percentile(&day, 95.0));
+            }
+            (Some(p5), Some(p50), Some(p95))
+        } else {
+            (None, None, None)
+        };
 
-        let mean_throughput = totals.iter().sum::<f64>() / totals.len() as f64;
-        let variance = totals
+        // Simulated uptime and output per machine, averaged over simulations
+        let mut machine_stats: Vec<(String, f64, f64)> = machines
             .iter()
-            .map(|x| (x - mean_throughput).powi(2))
-            .sum::<f64>()
-            / totals.len() as f64;
-        let std_throughput = variance.sqrt();
-
-        // Percentile helper
-        let percentile = |p: f64| -> f64 {
-            let idx = ((p / 100.0) * (sorted_totals.len() - 1) as f64).round() as usize;
-            sorted_totals[idx.min(sorted_totals.len() - 1)]
-        };
+            .map(|m| (m.machine_id.clone(), 0.0, 0.0))
+            .collect();
+        for (_, _, machine_totals) in &all_simulations {
+            for (stats, (uptime_days, output)) in machine_stats.iter_mut().zip(machine_totals) {
+                stats.1 += uptime_days;
+                stats.2 += output;
+            }
+        }
+        for stats in &mut machine_stats {
+            stats.1 /= (n_simulations * time_horizon_days.max(1)) as f64;
+            stats.2 /= n_simulations as f64;
+        }
 
-        // Bottleneck analysis - find machine with lowest contribution
+        // Nameplate contribution of each machine
         let mut machine_contributions: Vec<(String, String, f64)> = machines
             .iter()
             .map(|m| {
@@ -199,35 +752,301 @@ impl MonteCarloSimulator {
                 (m.machine_id.clone(), m.name.clone(), contrib)
             })
             .collect();
-        machine_contributions.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
+        machine_contributions.sort_by(|a, b| b.2.total_cmp(&a.2));
 
-        let bottleneck = machine_contributions
-            .last()
-            .map(|(_, name, _)| name.clone())
+        // Risk: expected output exposed to efficiency swings and downtime
+        let risk_weighted_bottleneck = machines
+            .iter()
+            .map(|m| {
+                let contrib =
+                    m.base_throughput * m.efficiency_mean * 24.0 * time_horizon_days as f64;
+                (m, contrib * (m.efficiency_std + m.unavailability()))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(m, _)| m.name.clone())
             .unwrap_or_else(|| "Unknown".to_string());
 
-        Ok(SimulationResult {
-            mean_throughput,
-            std_throughput,
-            p5: percentile(5.0),
+        Ok(SimulationResult::from_sorted_totals(
+            totals,
+            daily_means,
+            (daily_p5, daily_p50, daily_p95),
+            risk_weighted_bottleneck,
+            machine_contributions,
+            machine_stats,
+            self.config.wafers_per_lot,
+        ))
+    }
+
+    /// Total output that can be committed at a reliability level
+    ///
+    /// The (1 − reliability) percentile of simulated output: at 0.9, nine
+    /// simulations in ten produce at least this much.
+    #[pyo3(signature = (machines, time_horizon_days=30, n_simulations=10000, reliability=0.9))]
+    pub fn committed_capacity(
+        &self,
+        machines: Vec<MachineConfig>,
+        time_horizon_days: usize,
+        n_simulations: usize,
+        reliability: f64,
+    ) -> PyResult<f64> {
+        if !(0.0..=1.0).contains(&reliability) {
+            return Err(PyValueError::new_err(format!(
+                "reliability must be between 0 and 1, got {}",
+                reliability
+            )));
+        }
+        let result = self.run_simulation(machines, time_horizon_days, n_simulations)?;
+        Ok(sorted_percentile(
+            &result.totals,
+            (1.0 - reliability) * 100.0,
+        ))
+    }
+
+    /// Simulate how long one machine takes to produce a wafer target
+    ///
+    /// Uses the same daily downtime and efficiency model as `run_simulation`,
+    /// resolving the completion time to the hour within the finishing day.
+    ///
+    /// # Arguments
+    /// * `machine` - Machine configuration
+    /// * `wafers` - Wafer target for the job
+    /// * `deadline_hours` - Deadline used for the on-time probability
+    /// * `n_simulations` - Number of Monte Carlo iterations
+    #[pyo3(signature = (machine, wafers, deadline_hours, n_simulations=10000))]
+    pub fn completion_time_distribution(
+        &self,
+        machine: MachineConfig,
+        wafers: f64,
+        deadline_hours: f64,
+        n_simulations: usize,
+    ) -> PyResult<CompletionTimeResult> {
+        if n_simulations == 0 {
+            return Err(PyValueError::new_err("n_simulations must be positive"));
+        }
+        machine.check_finite()?;
+        self.check_degradation_events()?;
+        if !wafers.is_finite() || deadline_hours.is_nan() {
+            return Err(PyValueError::new_err(
+                "wafers must be finite and deadline_hours not NaN",
+            ));
+        }
+        if wafers > 0.0 && (machine.base_throughput <= 0.0 || machine.unavailability() >= 1.0) {
+            return Err(PyValueError::new_err(format!(
+                "{} can never complete {} wafers",
+                machine.name, wafers
+            )));
+        }
+
+        let mut completion_hours: Vec<f64> = (0..n_simulations)
+            .into_par_iter()
+            .map(|sim_idx| {
+                let mut rng = StdRng::seed_from_u64(self.random_seed + sim_idx as u64);
+                let variation_normal = Normal::new(1.0, 0.02).unwrap();
+                let mut remaining = wafers;
+                let mut downtime = DowntimeState::default();
+                let mut efficiency_deviation = None;
+
+                for day in 0..MAX_COMPLETION_DAYS {
+                    if remaining <= 0.0 {
+                        return day as f64 * 24.0;
+                    }
+
+                    // Check for downtime
+                    let uptime = machine.uptime_fraction(day, &mut downtime, &mut rng);
+                    if uptime <= 0.0 {
+                        continue;
+                    }
+
+                    let efficiency = machine.sample_efficiency(
+                        self.efficiency_mean_on(&machine, day),
+                        &mut efficiency_deviation,
+                        &mut rng,
+                    );
+                    // Output spread over the day's uptime
+                    let hourly_output = machine.base_throughput
+                        * efficiency
+                        * variation_normal.sample(&mut rng)
+                        * uptime;
+                    let daily_output = hourly_output * 24.0;
+
+                    if daily_output >= remaining {
+                        return day as f64 * 24.0 + remaining / hourly_output;
+                    }
+                    remaining -= daily_output;
+                }
+
+                MAX_COMPLETION_DAYS as f64 * 24.0
+            })
+            .collect();
+        completion_hours.sort_by(f64::total_cmp);
+
+        let n = completion_hours.len();
+        let percentile = |p: f64| -> f64 {
+            let idx = ((p / 100.0) * (n - 1) as f64).round() as usize;
+            completion_hours[idx.min(n - 1)]
+        };
+        let on_time = completion_hours
+            .iter()
+            .filter(|&&h| h <= deadline_hours)
+            .count();
+
+        Ok(CompletionTimeResult {
+            mean_hours: completion_hours.iter().sum::<f64>() / n as f64,
             p50: percentile(50.0),
             p95: percentile(95.0),
             p99: percentile(99.0),
-            confidence_lower: percentile(2.5),
-            confidence_upper: percentile(97.5),
-            daily_throughputs: daily_means,
-            bottleneck_machine: bottleneck,
-            machine_contributions,
+            on_time_probability: on_time as f64 / n as f64,
+            completion_hours,
         })
     }
 }
 
+impl MonteCarloSimulator {
+    /// Demand cap for a given day, if the scenario defines one
+    fn daily_demand(&self, day: usize) -> Option<f64> {
+        self.config
+            .demand_wafers
+            .as_ref()
+            .and_then(|demand| demand.get(day))
+            .map(|d| d.max(0.0))
+    }
+
+    /// Efficiency mean of a machine on a given day, after any degradation
+    /// events that have taken effect (the latest one wins)
+    fn efficiency_mean_on(&self, machine: &MachineConfig, day: usize) -> f64 {
+        self.config
+            .degradation_events
+            .iter()
+            .filter(|(machine_id, from_day, _)| {
+                *machine_id == machine.machine_id && *from_day <= day
+            })
+            .max_by_key(|(_, from_day, _)| *from_day)
+            .map_or(machine.efficiency_mean, |(_, _, mean)| *mean)
+    }
+
+    fn check_degradation_events(&self) -> PyResult<()> {
+        match self
+            .config
+            .degradation_events
+            .iter()
+            .find(|(_, _, mean)| !mean.is_finite())
+        {
+            Some((machine_id, day, _)) => Err(PyValueError::new_err(format!(
+                "degradation event for {} on day {} has a non-finite efficiency mean",
+                machine_id, day
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Nearest-rank percentile of an ascending-sorted, non-empty slice
+fn sorted_percentile(sorted: &[f64], p: f64) -> f64 {
+    let idx = ((p / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[idx.min(sorted.len() - 1)]
+}
+
+/// Linear-trend projection of daily output
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct DeclineForecast {
+    #[pyo3(get)]
+    pub slope_per_day: f64,
+    #[pyo3(get)]
+    pub projected: Vec<f64>, // days n..n+horizon
+    #[pyo3(get)]
+    pub lower: Vec<f64>, // 95% prediction interval
+    #[pyo3(get)]
+    pub upper: Vec<f64>,
+}
+
+#[pymethods]
+impl DeclineForecast {
+    /// True when the fitted trend is downward
+    pub fn is_declining(&self) -> bool {
+        self.slope_per_day < 0.0
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "DeclineForecast(slope={:.2}/day, final={:.1} [{:.1}, {:.1}])",
+            self.slope_per_day,
+            self.projected.last().copied().unwrap_or(f64::NAN),
+            self.lower.last().copied().unwrap_or(f64::NAN),
+            self.upper.last().copied().unwrap_or(f64::NAN),
+        )
+    }
+}
+
+/// Fit a least-squares line to daily output and project it forward
+///
+/// # Arguments
+/// * `daily` - Observed output per day, oldest first (at least 3 days)
+/// * `horizon` - Number of days to project past the last observation
+#[pyfunction]
+pub fn forecast_decline(daily: Vec<f64>, horizon: usize) -> PyResult<DeclineForecast> {
+    let n = daily.len();
+    if n < 3 {
+        return Err(PyValueError::new_err(
+            "at least 3 days of output are needed to fit a trend",
+        ));
+    }
+    if daily.iter().any(|y| !y.is_finite()) {
+        return Err(PyValueError::new_err("daily output must be finite"));
+    }
+
+    let nf = n as f64;
+    let x_mean = (nf - 1.0) / 2.0;
+    let y_mean = daily.iter().sum::<f64>() / nf;
+    let sxx: f64 = (0..n).map(|x| (x as f64 - x_mean).powi(2)).sum();
+    let sxy: f64 = daily
+        .iter()
+        .enumerate()
+        .map(|(x, y)| (x as f64 - x_mean) * (y - y_mean))
+        .sum();
+
+    let slope = sxy / sxx;
+    let intercept = y_mean - slope * x_mean;
+
+    // Residual standard error (n - 2 degrees of freedom)
+    let sse: f64 = daily
+        .iter()
+        .enumerate()
+        .map(|(x, y)| (y - (intercept + slope * x as f64)).powi(2))
+        .sum();
+    let residual_std = (sse / (nf - 2.0)).sqrt();
+
+    let mut projected = Vec::with_capacity(horizon);
+    let mut lower = Vec::with_capacity(horizon);
+    let mut upper = Vec::with_capacity(horizon);
+    for step in 0..horizon {
+        let x = (n + step) as f64;
+        let y = intercept + slope * x;
+        // Prediction error widens with distance from the observed window
+        let half_width = 1.96 * residual_std * (1.0 + 1.0 / nf + (x - x_mean).powi(2) / sxx).sqrt();
+        projected.push(y);
+        lower.push(y - half_width);
+        upper.push(y + half_width);
+    }
+
+    Ok(DeclineForecast {
+        slope_per_day: slope,
+        projected,
+        lower,
+        upper,
+    })
+}
+
 /// Python module initialization
 #[pymodule]
 fn yieldops_monte_carlo(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<MachineConfig>()?;
+    m.add_class::<SimulationConfig>()?;
     m.add_class::<SimulationResult>()?;
+    m.add_class::<CompletionTimeResult>()?;
     m.add_class::<MonteCarloSimulator>()?;
+    m.add_class::<DeclineForecast>()?;
+    m.add_function(wrap_pyfunction!(forecast_decline, m)?)?;
     Ok(())
 }
 
@@ -245,7 +1064,7 @@ mod tests {
 
     #[test]
     fn test_simulation_runs() {
-        let sim = MonteCarloSimulator::new(42);
+        let sim = MonteCarloSimulator::new(42, None);
         let machines = sample_machines();
         let result = sim.run_simulation(machines, 30, 1000).unwrap();
 
@@ -255,9 +1074,179 @@ mod tests {
         assert!(result.p95 <= result.p99);
     }
 
+    #[test]
+    fn test_non_finite_config_rejected() {
+        let sim = MonteCarloSimulator::new(42, None);
+        let mut machines = sample_machines();
+        machines[1].efficiency_mean = f64::NAN;
+
+        assert!(sim.run_simulation(machines.clone(), 30, 100).is_err());
+        assert!(sim
+            .completion_time_distribution(machines[1].clone(), 500.0, 72.0, 100)
+            .is_err());
+        assert!(sim
+            .completion_time_distribution(machines[0].clone(), f64::INFINITY, 72.0, 100)
+            .is_err());
+        assert!(forecast_decline(vec![100.0, f64::NAN, 90.0], 3).is_err());
+
+        machines[1].efficiency_mean = 0.88;
+        assert!(sim.run_simulation(machines, 30, 100).is_ok());
+    }
+
+    #[test]
+    fn test_combine_matches_single_run_over_union() {
+        // Simulation i uses seed random_seed + i, so these batches split one run
+        let first = MonteCarloSimulator::new(42, None)
+            .run_simulation(sample_machines(), 30, 400)
+            .unwrap();
+        let second = MonteCarloSimulator::new(442, None)
+            .run_simulation(sample_machines(), 30, 600)
+            .unwrap();
+        let single = MonteCarloSimulator::new(42, None)
+            .run_simulation(sample_machines(), 30, 1000)
+            .unwrap();
+
+        let combined = SimulationResult::combine(vec![first, second]).unwrap();
+
+        assert_eq!(combined.totals, single.totals);
+        assert_eq!(combined.p5, single.p5);
+        assert_eq!(combined.p50, single.p50);
+        assert_eq!(combined.p99, single.p99);
+        assert_eq!(combined.confidence_lower, single.confidence_lower);
+        assert!((combined.mean_throughput - single.mean_throughput).abs() < 1e-6);
+        assert!((combined.std_throughput - single.std_throughput).abs() < 1e-6);
+        for (a, b) in combined
+            .daily_throughputs
+            .iter()
+            .zip(&single.daily_throughputs)
+        {
+            assert!((a - b).abs() < 1e-6);
+        }
+        for (a, b) in combined.machine_stats.iter().zip(&single.machine_stats) {
+            assert_eq!(a.0, b.0);
+            assert!((a.1 - b.1).abs() < 1e-9);
+            assert!((a.2 - b.2).abs() < 1e-6);
+        }
+        assert_eq!(combined.bottleneck_machine, single.bottleneck_machine);
+        assert!(SimulationResult::combine(vec![]).is_err());
+    }
+
+    #[test]
+    fn test_lot_percentiles_scale_wafer_percentiles() {
+        let config = SimulationConfig {
+            wafers_per_lot: Some(25.0),
+            ..SimulationConfig::default()
+        };
+        let result = MonteCarloSimulator::new(42, Some(config))
+            .run_simulation(sample_machines(), 30, 500)
+            .unwrap();
+
+        let lots = [
+            result.lots_p5,
+            result.lots_p50,
+            result.lots_p95,
+            result.lots_p99,
+        ];
+        let wafers = [result.p5, result.p50, result.p95, result.p99];
+        for (lots, wafers) in lots.iter().zip(wafers) {
+            assert!((lots.unwrap() - wafers / 25.0).abs() <= 0.005 + 1e-9);
+        }
+
+        let plain = MonteCarloSimulator::new(42, None)
+            .run_simulation(sample_machines(), 30, 500)
+            .unwrap();
+        assert_eq!(plain.p50, result.p50);
+        assert!(plain.lots_p50.is_none());
+    }
+
+    #[test]
+    fn test_risk_weighted_bottleneck_flags_volatile_machine() {
+        let machines = vec![
+            // Largest contributor, but steady
+            MachineConfig::new("m1".into(), "LITHO-01".into(), 100.0, 0.90, 0.01, 0.01, 4.0),
+            // Moderate output, swings widely and breaks down often
+            MachineConfig::new("m2".into(), "ETCH-01".into(), 60.0, 0.85, 0.15, 0.10, 3.0),
+            // Smallest contributor (the static bottleneck), steady
+            MachineConfig::new("m3".into(), "DEP-01".into(), 30.0, 0.90, 0.01, 0.01, 5.0),
+        ];
+        let result = MonteCarloSimulator::new(42, None)
+            .run_simulation(machines, 30, 200)
+            .unwrap();
+
+        assert_eq!(result.bottleneck_machine, "DEP-01");
+        assert_eq!(result.risk_weighted_bottleneck, "ETCH-01");
+    }
+
+    #[test]
+    fn test_simulated_bottleneck_accounts_for_downtime() {
+        let machines = vec![
+            // Highest nameplate, but down half the time
+            MachineConfig::new("m1".into(), "LITHO-01".into(), 20.0, 0.90, 0.02, 0.6, 8.0),
+            MachineConfig::new("m2".into(), "ETCH-01".into(), 12.0, 0.90, 0.02, 0.02, 3.0),
+            MachineConfig::new("m3".into(), "DEP-01".into(), 10.0, 0.90, 0.02, 0.02, 5.0),
+        ];
+        let result = MonteCarloSimulator::new(42, None)
+            .run_simulation(machines, 30, 500)
+            .unwrap();
+
+        // Nameplate ranks LITHO-01 first...
+        assert_eq!(result.machine_contributions[0].1, "LITHO-01");
+        // ...but it spends ~60% of days down and ships the least
+        let litho = &result.machine_stats[0];
+        assert_eq!(litho.0, "m1");
+        assert!((litho.1 - 0.4).abs() < 0.03);
+        assert!(result.machine_stats[1..]
+            .iter()
+            .all(|(_, uptime, output)| { *uptime > 0.95 && *output > litho.2 }));
+        assert_eq!(result.bottleneck_machine, "LITHO-01");
+    }
+
+    #[test]
+    fn test_degradation_event_drops_output_from_that_day() {
+        let baseline = MonteCarloSimulator::new(42, None)
+            .run_simulation(sample_machines(), 30, 500)
+            .unwrap();
+
+        let mut config = SimulationConfig::new(None);
+        config.degradation_events = vec![("m2".to_string(), 10, 0.5)];
+        let degraded = MonteCarloSimulator::new(42, Some(config))
+            .run_simulation(sample_machines(), 30, 500)
+            .unwrap();
+
+        // Same seeds, so days before the event are unchanged
+        for day in 0..10 {
+            assert!(
+                (degraded.daily_throughputs[day] - baseline.daily_throughputs[day]).abs() < 1e-9
+            );
+        }
+        // ETCH-01 runs at ~0.5 instead of ~0.88 from day 10: ~15 wafers/h * 0.38 * 24h
+        for day in 10..30 {
+            let drop = baseline.daily_throughputs[day] - degraded.daily_throughputs[day];
+            assert!(drop > 100.0, "day {} dropped only {:.1}", day, drop);
+        }
+        assert!(degraded.p50 < baseline.p50);
+    }
+
+    #[test]
+    fn test_committed_capacity_reads_reliability_percentile() {
+        let sim = MonteCarloSimulator::new(42, None);
+        let result = sim.run_simulation(sample_machines(), 30, 1000).unwrap();
+        let committed = |reliability| {
+            sim.committed_capacity(sample_machines(), 30, 1000, reliability)
+                .unwrap()
+        };
+
+        assert_eq!(committed(0.9), sorted_percentile(&result.totals, 10.0));
+        assert_eq!(committed(0.5), result.p50);
+        assert!(committed(0.95) <= committed(0.9));
+        assert!(sim
+            .committed_capacity(sample_machines(), 30, 1000, 1.5)
+            .is_err());
+    }
+
     #[test]
     fn test_percentiles_ordered() {
-        let sim = MonteCarloSimulator::new(123);
+        let sim = MonteCarloSimulator::new(123, None);
         let machines = sample_machines();
         let result = sim.run_simulation(machines, 30, 5000).unwrap();
 
@@ -267,10 +1256,287 @@ mod tests {
 
     #[test]
     fn test_daily_throughputs_length() {
-        let sim = MonteCarloSimulator::new(42);
+        let sim = MonteCarloSimulator::new(42, None);
         let machines = sample_machines();
         let result = sim.run_simulation(machines, 14, 100).unwrap();
 
         assert_eq!(result.daily_throughputs.len(), 14);
     }
+
+    #[test]
+    fn test_daily_percentile_bands() {
+        let sim = MonteCarloSimulator::new(42, None);
+        let result = sim.run_simulation(sample_machines(), 14, 2000).unwrap();
+        assert!(result.daily_p50.is_none());
+
+        let config = SimulationConfig {
+            daily_percentiles: true,
+            ..Default::default()
+        };
+        let sim = MonteCarloSimulator::new(42, Some(config));
+        let result = sim.run_simulation(sample_machines(), 14, 2000).unwrap();
+
+        let p5 = result.daily_p5.unwrap();
+        let p50 = result.daily_p50.unwrap();
+        let p95 = result.daily_p95.unwrap();
+        assert_eq!(p50.len(), 14);
+        for day in 0..14 {
+            assert!(p5[day] <= p50[day] && p50[day] <= p95[day]);
+            assert!(p5[day] < p95[day], "day {} band should have width", day);
+            // Median tracks the mean (downtime only skews the low tail)
+            let mean = result.daily_throughputs[day];
+            assert!((p50[day] - mean).abs() / mean < 0.1);
+        }
+    }
+
+    #[test]
+    fn test_bathtub_clusters_downtime_at_ends() {
+        let sim = MonteCarloSimulator::new(42, None);
+        let flat = MachineConfig::new("m1".into(), "LITHO-01".into(), 10.0, 0.92, 0.0, 0.1, 4.0);
+        let mut bathtub = flat.clone();
+        bathtub.infant_mortality_days = Some(5);
+        bathtub.wearout_start_day = Some(25);
+        bathtub.bathtub_factor = 4.0;
+
+        // Mean output of the burn-in, middle and wear-out periods
+        let periods = |daily: &[f64]| {
+            let mean = |days: &[f64]| days.iter().sum::<f64>() / days.len() as f64;
+            (mean(&daily[..5]), mean(&daily[5..25]), mean(&daily[25..]))
+        };
+
+        let result = sim.run_simulation(vec![flat], 30, 5000).unwrap();
+        let (early, middle, late) = periods(&result.daily_throughputs);
+        assert!((early - middle).abs() / middle < 0.03);
+        assert!((late - middle).abs() / middle < 0.03);
+
+        // 40% downtime at the ends vs 10% in the middle
+        let result = sim.run_simulation(vec![bathtub], 30, 5000).unwrap();
+        let (early, middle, late) = periods(&result.daily_throughputs);
+        assert!(
+            early < middle * 0.75,
+            "early {} vs middle {}",
+            early,
+            middle
+        );
+        assert!(late < middle * 0.75, "late {} vs middle {}", late, middle);
+    }
+
+    #[test]
+    fn test_downtime_persistence_lengthens_runs() {
+        // Mean length of consecutive-downtime runs and fraction of days down
+        let runs = |machine: &MachineConfig| {
+            let mut rng = StdRng::seed_from_u64(7);
+            let (mut down_days, mut runs) = (0usize, 0usize);
+            let mut down = false;
+            for day in 0..200_000 {
+                let next = machine.draw_down(day, down, &mut rng);
+                if next {
+                    down_days += 1;
+                    if !down {
+                        runs += 1;
+                    }
+                }
+                down = next;
+            }
+            (down_days as f64 / runs as f64, down_days as f64 / 200_000.0)
+        };
+
+        let independent =
+            MachineConfig::new("m1".into(), "LITHO-01".into(), 10.0, 0.92, 0.0, 0.1, 4.0);
+        let mut sticky = independent.clone();
+        sticky.downtime_persistence = 0.7;
+
+        let (independent_run, independent_rate) = runs(&independent);
+        let (sticky_run, sticky_rate) = runs(&sticky);
+        // Same marginal probability of being down...
+        assert!((independent_rate - 0.1).abs() < 0.01);
+        assert!((sticky_rate - 0.1).abs() < 0.01);
+        // ...but runs of 1/(1-0.1) ≈ 1.1 days vs 1/(1-0.73) ≈ 3.7 days
+        assert!(sticky_run > independent_run * 2.5);
+
+        let sim = MonteCarloSimulator::new(42, None);
+        sticky.downtime_persistence = 1.0;
+        assert!(sim.run_simulation(vec![sticky], 30, 10).is_err());
+    }
+
+    #[test]
+    fn test_efficiency_autocorrelation_smooths_daily_series() {
+        // Variance of the series and of its day-to-day differences
+        let variances = |machine: &MachineConfig| {
+            let mut rng = StdRng::seed_from_u64(7);
+            let mut deviation = None;
+            let series: Vec<f64> = (0..100_000)
+                .map(|_| machine.sample_efficiency(0.8, &mut deviation, &mut rng))
+                .collect();
+            let variance = |values: &[f64]| {
+                let mean = values.iter().sum::<f64>() / values.len() as f64;
+                values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+            };
+            let diffs: Vec<f64> = series.windows(2).map(|w| w[1] - w[0]).collect();
+            (variance(&series), variance(&diffs))
+        };
+
+        let iid = MachineConfig::new("m1".into(), "LITHO-01".into(), 10.0, 0.8, 0.05, 0.0, 4.0);
+        let mut ar = iid.clone();
+        ar.efficiency_phi = 0.9;
+
+        let (iid_var, iid_diff_var) = variances(&iid);
+        let (ar_var, ar_diff_var) = variances(&ar);
+        // Same spread around the mean...
+        assert!((ar_var / iid_var - 1.0).abs() < 0.1);
+        // ...but first differences shrink by (1 - φ): 2σ²(1 - 0.9) vs 2σ²
+        assert!(ar_diff_var < iid_diff_var * 0.2);
+
+        let sim = MonteCarloSimulator::new(42, None);
+        ar.efficiency_phi = 1.0;
+        assert!(sim.run_simulation(vec![ar], 30, 10).is_err());
+    }
+
+    #[test]
+    fn test_efficiency_distribution_families() {
+        // Mean, standard deviation and skewness of raw draws
+        let moments = |distribution: &str| {
+            let mut machine =
+                MachineConfig::new("m1".into(), "LITHO-01".into(), 10.0, 0.7, 0.08, 0.0, 4.0);
+            machine.distribution = distribution.to_string();
+            let mut rng = StdRng::seed_from_u64(7);
+            let draws: Vec<f64> = (0..100_000)
+                .map(|_| machine.draw_efficiency(0.7, 0.08, &mut rng))
+                .collect();
+            let n = draws.len() as f64;
+            let mean = draws.iter().sum::<f64>() / n;
+            let std = (draws.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / n).sqrt();
+            let skew = draws
+                .iter()
+                .map(|d| ((d - mean) / std).powi(3))
+                .sum::<f64>()
+                / n;
+            (mean, std, skew, draws)
+        };
+
+        for distribution in EFFICIENCY_DISTRIBUTIONS {
+            let (mean, std, _, draws) = moments(distribution);
+            assert!((mean - 0.7).abs() < 0.005, "{} mean {}", distribution, mean);
+            assert!((std - 0.08).abs() < 0.005, "{} std {}", distribution, std);
+            assert!(
+                draws.iter().all(|d| (0.0..1.5).contains(d)),
+                "{}",
+                distribution
+            );
+        }
+        // Triangular stays within mean ± std × √6
+        let (_, _, _, triangular) = moments("triangular");
+        assert!(triangular
+            .iter()
+            .all(|d| (d - 0.7).abs() <= 0.08 * 6f64.sqrt()));
+        // Lognormal has a right tail; the symmetric families don't
+        let (_, _, lognormal_skew, _) = moments("lognormal");
+        let (_, _, normal_skew, _) = moments("normal");
+        assert!(lognormal_skew > 0.2);
+        assert!(normal_skew.abs() < 0.05);
+
+        let sim = MonteCarloSimulator::new(42, None);
+        let mut machines = sample_machines();
+        machines[0].distribution = "lognormal".to_string();
+        machines[1].distribution = "triangular".to_string();
+        assert!(
+            sim.run_simulation(machines.clone(), 30, 100)
+                .unwrap()
+                .mean_throughput
+                > 0.0
+        );
+        machines[2].distribution = "weibull".to_string();
+        assert!(sim.run_simulation(machines, 30, 100).is_err());
+    }
+
+    #[test]
+    fn test_longer_repairs_lower_mtbf_throughput() {
+        let sim = MonteCarloSimulator::new(42, None);
+        let mean_with_mttr = |mttr: f64| {
+            let mut machine =
+                MachineConfig::new("m1".into(), "LITHO-01".into(), 10.0, 0.92, 0.05, 0.0, 4.0);
+            machine.mtbf_hours = Some(120.0);
+            machine.mttr_hours = Some(mttr);
+            sim.run_simulation(vec![machine], 30, 2000)
+                .unwrap()
+                .mean_throughput
+        };
+
+        let quick = mean_with_mttr(2.0);
+        let slow = mean_with_mttr(24.0);
+        let very_slow = mean_with_mttr(96.0);
+        assert!(quick > slow && slow > very_slow);
+        // Availability mtbf / (mtbf + mttr): 120/216 of the quick-repair 120/122
+        let ratio = very_slow / quick;
+        assert!((ratio - (120.0 / 216.0) / (120.0 / 122.0)).abs() < 0.05);
+
+        let mut bad =
+            MachineConfig::new("m1".into(), "LITHO-01".into(), 10.0, 0.92, 0.05, 0.0, 4.0);
+        bad.mtbf_hours = Some(0.0);
+        assert!(sim.run_simulation(vec![bad], 30, 10).is_err());
+    }
+
+    #[test]
+    fn test_downtime_lengthens_completion_tail() {
+        let sim = MonteCarloSimulator::new(42, None);
+        let reliable =
+            MachineConfig::new("m1".into(), "LITHO-01".into(), 10.0, 0.92, 0.05, 0.02, 4.0);
+        let flaky = MachineConfig::new("m2".into(), "LITHO-02".into(), 10.0, 0.92, 0.05, 0.30, 4.0);
+
+        // ~1000 wafers is roughly 4.5 days of uninterrupted output
+        let deadline = 6.0 * 24.0;
+        let reliable = sim
+            .completion_time_distribution(reliable, 1000.0, deadline, 2000)
+            .unwrap();
+        let flaky = sim
+            .completion_time_distribution(flaky, 1000.0, deadline, 2000)
+            .unwrap();
+
+        assert!(reliable.p50 <= reliable.p95);
+        assert_eq!(reliable.completion_hours.len(), 2000);
+        assert!(
+            flaky.p95 > reliable.p95,
+            "Downtime should lengthen the tail"
+        );
+        assert!(flaky.mean_hours > reliable.mean_hours);
+        assert!(flaky.on_time_probability < reliable.on_time_probability);
+        assert!(reliable.on_time_probability > 0.9);
+    }
+
+    #[test]
+    fn test_forecast_declining_output() {
+        // ~5 wafers/day loss with some day-to-day noise
+        let noise = [3.0, -2.0, 1.0, -4.0, 2.0, 0.0, -1.0, 3.0, -3.0, 1.0];
+        let daily: Vec<f64> = noise
+            .iter()
+            .enumerate()
+            .map(|(day, n)| 800.0 - 5.0 * day as f64 + n)
+            .collect();
+
+        let forecast = forecast_decline(daily.clone(), 7).unwrap();
+
+        assert!(forecast.is_declining());
+        assert_eq!(forecast.projected.len(), 7);
+        assert!(forecast.projected[6] < forecast.projected[0]);
+        assert!(forecast.projected[0] < *daily.last().unwrap());
+
+        let width = |i: usize| forecast.upper[i] - forecast.lower[i];
+        assert!(width(6) > width(0), "Interval should widen with horizon");
+        assert!(forecast.lower[0] < forecast.projected[0]);
+
+        assert!(forecast_decline(vec![1.0, 2.0], 3).is_err());
+    }
+
+    #[test]
+    fn test_demand_caps_daily_output() {
+        // Day 1 demand is far below the ~800 wafers/day the line can make
+        let config = SimulationConfig::new(Some(vec![10_000.0, 50.0, 10_000.0]));
+        let sim = MonteCarloSimulator::new(42, Some(config));
+        let result = sim.run_simulation(sample_machines(), 5, 200).unwrap();
+
+        assert!((result.daily_throughputs[1] - 50.0).abs() < 1e-9);
+        assert!(result.daily_throughputs[0] > 50.0);
+        // Days beyond the demand series are capacity-limited only
+        assert!(result.daily_throughputs[4] > 50.0);
+    }
 }
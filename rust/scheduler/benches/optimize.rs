@@ -0,0 +1,77 @@
+//! Optimizer throughput across fleet sizes
+//!
+//! Run with: cargo bench -p yieldops_scheduler
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use yieldops_scheduler::{workload, ConstraintConfig, SchedulerOptimizer};
+
+fn bench_optimize(c: &mut Criterion) {
+    let optimizer = SchedulerOptimizer::new(None);
+    let mut group = c.benchmark_group("optimize_native");
+
+    for &machines in &[10, 100, 1000] {
+        // Several jobs competing for each machine
+        let (jobs, fleet) = workload::generate(machines * 5, machines, 42);
+        group.bench_with_input(BenchmarkId::from_parameter(machines), &machines, |b, &n| {
+            b.iter(|| optimizer.optimize_native(black_box(&jobs), black_box(&fleet), n))
+        });
+    }
+
+    group.finish();
+}
+
+/// Serial vs parallel machine scoring, to tune `parallel_machine_threshold`
+fn bench_parallel_scoring(c: &mut Criterion) {
+    let mut group = c.benchmark_group("machine_scoring");
+
+    for &machines in &[10, 100, 1000] {
+        let (jobs, fleet) = workload::generate(machines * 5, machines, 42);
+        for (label, threshold) in [("serial", usize::MAX), ("parallel", 0)] {
+            let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+                parallel_machine_threshold: threshold,
+                ..Default::default()
+            }));
+            group.bench_with_input(BenchmarkId::new(label, machines), &machines, |b, &n| {
+                b.iter(|| optimizer.optimize_native(black_box(&jobs), black_box(&fleet), n))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+/// One machine per recipe: the dedicated fast path vs scoring the fleet
+fn bench_dedicated_machines(c: &mut Criterion) {
+    let optimizer = SchedulerOptimizer::new(None);
+    let mut group = c.benchmark_group("dedicated_machines");
+
+    for &jobs in &[50, 500] {
+        let (jobs_list, mut fleet) = workload::generate(jobs, 5, 42);
+        for machine in &mut fleet {
+            machine.status = "IDLE".to_string();
+        }
+        // Full-queue twins take no work but force the general path
+        let mut general_fleet = fleet.clone();
+        for machine in &fleet {
+            let mut twin = machine.clone();
+            twin.machine_id.push_str("-twin");
+            twin.max_queue_depth = Some(0);
+            general_fleet.push(twin);
+        }
+        for (label, machines) in [("fast_path", &fleet), ("general", &general_fleet)] {
+            group.bench_with_input(BenchmarkId::new(label, jobs), &jobs, |b, &n| {
+                b.iter(|| optimizer.optimize_native(black_box(&jobs_list), black_box(machines), n))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_optimize,
+    bench_parallel_scoring,
+    bench_dedicated_machines
+);
+criterion_main!(benches);
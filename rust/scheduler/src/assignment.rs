@@ -0,0 +1,269 @@
+//! Maximum-weight assignment (Hungarian / Kuhn-Munkres)
+//!
+//! Used by the global optimizer mode: given a job×machine score matrix,
+//! pick at most one machine per job and one job per machine so the summed
+//! score is as large as possible.
+
+/// Best one-to-one matching of rows to columns
+///
+/// `scores[row][col]` is `None` where the pair is forbidden. Rows may also
+/// stay unmatched, so pairs that would lower the total are left out.
+/// Returns the chosen column for each row.
+///
+/// `seed` is an earlier matching (e.g. the previous run's plan). Seeded
+/// pairs that are allowed and already optimal for their row start out
+/// matched, so fewer rows need an augmenting search. The result is the same
+/// as without a seed: among equally good plans the one that gives the
+/// earliest rows the lowest columns is returned.
+pub(crate) fn max_weight_matching(
+    scores: &[Vec<Option<f64>>],
+    seed: &[Option<usize>],
+) -> Vec<Option<usize>> {
+    let rows = scores.len();
+    let cols = scores.iter().map(Vec::len).max().unwrap_or(0);
+    if rows == 0 || cols == 0 {
+        return vec![None; rows];
+    }
+
+    // Square cost matrix: real columns, then one "unassigned" column per row;
+    // padding rows soak up whatever columns are left. Leaving a row
+    // unassigned costs 0, and forbidden pairs cost more than any real plan.
+    let size = rows + cols;
+    let forbidden = 1.0
+        + scores
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|s| s.abs())
+            .sum::<f64>();
+    let cost = |row: usize, col: usize| -> f64 {
+        if row >= rows || col >= cols {
+            return 0.0;
+        }
+        match scores[row].get(col).copied().flatten() {
+            Some(score) => -score,
+            None => forbidden,
+        }
+    };
+
+    let seed: Vec<Option<usize>> = (0..rows)
+        .map(|row| {
+            let col = seed.get(row).copied().flatten()?;
+            scores[row].get(col).copied().flatten().map(|_| col)
+        })
+        .collect();
+    let matched = solve_min_cost(size, cost, &seed);
+    (0..rows)
+        .map(|row| {
+            let col = matched[row];
+            (col < cols && scores[row].get(col).copied().flatten().is_some()).then_some(col)
+        })
+        .collect()
+}
+
+/// Minimum-cost perfect matching on a `size`×`size` matrix
+///
+/// Classic O(n³) potentials formulation; returns the column of each row.
+/// Rows of `seed` whose column is tight under the starting potentials are
+/// matched up front and skip their augmenting search. Ties are broken by
+/// [`first_optimum`], so the seed never changes the matching returned.
+fn solve_min_cost(
+    size: usize,
+    cost: impl Fn(usize, usize) -> f64,
+    seed: &[Option<usize>],
+) -> Vec<usize> {
+    // 1-based internally; index 0 is the virtual start column
+    let mut u = vec![0.0; size + 1];
+    let mut v = vec![0.0; size + 1];
+    let mut row_of_col = vec![0usize; size + 1];
+    let mut way = vec![0usize; size + 1];
+
+    // Warm start: row potentials at each row's cheapest cell keep every
+    // reduced cost non-negative, and a seeded pair at its row's minimum is
+    // tight, so it can be matched before the search starts
+    let mut seeded = vec![false; size + 1];
+    if seed.iter().any(Option::is_some) {
+        for (row, potential) in u.iter_mut().enumerate().skip(1) {
+            *potential = (1..=size)
+                .map(|col| cost(row - 1, col - 1))
+                .fold(f64::INFINITY, f64::min);
+        }
+        for (row, col) in seed
+            .iter()
+            .enumerate()
+            .filter_map(|(row, col)| Some((row + 1, (*col)? + 1)))
+        {
+            if row <= size
+                && col <= size
+                && row_of_col[col] == 0
+                && cost(row - 1, col - 1) - u[row] <= 0.0
+            {
+                row_of_col[col] = row;
+                seeded[row] = true;
+            }
+        }
+    }
+
+    for row in (1..=size).filter(|row| !seeded[*row]) {
+        row_of_col[0] = row;
+        let mut col0 = 0;
+        let mut min_slack = vec![f64::INFINITY; size + 1];
+        let mut used = vec![false; size + 1];
+        loop {
+            used[col0] = true;
+            let row0 = row_of_col[col0];
+            let mut delta = f64::INFINITY;
+            let mut col1 = 0;
+            for col in 1..=size {
+                if used[col] {
+                    continue;
+                }
+                let slack = cost(row0 - 1, col - 1) - u[row0] - v[col];
+                if slack < min_slack[col] {
+                    min_slack[col] = slack;
+                    way[col] = col0;
+                }
+                if min_slack[col] < delta {
+                    delta = min_slack[col];
+                    col1 = col;
+                }
+            }
+            for col in 0..=size {
+                if used[col] {
+                    u[row_of_col[col]] += delta;
+                    v[col] -= delta;
+                } else {
+                    min_slack[col] -= delta;
+                }
+            }
+            col0 = col1;
+            if row_of_col[col0] == 0 {
+                break;
+            }
+        }
+        // Flip the augmenting path
+        loop {
+            let prev = way[col0];
+            row_of_col[col0] = row_of_col[prev];
+            col0 = prev;
+            if col0 == 0 {
+                break;
+            }
+        }
+    }
+
+    first_optimum(size, &cost, &u, &v, &mut row_of_col);
+    let mut col_of_row = vec![0; size];
+    for col in 1..=size {
+        col_of_row[row_of_col[col] - 1] = col - 1;
+    }
+    col_of_row
+}
+
+/// Move an optimal matching to the first optimal one in row/column order
+///
+/// Which optimum the search lands on depends on where it started. The
+/// optimal matchings are exactly the perfect matchings on the pairs that
+/// are tight under the final potentials `u`/`v`, so each row in turn takes
+/// the lowest tight column it can while the rows after it can still be
+/// rematched, by flipping an alternating cycle through later rows only.
+/// `row_of_col` is 1-based, as in [`solve_min_cost`].
+fn first_optimum(
+    size: usize,
+    cost: impl Fn(usize, usize) -> f64,
+    u: &[f64],
+    v: &[f64],
+    row_of_col: &mut [usize],
+) {
+    let scale = u.iter().chain(v).fold(1.0_f64, |acc, p| acc.max(p.abs()));
+    let tight =
+        |row: usize, col: usize| (cost(row - 1, col - 1) - u[row] - v[col]).abs() <= 1e-9 * scale;
+    let mut col_of_row = vec![0usize; size + 1];
+    for col in 1..=size {
+        col_of_row[row_of_col[col]] = col;
+    }
+
+    for row in 1..=size {
+        let target = col_of_row[row];
+        for col in (1..target).filter(|col| tight(row, *col)) {
+            // The row holding `col` must reach `target` through later rows
+            let start = row_of_col[col];
+            if start < row {
+                continue;
+            }
+            let mut came_from = vec![None; size + 1];
+            came_from[start] = Some((0, col));
+            let mut queue = std::collections::VecDeque::from([start]);
+            let mut end = None;
+            while let Some(r) = queue.pop_front() {
+                if tight(r, target) {
+                    end = Some(r);
+                    break;
+                }
+                for c in (1..=size).filter(|c| *c != col_of_row[r] && tight(r, *c)) {
+                    let next = row_of_col[c];
+                    if next > row && came_from[next].is_none() {
+                        came_from[next] = Some((r, c));
+                        queue.push_back(next);
+                    }
+                }
+            }
+            let Some(mut r) = end else {
+                continue;
+            };
+
+            // Flip the cycle: each row on the path takes the column it was
+            // reached through, the last one takes `target`
+            let mut next_col = target;
+            loop {
+                let (prev, c) = came_from[r].expect("row on the path");
+                col_of_row[r] = next_col;
+                row_of_col[next_col] = r;
+                next_col = c;
+                if prev == 0 {
+                    break;
+                }
+                r = prev;
+            }
+            col_of_row[row] = col;
+            row_of_col[col] = row;
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_skips_forbidden_pairs() {
+        // Row 0 prefers column 0, but row 1 can only use column 0
+        let scores = vec![vec![Some(1.0), Some(0.8)], vec![Some(0.9), None]];
+        assert_eq!(max_weight_matching(&scores, &[]), vec![Some(1), Some(0)]);
+
+        // A row with nothing allowed stays unmatched
+        let scores = vec![vec![None, None], vec![Some(0.5), Some(0.7)]];
+        assert_eq!(max_weight_matching(&scores, &[]), vec![None, Some(1)]);
+    }
+
+    #[test]
+    fn test_seed_does_not_change_the_matching() {
+        // Rows 0 and 1 tie on both columns; row 2 can only use column 1
+        let scores = vec![
+            vec![Some(0.5), Some(0.5), Some(0.2)],
+            vec![Some(0.5), Some(0.5), Some(0.2)],
+            vec![None, Some(0.9), Some(0.1)],
+        ];
+        let cold = max_weight_matching(&scores, &[]);
+        assert_eq!(cold, vec![Some(0), Some(2), Some(1)]);
+
+        // A seed that is no longer optimal is repaired
+        let stale = max_weight_matching(&scores, &[Some(1), Some(2), Some(0)]);
+        assert_eq!(stale, cold);
+
+        // A different but equally good plan still gives way to the first one
+        let tied = max_weight_matching(&scores, &[Some(2), Some(0), Some(1)]);
+        assert_eq!(tied, cold);
+    }
+}
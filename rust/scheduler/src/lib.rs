@@ -2,34 +2,46 @@
 //!
 //! Optimizes job-to-machine assignments using constraint satisfaction
 //! and multi-objective scoring.
+//!
+//! Jobs, machines, configs and results serialize to JSON, so a scheduling
+//! run can be saved and replayed without the Python objects.
 
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+use rand::prelude::*;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
+mod assignment;
+#[cfg(feature = "python")]
+mod python;
+pub mod workload;
+
 /// Job for scheduling
-#[pyclass]
-#[derive(Clone, Debug)]
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SchedulerJob {
-    #[pyo3(get, set)]
     pub job_id: String,
-    #[pyo3(get, set)]
     pub job_name: String,
-    #[pyo3(get, set)]
     pub priority_level: i32, // 1=highest, 5=lowest
-    #[pyo3(get, set)]
     pub wafer_count: i32,
-    #[pyo3(get, set)]
     pub is_hot_lot: bool,
-    #[pyo3(get, set)]
-    pub recipe_type: String, // lithography, etching, deposition, etc.
-    #[pyo3(get, set)]
+    pub recipe_type: String,         // lithography, etching, deposition, etc.
     pub deadline_hours: Option<f64>, // hours until deadline, None = no deadline
+    /// Target start; missing it costs score but never excludes a machine
+    pub soft_deadline_hours: Option<f64>,
+    /// Fixed recipe duration; overrides the default per-job queue time
+    pub processing_hours: Option<f64>,
+    /// Jobs that must finish before this one starts
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Hours the job has been waiting for a tool (drives priority aging)
+    #[serde(default)]
+    pub waiting_hours: f64,
 }
 
-#[pymethods]
 impl SchedulerJob {
-    #[new]
-    #[pyo3(signature = (job_id, job_name, priority_level, wafer_count, is_hot_lot, recipe_type, deadline_hours=None))]
     pub fn new(
         job_id: String,
         job_name: String,
@@ -47,34 +59,41 @@ impl SchedulerJob {
             is_hot_lot,
             recipe_type,
             deadline_hours,
+            soft_deadline_hours: None,
+            processing_hours: None,
+            depends_on: Vec::new(),
+            waiting_hours: 0.0,
         }
     }
 }
 
 /// Machine for scheduling
-#[pyclass]
-#[derive(Clone, Debug)]
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SchedulerMachine {
-    #[pyo3(get, set)]
     pub machine_id: String,
-    #[pyo3(get, set)]
     pub name: String,
-    #[pyo3(get, set)]
     pub machine_type: String, // lithography, etching, deposition, etc.
-    #[pyo3(get, set)]
-    pub status: String, // IDLE, RUNNING, DOWN, MAINTENANCE
-    #[pyo3(get, set)]
+    pub status: String,       // IDLE, RUNNING, DOWN, MAINTENANCE
     pub efficiency_rating: f64,
-    #[pyo3(get, set)]
     pub current_queue_depth: i32,
-    #[pyo3(get, set)]
     pub estimated_available_hours: f64, // when machine will be free
+    /// Recently observed efficiency readings (e.g. from Sentinel telemetry)
+    #[serde(default)]
+    pub recent_efficiency: Vec<f64>,
+    /// Hours until the machine may run again after a stop (pending inspection)
+    pub cooldown_until_hours: Option<f64>,
+    /// Recipe the machine ran last; switching family costs a changeover
+    pub last_recipe_type: Option<String>,
+    /// Lots the machine can physically hold queued (e.g. cassette slots);
+    /// it takes no job that would push its queue past this (None = no limit)
+    pub max_queue_depth: Option<i32>,
+    /// Wafers the machine can take across the planning window; jobs that
+    /// would push its assigned total past this go elsewhere (None = no limit)
+    pub max_wafer_capacity: Option<i32>,
 }
 
-#[pymethods]
 impl SchedulerMachine {
-    #[new]
-    #[pyo3(signature = (machine_id, name, machine_type, status, efficiency_rating, current_queue_depth=0, estimated_available_hours=0.0))]
     pub fn new(
         machine_id: String,
         name: String,
@@ -92,101 +111,293 @@ impl SchedulerMachine {
             efficiency_rating,
             current_queue_depth,
             estimated_available_hours,
+            recent_efficiency: Vec::new(),
+            cooldown_until_hours: None,
+            last_recipe_type: None,
+            max_queue_depth: None,
+            max_wafer_capacity: None,
         }
     }
 
     fn is_available(&self) -> bool {
-        self.status == "IDLE" || self.status == "RUNNING"
+        (self.status == "IDLE" || self.status == "RUNNING") && !self.in_cooldown()
+    }
+
+    /// Blend the static efficiency rating with the mean of recent readings
+    ///
+    /// `observed_weight` is the share given to the observed mean (0 = static
+    /// rating only, 1 = observations only). Non-finite readings are ignored;
+    /// with no usable readings the static rating is returned. The result is
+    /// clamped to [0, 1].
+    pub fn effective_efficiency(&self, recent_readings: Vec<f64>, observed_weight: f64) -> f64 {
+        self.blended_efficiency(&recent_readings, observed_weight)
+    }
+}
+
+impl SchedulerMachine {
+    /// Reject values that can only come from a data-entry error
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.efficiency_rating) {
+            return Err(format!(
+                "{}: efficiency_rating {} is outside [0, 1]",
+                self.name, self.efficiency_rating
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the cooldown still runs past the time the machine frees up
+    fn in_cooldown(&self) -> bool {
+        self.cooldown_until_hours
+            .is_some_and(|until| until > self.estimated_available_hours)
+    }
+
+    fn blended_efficiency(&self, recent_readings: &[f64], observed_weight: f64) -> f64 {
+        let readings: Vec<f64> = recent_readings
+            .iter()
+            .copied()
+            .filter(|r| r.is_finite())
+            .collect();
+        // A mis-entered rating (e.g. 95 for 95%) must not inflate the score
+        let rating = self.efficiency_rating.clamp(0.0, 1.0);
+        if readings.is_empty() {
+            return rating;
+        }
+
+        let observed = readings.iter().sum::<f64>() / readings.len() as f64;
+        let weight = observed_weight.clamp(0.0, 1.0);
+        (rating * (1.0 - weight) + observed * weight).clamp(0.0, 1.0)
     }
 }
 
 /// Assignment result for a single job-machine pair
-#[pyclass]
-#[derive(Clone, Debug)]
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Assignment {
-    #[pyo3(get)]
     pub job_id: String,
-    #[pyo3(get)]
     pub job_name: String,
-    #[pyo3(get)]
     pub machine_id: String,
-    #[pyo3(get)]
     pub machine_name: String,
-    #[pyo3(get)]
     pub score: f64,
-    #[pyo3(get)]
     pub reason: String,
-    #[pyo3(get)]
+    /// (label, value) pairs behind `reason`, e.g. ("optimizer_version", "1.0"),
+    /// ("score", "0.85"), ("efficiency", "95%"); ("hot_lot", "true") for hot lots
+    /// and ("preempted", job_id) when it bumped a job. Filled whatever the
+    /// `reason_verbosity`
+    pub reason_factors: Vec<(String, String)>,
     pub estimated_start_hours: f64,
-    #[pyo3(get)]
+    /// Start plus the job's processing time
+    pub estimated_finish_hours: f64,
     pub constraint_violations: Vec<String>,
-}
-
-#[pymethods]
-impl Assignment {
-    fn __repr__(&self) -> String {
-        format!(
-            "Assignment({} -> {}, score={:.2})",
-            self.job_name, self.machine_name, self.score
-        )
-    }
+    /// Weighted contribution of each objective to `score`: "priority",
+    /// "efficiency", "deadline", "queue_depth" and "status_bonus", plus
+    /// "utilization", "changeover", "tardiness" or "recipe_mismatch" when
+    /// those penalties apply
+    pub score_breakdown: HashMap<String, f64>,
+    /// Hours the job would finish past its deadline, scaled by priority
+    /// (hot lots and priority 1 count in full, priority 5 at 0.2)
+    pub weighted_tardiness: f64,
+    /// Wafers placed (a sub-lot's share when the lot was split)
+    #[serde(default)]
+    pub wafer_count: i32,
 }
 
 /// Overall optimization result
-#[pyclass]
-#[derive(Clone, Debug)]
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OptimizationResult {
-    #[pyo3(get)]
     pub assignments: Vec<Assignment>,
-    #[pyo3(get)]
     pub total_score: f64,
-    #[pyo3(get)]
+    /// Sum of `weighted_tardiness` over the assignments
+    pub total_weighted_tardiness: f64,
     pub unassigned_jobs: Vec<String>,
-    #[pyo3(get)]
+    /// Why each unassigned job was left out (job_id -> reason)
+    pub unassigned_reasons: HashMap<String, String>,
+    /// Jobs left for the next cycle: they could only start beyond the
+    /// planning horizon, or fell past `max_jobs_considered`
+    pub deferred_jobs: Vec<String>,
+    /// Jobs bumped off their machine to make room for a hot lot
+    #[serde(default)]
+    pub preempted_jobs: Vec<String>,
+    /// Wafers across the assignments, split sub-lots included
+    #[serde(default)]
+    pub total_wafers_assigned: i64,
+    /// Wafers of the input jobs not on the plan: unassigned, deferred,
+    /// preempted, or left over once the batch was full
+    #[serde(default)]
+    pub total_wafers_unassigned: i64,
     pub optimization_time_ms: f64,
 }
 
-#[pymethods]
 impl OptimizationResult {
-    fn __repr__(&self) -> String {
-        format!(
-            "OptimizationResult({} assignments, score={:.2}, {} unassigned)",
-            self.assignments.len(),
-            self.total_score,
-            self.unassigned_jobs.len()
-        )
+    /// Gantt bars for the plan: (machine_id, job_id, start_hours, end_hours)
+    ///
+    /// Sorted by machine, then start. Jobs queued on the same machine follow
+    /// one another, so their bars never overlap.
+    pub fn timeline(&self) -> Vec<(String, String, f64, f64)> {
+        let mut bars: Vec<(String, String, f64, f64)> = self
+            .assignments
+            .iter()
+            .map(|a| {
+                (
+                    a.machine_id.clone(),
+                    a.job_id.clone(),
+                    a.estimated_start_hours,
+                    a.estimated_finish_hours,
+                )
+            })
+            .collect();
+        bars.sort_by(|a, b| {
+            a.0.cmp(&b.0)
+                .then(a.2.total_cmp(&b.2))
+                .then(a.3.total_cmp(&b.3))
+        });
+        bars
+    }
+
+    /// The whole result as JSON, for saving and replaying a run
+    ///
+    /// Unset optional fields (e.g. `deadline_hours`) are written as `null`.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("optimization results always serialize")
     }
 }
 
-/// Constraint configuration
-#[pyclass]
+/// Gain from adding one machine to the fleet (capex justification)
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug)]
+pub struct MarginalValue {
+    pub machine_id: String,
+    /// Change in `total_score` with the candidate machine added
+    pub score_delta: f64,
+    /// Additional jobs assigned with the candidate machine added
+    pub assignment_delta: i64,
+}
+
+/// One schedule on the score / on-time trade-off curve
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[derive(Clone, Debug)]
+pub struct ParetoPoint {
+    /// `deadline_weight` that produced this schedule
+    pub deadline_weight: f64,
+    /// Summed assignment scores without the deadline objective
+    pub total_score: f64,
+    /// Assigned jobs finishing by their deadline and soft deadline
+    pub on_time_count: usize,
+    pub assignments: Vec<Assignment>,
+}
+
+impl ParetoPoint {
+    /// At least as good on both axes and better on one
+    fn dominates(&self, other: &ParetoPoint) -> bool {
+        let score_not_worse = self.total_score >= other.total_score - SCORE_TIE_EPSILON;
+        let score_better = self.total_score > other.total_score + SCORE_TIE_EPSILON;
+        score_not_worse
+            && self.on_time_count >= other.on_time_count
+            && (score_better || self.on_time_count > other.on_time_count)
+    }
+
+    fn same_tradeoff(&self, other: &ParetoPoint) -> bool {
+        (self.total_score - other.total_score).abs() <= SCORE_TIE_EPSILON
+            && self.on_time_count == other.on_time_count
+    }
+}
+
+/// Suggested fix for a group of unassigned jobs sharing one cause
+#[cfg_attr(feature = "python", pyclass(get_all))]
 #[derive(Clone, Debug)]
+pub struct UnassignedRemedy {
+    /// Machine type(s) the jobs need, e.g. "etching"
+    pub machine_type: String,
+    pub job_ids: Vec<String>,
+    /// "machines_unavailable", "capacity", "no_compatible_machine",
+    /// "recipe_budget" or "constraints"
+    pub cause: String,
+    /// Machines to bring up or free, fewest needed first
+    pub machines: Vec<String>,
+    pub suggestion: String,
+}
+
+/// Constraint configuration
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ConstraintConfig {
-    #[pyo3(get, set)]
     pub enforce_recipe_match: bool,
-    #[pyo3(get, set)]
+    /// How a machine type qualifies for a recipe: "contains" (a combined
+    /// type like "etching_and_cleaning" runs either family) or "exact"
+    pub match_mode: String,
+    /// Score subtracted from a machine that can't run the recipe when
+    /// `enforce_recipe_match` is off (a fallback, not a preference)
+    pub recipe_mismatch_penalty: f64,
     pub enforce_deadlines: bool,
-    #[pyo3(get, set)]
     pub priority_weight: f64,
-    #[pyo3(get, set)]
     pub efficiency_weight: f64,
-    #[pyo3(get, set)]
     pub deadline_weight: f64,
-    #[pyo3(get, set)]
+    /// Score lost per priority-weighted hour a job would finish past its
+    /// deadline; a smoother objective than the urgency bucket (0 = off)
+    pub tardiness_weight: f64,
     pub queue_depth_weight: f64,
+    /// Queue score is `1 / (1 + depth * factor)`; larger values make deep
+    /// queues count for more
+    pub queue_saturation_factor: f64,
+    /// Blend of wafer count into the priority score (0 = priority only)
+    pub wafer_weight: f64,
+    /// Share of a machine's recent observed efficiency in its effective rating
+    pub observed_efficiency_weight: f64,
+    /// Idle compatible machines kept free of normal jobs for later hot lots
+    pub reserve_for_hot_lots: usize,
+    /// A hot lot with no compatible machine free may bump the lowest-priority
+    /// job placed on one (listed in `OptimizationResult.preempted_jobs`)
+    pub allow_hot_lot_preemption: bool,
+    /// Let machines queue several jobs per batch (up to any set
+    /// `max_jobs_per_machine`), round-robining among tied-score machines
+    /// of the same type
+    pub load_balance: bool,
+    /// Jobs one machine may take per batch. Unset, a machine takes one job,
+    /// or any number once `load_balance` or `target_utilization` is on; a
+    /// set cap is never raised by either
+    pub max_jobs_per_machine: Option<usize>,
+    /// Lots with more wafers are split into sub-lots of at most this size,
+    /// placed independently as `{job_id}#1`, `#2`, ... (None = never split)
+    pub max_wafers_per_assignment: Option<i32>,
+    /// Keep hot lots whole even when splitting is enabled
+    pub no_split_hot_lots: bool,
+    /// Cap on jobs of a recipe type placed per batch (e.g. a shared gas line)
+    pub max_assignments_per_recipe: HashMap<String, usize>,
+    /// Machines that can't start a job within this many hours are not used;
+    /// jobs with no closer option are deferred (None = no horizon)
+    pub planning_horizon_hours: Option<f64>,
+    /// Detail in `Assignment.reason`: "full", "compact" (score only) or "none"
+    pub reason_verbosity: String,
+    /// Setup time added before a job whose recipe family differs from the
+    /// machine's last one
+    pub changeover_hours: f64,
+    /// Score subtracted when a job needs a changeover (keeps tools on one
+    /// recipe family)
+    pub changeover_weight: f64,
+    /// Reject machines with an out-of-range efficiency rating instead of
+    /// clamping it to [0, 1]
+    pub strict_validation: bool,
+    /// Fraction of the utilization window a machine should be booked to;
+    /// jobs that push a machine past it are penalized so work spreads to
+    /// other tools instead (None = maximize score only)
+    pub target_utilization: Option<f64>,
+    /// Hours assumed for a job without its own `processing_hours`
+    pub default_processing_hours: f64,
+    /// Jobs (in dispatch order) given a placement attempt per run; the rest
+    /// are deferred untried (None = every job)
+    pub max_jobs_considered: Option<usize>,
+    /// Waiting hours that raise a job one priority level, so low-priority
+    /// lots aren't starved across runs (0 = no aging)
+    pub aging_hours_per_level: f64,
+    /// Fleets of at least this many machines are scored in parallel for
+    /// each job; smaller ones stay serial, where threads cost more than
+    /// they save
+    pub parallel_machine_threshold: usize,
 }
 
-#[pymethods]
 impl ConstraintConfig {
-    #[new]
-    #[pyo3(signature = (
-        enforce_recipe_match=true,
-        enforce_deadlines=false,
-        priority_weight=0.3,
-        efficiency_weight=0.3,
-        deadline_weight=0.2,
-        queue_depth_weight=0.2
-    ))]
     pub fn new(
         enforce_recipe_match: bool,
         enforce_deadlines: bool,
@@ -197,11 +408,34 @@ impl ConstraintConfig {
     ) -> Self {
         Self {
             enforce_recipe_match,
+            match_mode: "contains".to_string(),
+            recipe_mismatch_penalty: 0.5,
             enforce_deadlines,
             priority_weight,
             efficiency_weight,
             deadline_weight,
+            tardiness_weight: 0.0,
             queue_depth_weight,
+            queue_saturation_factor: 0.2,
+            wafer_weight: 0.0,
+            observed_efficiency_weight: 0.5,
+            reserve_for_hot_lots: 0,
+            allow_hot_lot_preemption: false,
+            load_balance: false,
+            max_jobs_per_machine: None,
+            max_wafers_per_assignment: None,
+            no_split_hot_lots: false,
+            max_assignments_per_recipe: HashMap::new(),
+            planning_horizon_hours: None,
+            reason_verbosity: "full".to_string(),
+            changeover_hours: 0.0,
+            changeover_weight: 0.0,
+            strict_validation: false,
+            target_utilization: None,
+            default_processing_hours: 2.0,
+            max_jobs_considered: None,
+            aging_hours_per_level: 0.0,
+            parallel_machine_threshold: 64,
         }
     }
 }
@@ -212,6 +446,213 @@ impl Default for ConstraintConfig {
     }
 }
 
+impl ConstraintConfig {
+    /// First scoring weight that is NaN or infinite, if any
+    fn non_finite_weight(&self) -> Option<&'static str> {
+        [
+            ("priority_weight", self.priority_weight),
+            ("efficiency_weight", self.efficiency_weight),
+            ("deadline_weight", self.deadline_weight),
+            ("tardiness_weight", self.tardiness_weight),
+            ("queue_depth_weight", self.queue_depth_weight),
+            ("queue_saturation_factor", self.queue_saturation_factor),
+            ("recipe_mismatch_penalty", self.recipe_mismatch_penalty),
+            ("wafer_weight", self.wafer_weight),
+            ("changeover_hours", self.changeover_hours),
+            ("changeover_weight", self.changeover_weight),
+            ("default_processing_hours", self.default_processing_hours),
+            (
+                "observed_efficiency_weight",
+                self.observed_efficiency_weight,
+            ),
+        ]
+        .into_iter()
+        .find(|(_, weight)| !weight.is_finite())
+        .map(|(name, _)| name)
+    }
+}
+
+/// Accepted values of `ConstraintConfig.reason_verbosity`
+const REASON_VERBOSITIES: [&str; 3] = ["full", "compact", "none"];
+
+/// Accepted values of `ConstraintConfig.match_mode`
+const MATCH_MODES: [&str; 2] = ["contains", "exact"];
+
+/// Per-machine state accumulated while placing one batch of jobs
+#[derive(Clone, Debug, Default)]
+struct BatchState {
+    /// Machines that received a job in this batch
+    assigned: HashSet<String>,
+    /// Jobs added to each machine's queue in this batch
+    added_queue: HashMap<String, i32>,
+    /// Processing hours added to each machine's queue in this batch
+    added_hours: HashMap<String, f64>,
+    /// Wafers added to each machine's queue in this batch
+    added_wafers: HashMap<String, i32>,
+    /// Machine hours each job placed in this batch takes, including any
+    /// changeover or wait for predecessors
+    job_hours: HashMap<String, f64>,
+    /// Jobs placed per recipe type (lowercased) in this batch
+    recipe_assignments: HashMap<String, usize>,
+    /// Recipes queued on each machine in this batch, in order; the last one
+    /// decides the next changeover
+    queued_recipes: HashMap<String, Vec<String>>,
+    /// Estimated finish of each job placed in this batch; a split lot is
+    /// also recorded under its parent id, finishing with its last part
+    finished: HashMap<String, f64>,
+}
+
+impl BatchState {
+    fn record(&mut self, machine_id: &str, job: &SchedulerJob, processing_hours: f64) {
+        self.reserve(machine_id, processing_hours);
+        self.queued_recipes
+            .entry(machine_id.to_string())
+            .or_default()
+            .push(job.recipe_type.clone());
+        self.job_hours.insert(job.job_id.clone(), processing_hours);
+        *self.added_wafers.entry(machine_id.to_string()).or_insert(0) += job.wafer_count;
+    }
+
+    /// Take a queue slot and machine time for work whose job isn't known
+    fn reserve(&mut self, machine_id: &str, processing_hours: f64) {
+        self.assigned.insert(machine_id.to_string());
+        *self.added_queue.entry(machine_id.to_string()).or_insert(0) += 1;
+        *self
+            .added_hours
+            .entry(machine_id.to_string())
+            .or_insert(0.0) += processing_hours;
+    }
+
+    /// Undo `record` for a job bumped from the batch
+    fn release(&mut self, machine_id: &str, job: &SchedulerJob) {
+        let processing_hours = self.job_hours.remove(&job.job_id).unwrap_or(0.0);
+        if let Some(queue) = self.added_queue.get_mut(machine_id) {
+            *queue -= 1;
+            if *queue <= 0 {
+                self.assigned.remove(machine_id);
+            }
+        }
+        if let Some(hours) = self.added_hours.get_mut(machine_id) {
+            *hours = (*hours - processing_hours).max(0.0);
+        }
+        if let Some(wafers) = self.added_wafers.get_mut(machine_id) {
+            *wafers -= job.wafer_count;
+        }
+        // The machine goes back to the recipe it had before this job
+        if let Some(recipes) = self.queued_recipes.get_mut(machine_id) {
+            if let Some(k) = recipes.iter().rposition(|r| *r == job.recipe_type) {
+                recipes.remove(k);
+            }
+        }
+        if let Some(count) = self
+            .recipe_assignments
+            .get_mut(&job.recipe_type.to_lowercase())
+        {
+            *count = count.saturating_sub(1);
+        }
+        self.finished.remove(&job.job_id);
+    }
+
+    fn record_finish(&mut self, job_id: &str, finish: f64) {
+        let parent = job_id.rsplit_once('#').map(|(parent, _)| parent);
+        for id in std::iter::once(job_id).chain(parent) {
+            let entry = self.finished.entry(id.to_string()).or_insert(finish);
+            *entry = entry.max(finish);
+        }
+    }
+
+    /// Earliest start allowed by the job's predecessors
+    fn ready_at(&self, job: &SchedulerJob) -> f64 {
+        job.depends_on
+            .iter()
+            .filter_map(|dep| self.finished.get(dep))
+            .fold(0.0, |ready, &finish| ready.max(finish))
+    }
+
+    fn record_recipe(&mut self, recipe_type: &str) {
+        *self
+            .recipe_assignments
+            .entry(recipe_type.to_lowercase())
+            .or_insert(0) += 1;
+    }
+
+    fn recipe_count(&self, recipe_type: &str) -> usize {
+        self.recipe_assignments
+            .get(&recipe_type.to_lowercase())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn queue_additions(&self, machine_id: &str) -> i32 {
+        self.added_queue.get(machine_id).copied().unwrap_or(0)
+    }
+
+    fn hours_added(&self, machine_id: &str) -> f64 {
+        self.added_hours.get(machine_id).copied().unwrap_or(0.0)
+    }
+
+    fn wafers_added(&self, machine_id: &str) -> i32 {
+        self.added_wafers.get(machine_id).copied().unwrap_or(0)
+    }
+
+    fn last_recipe(&self, machine_id: &str) -> Option<&String> {
+        self.queued_recipes.get(machine_id)?.last()
+    }
+}
+
+/// Upper end of the `deadline_weight` sweep in `pareto_schedules`
+/// (several times the other default weights, so deadlines dominate)
+const PARETO_MAX_DEADLINE_WEIGHT: f64 = 2.0;
+
+/// Scores closer than this are treated as a tie when load balancing
+const SCORE_TIE_EPSILON: f64 = 1e-9;
+
+/// Annealing temperature at the first and last iteration, in score units
+/// (a worse plan by the start temperature is taken about a third of the time)
+const ANNEAL_START_TEMPERATURE: f64 = 0.1;
+const ANNEAL_END_TEMPERATURE: f64 = 1e-4;
+
+/// Wafer count at which a lot's size contributes half of the wafer factor
+/// (one standard 25-wafer FOUP)
+const WAFER_REFERENCE_COUNT: f64 = 25.0;
+
+/// Lateness past the soft deadline that costs half of the deadline weight
+const SOFT_DEADLINE_REFERENCE_HOURS: f64 = 4.0;
+
+/// Window utilization is measured over when no planning horizon is set
+const UTILIZATION_WINDOW_HOURS: f64 = 24.0;
+
+/// Score lost per unit of utilization above `target_utilization`
+/// (10 points over target costs 0.1, about the idle-machine bonus)
+const UTILIZATION_OVERSHOOT_PENALTY: f64 = 1.0;
+
+/// Summed score of a plan
+fn total_score(assignments: &[Assignment]) -> f64 {
+    assignments.iter().map(|a| a.score).sum()
+}
+
+/// (wafers assigned, wafers of `jobs` left off the plan)
+///
+/// A sub-lot (`{job_id}#{k}`) counts towards its parent lot.
+fn wafer_totals(jobs: &[SchedulerJob], assignments: &[Assignment]) -> (i64, i64) {
+    let mut placed: HashMap<&str, i64> = jobs.iter().map(|j| (j.job_id.as_str(), 0)).collect();
+    for a in assignments {
+        let parent = match a.job_id.rsplit_once('#') {
+            Some((parent, _)) if !placed.contains_key(a.job_id.as_str()) => parent,
+            _ => a.job_id.as_str(),
+        };
+        if let Some(wafers) = placed.get_mut(parent) {
+            *wafers += i64::from(a.wafer_count);
+        }
+    }
+    let assigned = assignments.iter().map(|a| i64::from(a.wafer_count)).sum();
+    let unassigned = jobs
+        .iter()
+        .map(|j| (i64::from(j.wafer_count) - placed[j.job_id.as_str()]).max(0))
+        .sum();
+    (assigned, unassigned)
+}
+
 /// Recipe to machine type mapping
 fn get_compatible_machine_types(recipe_type: &str) -> Vec<&'static str> {
     match recipe_type.to_lowercase().as_str() {
@@ -231,244 +672,1690 @@ fn get_compatible_machine_types(recipe_type: &str) -> Vec<&'static str> {
 }
 
 /// Main Scheduler Optimizer
-#[pyclass]
+#[cfg_attr(feature = "python", pyclass)]
 pub struct SchedulerOptimizer {
     config: ConstraintConfig,
+    /// Fab qualification data: machine_id -> recipe types the tool is
+    /// qualified for. A machine listed here runs exactly those recipes; the
+    /// machine-type family check only applies to machines without an entry.
+    qualifications: HashMap<String, HashSet<String>>,
 }
 
-#[pymethods]
 impl SchedulerOptimizer {
-    #[new]
-    #[pyo3(signature = (config=None))]
-    pub fn new(config: Option<ConstraintConfig>) -> Self {
-        Self {
-            config: config.unwrap_or_default(),
-        }
-    }
-
     /// Optimize job assignments to machines
     ///
     /// # Arguments
     /// * `jobs` - Jobs to schedule
     /// * `machines` - Available machines
     /// * `max_assignments` - Maximum number of assignments to make
-    #[pyo3(signature = (jobs, machines, max_assignments=10))]
     pub fn optimize(
         &self,
         jobs: Vec<SchedulerJob>,
         machines: Vec<SchedulerMachine>,
         max_assignments: usize,
-    ) -> PyResult<OptimizationResult> {
-        let start = std::time::Instant::now();
-
-        // Sort jobs by priority (hot lots first, then by priority level)
-        let mut sorted_jobs = jobs.clone();
-        sorted_jobs.sort_by(|a, b| {
-            // Hot lots first
-            match (a.is_hot_lot, b.is_hot_lot) {
-                (true, false) => std::cmp::Ordering::Less,
-                (false, true) => std::cmp::Ordering::Greater,
-                _ => a.priority_level.cmp(&b.priority_level),
-            }
-        });
-
-        let mut assignments = Vec::new();
-        let mut unassigned_jobs = Vec::new();
-        let mut assigned_machines: HashSet<String> = HashSet::new();
-        let mut machine_added_queue: HashMap<String, i32> = HashMap::new();
-
-        for job in sorted_jobs
-            .iter()
-            .take(max_assignments + unassigned_jobs.len())
-        {
-            if assignments.len() >= max_assignments {
-                break;
-            }
+    ) -> Result<OptimizationResult, String> {
+        self.check_inputs(&machines)?;
+        Ok(self.optimize_native(&jobs, &machines, max_assignments))
+    }
 
-            // Find best machine for this job
-            let best_assignment =
-                self.find_best_machine(&job, &machines, &assigned_machines, &machine_added_queue);
+    /// Optimize the batch as a whole instead of job by job
+    ///
+    /// Scores every job×machine pair and solves the assignment problem
+    /// (Hungarian method) for the largest total score with one job per
+    /// machine, so an early job can't take the only machine a later job
+    /// could use. Pairs ruled out by hard constraints are never chosen. The
+    /// batch is the first `max_assignments` jobs in priority order; options
+    /// that depend on placement order (dependencies, lot splitting, recipe
+    /// budgets, hot-lot reserve, several jobs per machine) don't apply.
+    ///
+    /// `warm_start` is a previous result for (mostly) the same jobs: its
+    /// assignments that are still allowed seed the matching, which saves
+    /// work when re-optimizing large batches. The plan is the same with or
+    /// without it.
+    pub fn optimize_global(
+        &self,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+        max_assignments: usize,
+        warm_start: Option<OptimizationResult>,
+    ) -> Result<OptimizationResult, String> {
+        self.check_inputs(&machines)?;
+        Ok(self.optimize_global_native(&jobs, &machines, max_assignments, warm_start.as_ref()))
+    }
 
-            match best_assignment {
-                Some(assignment) => {
-                    assigned_machines.insert(assignment.machine_id.clone());
-                    *machine_added_queue
-                        .entry(assignment.machine_id.clone())
-                        .or_insert(0) += 1;
-                    assignments.push(assignment);
-                }
-                None => {
-                    unassigned_jobs.push(job.job_id.clone());
-                }
-            }
-        }
+    /// Re-optimize around assignments that are already committed
+    ///
+    /// `locked` assignments keep their machines' queue slots and time and
+    /// come back unchanged, ahead of the new ones; only the other jobs are
+    /// placed, after the locked work, and `max_assignments` counts only them.
+    /// Jobs may depend on locked ones.
+    pub fn optimize_incremental(
+        &self,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+        locked: Vec<Assignment>,
+        max_assignments: usize,
+    ) -> Result<OptimizationResult, String> {
+        self.check_inputs(&machines)?;
+        Ok(self.optimize_incremental_native(&jobs, &machines, &locked, max_assignments))
+    }
 
-        // Calculate total score
-        let total_score: f64 = assignments.iter().map(|a| a.score).sum();
+    /// Refine the greedy plan by simulated annealing
+    ///
+    /// Starts from `optimize`'s plan and tries `iterations` random moves (a
+    /// job to another machine) and swaps (two jobs trade machines), taking a
+    /// worse plan with a probability that shrinks as the temperature decays.
+    /// Plans that break a hard constraint are never taken. The best plan seen
+    /// is returned, so the score never falls below the greedy one; the same
+    /// `seed` gives the same plan. The assigned jobs stay the same.
+    pub fn optimize_annealed(
+        &self,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+        max_assignments: usize,
+        iterations: usize,
+        seed: u64,
+    ) -> Result<OptimizationResult, String> {
+        self.check_inputs(&machines)?;
+        Ok(self.optimize_annealed_native(&jobs, &machines, max_assignments, iterations, seed))
+    }
 
-        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+    /// Score every machine for a single job (diagnostic)
+    ///
+    /// Returns `(machine_id, score, violations)` for each machine, best score
+    /// first. Machines excluded by a hard constraint have no score and are
+    /// listed last with the reasons they were excluded.
+    pub fn rank_machines_for_job(
+        &self,
+        job: SchedulerJob,
+        machines: Vec<SchedulerMachine>,
+    ) -> Vec<(String, Option<f64>, Vec<String>)> {
+        let batch = BatchState::default();
+        let idle_reserved = self.idle_reserved(&job, &machines, &batch);
 
-        Ok(OptimizationResult {
-            assignments,
-            total_score,
-            unassigned_jobs,
-            optimization_time_ms: elapsed_ms,
-        })
-    }
+        let mut ranking: Vec<(String, Option<f64>, Vec<String>)> = machines
+            .iter()
+            .map(|machine| {
+                let (score, violations) =
+                    self.evaluate_machine(&job, machine, &batch, idle_reserved);
+                (machine.machine_id.clone(), score, violations)
+            })
+            .collect();
 
-    /// Get the current constraint configuration
-    pub fn get_config(&self) -> ConstraintConfig {
-        self.config.clone()
+        ranking.sort_by(|a, b| match (a.1, b.1) {
+            (Some(x), Some(y)) => y.total_cmp(&x),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        ranking
     }
-}
 
-impl SchedulerOptimizer {
-    fn find_best_machine(
+    /// The `n` best eligible machines for a single job (diagnostic)
+    ///
+    /// Each candidate is the assignment `optimize` would build for that
+    /// machine, best score first, so runners-up show how much they lost by
+    /// and which soft constraints they broke. Hard-excluded machines are left
+    /// out (see `rank_machines_for_job` for why).
+    pub fn candidates(
         &self,
-        job: &SchedulerJob,
-        machines: &[SchedulerMachine],
-        assigned: &HashSet<String>,
-        queue_additions: &HashMap<String, i32>,
-    ) -> Option<Assignment> {
-        let compatible_types = get_compatible_machine_types(&job.recipe_type);
-
-        let mut best_score = f64::NEG_INFINITY;
-        let mut best_machine: Option<&SchedulerMachine> = None;
-        let mut best_violations: Vec<String> = Vec::new();
+        job: SchedulerJob,
+        machines: Vec<SchedulerMachine>,
+        n: usize,
+    ) -> Vec<Assignment> {
+        let batch = BatchState::default();
+        let idle_reserved = self.idle_reserved(&job, &machines, &batch);
 
-        for machine in machines {
-            // Skip unavailable machines
-            if !machine.is_available() {
-                continue;
-            }
+        let mut candidates: Vec<Assignment> = machines
+            .iter()
+            .filter(|machine| !self.beyond_horizon(machine, &batch))
+            .filter_map(|machine| {
+                let (score, violations) =
+                    self.evaluate_machine(&job, machine, &batch, idle_reserved);
+                Some(self.build_assignment(&job, machine, score?, violations, &batch))
+            })
+            .collect();
 
-            // Skip already assigned machines (for this batch)
-            if assigned.contains(&machine.machine_id) {
-                continue;
-            }
+        // Stable, so ties keep machine order as in `find_best_machine`
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        candidates.truncate(n);
+        candidates
+    }
 
-            let mut violations = Vec::new();
+    /// Check a proposed schedule against the hard constraints
+    ///
+    /// `assignments` are `(job_id, machine_id)` pairs in queue order. Returns
+    /// each pair's job_id with its violations (empty when valid); nothing is
+    /// re-optimized.
+    pub fn validate_assignments(
+        &self,
+        assignments: Vec<(String, String)>,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+    ) -> Vec<(String, Vec<String>)> {
+        let mut batch = BatchState::default();
+        let mut seen_jobs = HashSet::new();
+        let mut machine_jobs: HashMap<&str, &str> = HashMap::new();
 
-            // Check recipe compatibility
-            let type_matches = compatible_types
-                .iter()
-                .any(|t| machine.machine_type.to_lowercase().contains(t));
+        assignments
+            .iter()
+            .map(|(job_id, machine_id)| {
+                let mut violations = Vec::new();
+                let job = jobs.iter().find(|j| &j.job_id == job_id);
+                let machine = machines.iter().find(|m| &m.machine_id == machine_id);
 
-            if self.config.enforce_recipe_match && !type_matches {
-                violations.push(format!(
-                    "Recipe {} incompatible with {}",
-                    job.recipe_type, machine.machine_type
-                ));
-                if self.config.enforce_recipe_match {
-                    continue; // Hard constraint
+                if !seen_jobs.insert(job_id.as_str()) {
+                    violations.push("Job assigned more than once".to_string());
                 }
-            }
 
-            // Calculate score
-            let score = self.calculate_score(job, machine, queue_additions);
+                let (job, machine) = match (job, machine) {
+                    (Some(job), Some(machine)) => (job, machine),
+                    (job, machine) => {
+                        if job.is_none() {
+                            violations.push(format!("Unknown job {}", job_id));
+                        }
+                        if machine.is_none() {
+                            violations.push(format!("Unknown machine {}", machine_id));
+                        }
+                        return (job_id.clone(), violations);
+                    }
+                };
 
-            // Check deadline constraint
-            if let Some(deadline) = job.deadline_hours {
-                let additional_queue = queue_additions.get(&machine.machine_id).unwrap_or(&0);
-                let estimated_start =
-                    machine.estimated_available_hours + (*additional_queue as f64 * 2.0);
-                if estimated_start > deadline {
+                if let Some(violation) = Self::availability_violation(machine) {
+                    violations.push(violation);
+                }
+                if !self.is_compatible(job, machine) {
                     violations.push(format!(
-                        "Would miss deadline by {:.1}h",
-                        estimated_start - deadline
+                        "Recipe {} incompatible with {}",
+                        job.recipe_type, machine.machine_type
                     ));
-                    if self.config.enforce_deadlines {
-                        continue; // Hard constraint
+                }
+                let cap = self.jobs_per_machine_cap();
+                let first = *machine_jobs.entry(machine_id).or_insert(job_id);
+                if batch.queue_additions(machine_id) as usize >= cap {
+                    violations.push(if cap == 1 {
+                        format!("Machine {} double-booked with {}", machine.name, first)
+                    } else {
+                        format!("Machine {} over its limit of {} jobs", machine.name, cap)
+                    });
+                }
+                if let Some(limit) = machine.max_queue_depth {
+                    if machine.current_queue_depth + batch.queue_additions(machine_id) >= limit {
+                        violations.push(format!("Queue at its limit of {} lots", limit));
+                    }
+                }
+                if let Some(capacity) = machine.max_wafer_capacity {
+                    let wafers = batch.wafers_added(machine_id) + job.wafer_count;
+                    if wafers > capacity {
+                        violations.push(format!(
+                            "Wafer capacity exceeded ({} of {} wafers)",
+                            wafers, capacity
+                        ));
+                    }
+                }
+                if let Some(late) = self.soft_deadline_lateness(job, machine, &batch) {
+                    violations.push(format!("Would miss soft deadline by {:.1}h", late));
+                }
+                if let Some(deadline) = job.deadline_hours {
+                    let estimated_start = self.job_start(job, machine, &batch);
+                    if estimated_start > deadline {
+                        violations.push(format!(
+                            "Would miss deadline by {:.1}h",
+                            estimated_start - deadline
+                        ));
                     }
                 }
-            }
 
-            if score > best_score {
-                best_score = score;
-                best_machine = Some(machine);
-                best_violations = violations;
-            }
-        }
+                let finish = self.job_start(job, machine, &batch) + self.processing_hours(job);
+                let hours = finish - Self::estimated_start(machine, &batch);
+                batch.record(&machine.machine_id, job, hours);
+                batch.record_finish(job_id, finish);
+                (job_id.clone(), violations)
+            })
+            .collect()
+    }
 
-        best_machine.map(|machine| {
-            let additional_queue = queue_additions.get(&machine.machine_id).unwrap_or(&0);
-            let estimated_start =
-                machine.estimated_available_hours + (*additional_queue as f64 * 2.0);
+    /// Value of adding `candidate` to the fleet
+    ///
+    /// Runs the optimizer with and without the candidate machine and reports
+    /// the difference in total score and number of assigned jobs.
+    pub fn marginal_machine_value(
+        &self,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+        candidate: SchedulerMachine,
+        max_assignments: usize,
+    ) -> Result<MarginalValue, String> {
+        let baseline = self.optimize(jobs.clone(), machines.clone(), max_assignments)?;
 
-            let mut reason_parts = vec![
-                format!("Optimizer v1.0"),
-                format!("Score: {:.2}", best_score),
-                format!("Efficiency: {:.0}%", machine.efficiency_rating * 100.0),
-            ];
-            if job.is_hot_lot {
-                reason_parts.insert(1, "HOT LOT".to_string());
-            }
+        let machine_id = candidate.machine_id.clone();
+        let mut expanded = machines;
+        expanded.push(candidate);
+        let with_candidate = self.optimize(jobs, expanded, max_assignments)?;
 
-            Assignment {
-                job_id: job.job_id.clone(),
-                job_name: job.job_name.clone(),
-                machine_id: machine.machine_id.clone(),
-                machine_name: machine.name.clone(),
-                score: best_score,
-                reason: reason_parts.join(" | "),
-                estimated_start_hours: estimated_start,
-                constraint_violations: best_violations,
-            }
+        Ok(MarginalValue {
+            machine_id,
+            score_delta: with_candidate.total_score - baseline.total_score,
+            assignment_delta: with_candidate.assignments.len() as i64
+                - baseline.assignments.len() as i64,
         })
     }
 
-    fn calculate_score(
+    /// Schedules trading total score against on-time jobs
+    ///
+    /// Re-runs the optimizer with `deadline_weight` swept from 0 to
+    /// `PARETO_MAX_DEADLINE_WEIGHT` in `steps` steps and keeps the
+    /// non-dominated schedules, fewest on-time jobs first. A schedule's
+    /// `total_score` leaves out the deadline objective, which `on_time_count`
+    /// measures instead, so points from different weights are comparable.
+    pub fn pareto_schedules(
         &self,
-        job: &SchedulerJob,
-        machine: &SchedulerMachine,
-        queue_additions: &HashMap<String, i32>,
-    ) -> f64 {
-        let mut score = 0.0;
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+        max_assignments: usize,
+        steps: usize,
+    ) -> Result<Vec<ParetoPoint>, String> {
+        self.check_inputs(&machines)?;
 
-        // Priority score (higher priority = higher score)
-        // Priority 1 (hot) = 1.0, Priority 5 = 0.2
-        let priority_score = if job.is_hot_lot {
-            1.0
-        } else {
-            1.0 - ((job.priority_level - 1) as f64 * 0.2)
-        };
-        score += priority_score * self.config.priority_weight;
+        let steps = steps.max(2);
+        let points: Vec<ParetoPoint> = (0..steps)
+            .map(|step| {
+                let deadline_weight = PARETO_MAX_DEADLINE_WEIGHT * step as f64 / (steps - 1) as f64;
+                let sweep = SchedulerOptimizer::new(Some(ConstraintConfig {
+                    deadline_weight,
+                    ..self.config.clone()
+                }))
+                .with_qualifications(self.qualifications.clone());
+                let result = sweep.optimize_native(&jobs, &machines, max_assignments);
+                let total_score = result
+                    .assignments
+                    .iter()
+                    .map(|a| a.score - a.score_breakdown.get("deadline").copied().unwrap_or(0.0))
+                    .sum();
+                // On time when the job (or its sub-lot) finishes by both deadlines
+                let on_time_count = result
+                    .assignments
+                    .iter()
+                    .filter(|a| {
+                        let parent = a
+                            .job_id
+                            .rsplit_once('#')
+                            .map_or(a.job_id.as_str(), |(parent, _)| parent);
+                        jobs.iter()
+                            .find(|j| j.job_id == a.job_id || j.job_id == parent)
+                            .is_none_or(|j| {
+                                [j.deadline_hours, j.soft_deadline_hours]
+                                    .into_iter()
+                                    .flatten()
+                                    .all(|deadline| a.estimated_finish_hours <= deadline)
+                            })
+                    })
+                    .count();
+                ParetoPoint {
+                    deadline_weight,
+                    total_score,
+                    on_time_count,
+                    assignments: result.assignments,
+                }
+            })
+            .collect();
 
-        // Efficiency score
-        score += machine.efficiency_rating * self.config.efficiency_weight;
+        // Drop dominated points, and repeats of a trade-off already kept
+        let mut front: Vec<ParetoPoint> = points
+            .iter()
+            .enumerate()
+            .filter(|(i, point)| {
+                !points.iter().enumerate().any(|(j, other)| {
+                    other.dominates(point) || (j < *i && other.same_tradeoff(point))
+                })
+            })
+            .map(|(_, point)| point.clone())
+            .collect();
+        front.sort_by(|a, b| {
+            a.on_time_count
+                .cmp(&b.on_time_count)
+                .then_with(|| b.total_score.total_cmp(&a.total_score))
+        });
+        Ok(front)
+    }
 
-        // Deadline urgency score
-        if let Some(deadline) = job.deadline_hours {
-            // More urgent = higher score
-            let urgency = (24.0 - deadline.min(24.0)) / 24.0;
-            score += urgency * self.config.deadline_weight;
-        }
+    /// Group a result's unassigned jobs by cause and suggest the smallest fix
+    ///
+    /// Jobs are grouped by the machine type they need. Unavailable tools
+    /// are preferred over new capacity, most efficient first, and only as
+    /// many as there are unassigned jobs are named. Largest groups first.
+    pub fn unassigned_remedies(
+        &self,
+        result: OptimizationResult,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+    ) -> Vec<UnassignedRemedy> {
+        let busy: HashSet<&str> = result
+            .assignments
+            .iter()
+            .map(|a| a.machine_id.as_str())
+            .collect();
 
-        // Queue depth score (lower queue = higher score)
-        let additional = queue_additions.get(&machine.machine_id).unwrap_or(&0);
-        let total_queue = machine.current_queue_depth + additional;
-        let queue_score = 1.0 / (1.0 + total_queue as f64 * 0.2);
-        score += queue_score * self.config.queue_depth_weight;
+        // Sub-lots from `split_lot` are reported as `{job_id}#k`
+        let find_job = |job_id: &str| {
+            let parent = job_id.rsplit_once('#').map_or(job_id, |(parent, _)| parent);
+            jobs.iter()
+                .find(|j| j.job_id == job_id)
+                .or_else(|| jobs.iter().find(|j| j.job_id == parent))
+        };
 
-        // Status bonus
-        if machine.status == "IDLE" {
-            score += 0.1;
-        }
+        let mut groups: Vec<UnassignedRemedy> = Vec::new();
+        for job_id in &result.unassigned_jobs {
+            let Some(job) = find_job(job_id) else {
+                continue;
+            };
+            let machine_type = get_compatible_machine_types(&job.recipe_type).join("/");
+            let budget_hit = result
+                .unassigned_reasons
+                .get(job_id)
+                .is_some_and(|r| r.starts_with("Recipe budget exhausted"));
 
-        score
+            let compatible: Vec<&SchedulerMachine> = machines
+                .iter()
+                .filter(|m| self.is_compatible(job, m))
+                .collect();
+            let cause = if budget_hit {
+                "recipe_budget"
+            } else if compatible.is_empty() {
+                "no_compatible_machine"
+            } else if compatible
+                .iter()
+                .any(|m| busy.contains(m.machine_id.as_str()))
+            {
+                "capacity"
+            } else if compatible.iter().all(|m| !m.is_available()) {
+                "machines_unavailable"
+            } else {
+                "constraints"
+            };
+
+            match groups
+                .iter_mut()
+                .find(|g| g.machine_type == machine_type && g.cause == cause)
+            {
+                Some(group) => group.job_ids.push(job_id.clone()),
+                None => groups.push(UnassignedRemedy {
+                    machine_type,
+                    job_ids: vec![job_id.clone()],
+                    cause: cause.to_string(),
+                    machines: Vec::new(),
+                    suggestion: String::new(),
+                }),
+            }
+        }
+
+        for group in &mut groups {
+            let count = group.job_ids.len();
+            let mut down: Vec<&SchedulerMachine> = machines
+                .iter()
+                .filter(|m| {
+                    !m.is_available()
+                        && group
+                            .job_ids
+                            .iter()
+                            .filter_map(|job_id| find_job(job_id))
+                            .any(|job| self.is_compatible(job, m))
+                })
+                .collect();
+            down.sort_by(|a, b| {
+                b.efficiency_rating
+                    .total_cmp(&a.efficiency_rating)
+                    .then_with(|| a.machine_id.cmp(&b.machine_id))
+            });
+            group.machines = down
+                .iter()
+                .take(count)
+                .map(|m| m.machine_id.clone())
+                .collect();
+
+            let jobs_label = format!("{} {} job(s) unassigned", count, group.machine_type);
+            group.suggestion = match group.cause.as_str() {
+                "recipe_budget" => format!(
+                    "{}: recipe budget exhausted; raise max_assignments_per_recipe by {}",
+                    jobs_label, count
+                ),
+                "no_compatible_machine" => format!(
+                    "{}: no compatible tool; add {} {} tool(s)",
+                    jobs_label, count, group.machine_type
+                ),
+                "machines_unavailable" => format!(
+                    "{}: all {} tools down; bring up {}",
+                    jobs_label,
+                    group.machine_type,
+                    group.machines.join(", ")
+                ),
+                "capacity" if !group.machines.is_empty() => format!(
+                    "{}: all {} tools down or assigned; add {} tool(s) or bring up {}",
+                    jobs_label,
+                    group.machine_type,
+                    count,
+                    group.machines.join(", ")
+                ),
+                "capacity" => format!(
+                    "{}: all {} tools assigned; add {} tool(s) or raise max_jobs_per_machine",
+                    jobs_label, group.machine_type, count
+                ),
+                _ => format!(
+                    "{}: tools available but excluded; review deadlines, cooldowns and hot-lot reserve",
+                    jobs_label
+                ),
+            };
+        }
+
+        groups.sort_by(|a, b| {
+            b.job_ids
+                .len()
+                .cmp(&a.job_ids.len())
+                .then_with(|| a.machine_type.cmp(&b.machine_type))
+        });
+        groups
+    }
+
+    /// Get the current constraint configuration
+    pub fn get_config(&self) -> ConstraintConfig {
+        self.config.clone()
+    }
+}
+
+impl SchedulerOptimizer {
+    pub fn new(config: Option<ConstraintConfig>) -> Self {
+        Self {
+            config: config.unwrap_or_default(),
+            qualifications: HashMap::new(),
+        }
+    }
+
+    /// Use per-machine recipe qualifications instead of type inference
+    pub fn with_qualifications(mut self, qualifications: HashMap<String, HashSet<String>>) -> Self {
+        self.qualifications = qualifications;
+        self
+    }
+
+    /// Config and machine checks shared by the public entry points
+    fn check_inputs(&self, machines: &[SchedulerMachine]) -> Result<(), String> {
+        if let Some(weight) = self.config.non_finite_weight() {
+            return Err(format!("ConstraintConfig.{} must be finite", weight));
+        }
+        if !REASON_VERBOSITIES.contains(&self.config.reason_verbosity.as_str()) {
+            return Err(format!(
+                "ConstraintConfig.reason_verbosity must be one of {:?}, got {:?}",
+                REASON_VERBOSITIES, self.config.reason_verbosity
+            ));
+        }
+        if !MATCH_MODES.contains(&self.config.match_mode.as_str()) {
+            return Err(format!(
+                "ConstraintConfig.match_mode must be one of {:?}, got {:?}",
+                MATCH_MODES, self.config.match_mode
+            ));
+        }
+        if let Some(target) = self.config.target_utilization {
+            if !(target.is_finite() && target > 0.0) {
+                return Err(format!(
+                    "ConstraintConfig.target_utilization must be positive, got {}",
+                    target
+                ));
+            }
+        }
+        if self.config.strict_validation {
+            for machine in machines {
+                machine.validate()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Dispatch order: hot lots first, then by (aged) priority level
+    fn dispatch_order(&self, a: &SchedulerJob, b: &SchedulerJob) -> std::cmp::Ordering {
+        match (a.is_hot_lot, b.is_hot_lot) {
+            (true, false) => std::cmp::Ordering::Less,
+            (false, true) => std::cmp::Ordering::Greater,
+            _ => self
+                .effective_priority(a)
+                .total_cmp(&self.effective_priority(b)),
+        }
+    }
+
+    /// Priority level after aging: one level better per
+    /// `aging_hours_per_level` waited, never better than 1
+    fn effective_priority(&self, job: &SchedulerJob) -> f64 {
+        let level = job.priority_level as f64;
+        let aging = self.config.aging_hours_per_level;
+        if aging > 0.0 && job.waiting_hours.is_finite() {
+            (level - job.waiting_hours.max(0.0) / aging).max(level.min(1.0))
+        } else {
+            level
+        }
+    }
+
+    /// Pure-Rust entry point behind `optimize` (benchmarks, other Rust callers)
+    pub fn optimize_native(
+        &self,
+        jobs: &[SchedulerJob],
+        machines: &[SchedulerMachine],
+        max_assignments: usize,
+    ) -> OptimizationResult {
+        self.schedule(jobs, machines, max_assignments, BatchState::default(), &[])
+    }
+
+    /// Pure-Rust entry point behind `optimize_incremental`
+    pub fn optimize_incremental_native(
+        &self,
+        jobs: &[SchedulerJob],
+        machines: &[SchedulerMachine],
+        locked: &[Assignment],
+        max_assignments: usize,
+    ) -> OptimizationResult {
+        let mut batch = BatchState::default();
+        for assignment in locked {
+            batch.record_finish(&assignment.job_id, assignment.estimated_finish_hours);
+            let Some(machine) = machines
+                .iter()
+                .find(|m| m.machine_id == assignment.machine_id)
+            else {
+                continue;
+            };
+            let busy = (assignment.estimated_finish_hours - Self::estimated_start(machine, &batch))
+                .max(0.0);
+            match jobs.iter().find(|j| j.job_id == assignment.job_id) {
+                Some(job) => {
+                    batch.record(&machine.machine_id, job, busy);
+                    batch.record_recipe(&job.recipe_type);
+                }
+                None => batch.reserve(&machine.machine_id, busy),
+            }
+        }
+
+        self.schedule(jobs, machines, max_assignments, batch, locked)
+    }
+
+    /// Pure-Rust entry point behind `optimize_annealed`
+    pub fn optimize_annealed_native(
+        &self,
+        jobs: &[SchedulerJob],
+        machines: &[SchedulerMachine],
+        max_assignments: usize,
+        iterations: usize,
+        seed: u64,
+    ) -> OptimizationResult {
+        let start = std::time::Instant::now();
+        let greedy = self.optimize_native(jobs, machines, max_assignments);
+
+        // The greedy plan as (job, machine index) pairs, in placement order
+        let placed: HashMap<String, SchedulerJob> = jobs
+            .iter()
+            .flat_map(|job| self.split_lot(job))
+            .map(|job| (job.job_id.clone(), job))
+            .collect();
+        let plan: Option<Vec<(&SchedulerJob, usize)>> = greedy
+            .assignments
+            .iter()
+            .map(|a| {
+                let job = placed.get(&a.job_id)?;
+                let machine = machines.iter().position(|m| m.machine_id == a.machine_id)?;
+                Some((job, machine))
+            })
+            .collect();
+        let Some(mut current) = plan.filter(|plan| !plan.is_empty()) else {
+            return greedy;
+        };
+        let Some(mut current_score) = self.replay(&current, machines).map(|a| total_score(&a))
+        else {
+            return greedy;
+        };
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let cooling = (ANNEAL_END_TEMPERATURE / ANNEAL_START_TEMPERATURE)
+            .powf(1.0 / iterations.max(1) as f64);
+        let mut temperature = ANNEAL_START_TEMPERATURE;
+        let mut best: Option<Vec<Assignment>> = None;
+        let mut best_score = greedy.total_score;
+        for _ in 0..iterations {
+            temperature *= cooling;
+            let mut candidate = current.clone();
+            let i = rng.gen_range(0..candidate.len());
+            if candidate.len() > 1 && rng.gen_bool(0.5) {
+                let j = rng.gen_range(0..candidate.len());
+                let machine = candidate[i].1;
+                candidate[i].1 = candidate[j].1;
+                candidate[j].1 = machine;
+            } else {
+                candidate[i].1 = rng.gen_range(0..machines.len());
+            }
+
+            let Some(assignments) = self.replay(&candidate, machines) else {
+                continue; // Hard constraint
+            };
+            let score = total_score(&assignments);
+            let delta = score - current_score;
+            if delta >= 0.0 || rng.gen::<f64>() < (delta / temperature).exp() {
+                current = candidate;
+                current_score = score;
+                if score > best_score + SCORE_TIE_EPSILON {
+                    best_score = score;
+                    best = Some(assignments);
+                }
+            }
+        }
+
+        match best {
+            Some(assignments) => OptimizationResult {
+                total_score: best_score,
+                total_weighted_tardiness: assignments.iter().map(|a| a.weighted_tardiness).sum(),
+                assignments,
+                optimization_time_ms: start.elapsed().as_secs_f64() * 1000.0,
+                ..greedy
+            },
+            None => greedy,
+        }
+    }
+
+    /// Place each job on the given machine, in order, as `schedule` would
+    ///
+    /// `None` if any placement breaks a hard constraint.
+    fn replay(
+        &self,
+        plan: &[(&SchedulerJob, usize)],
+        machines: &[SchedulerMachine],
+    ) -> Option<Vec<Assignment>> {
+        let mut batch = BatchState::default();
+        let mut assignments = Vec::with_capacity(plan.len());
+        for &(job, index) in plan {
+            let machine = &machines[index];
+            let idle_reserved = self.idle_reserved(job, machines, &batch);
+            let (score, violations) = self.evaluate_machine(job, machine, &batch, idle_reserved);
+            let score = score?;
+            if self.beyond_horizon(machine, &batch) {
+                return None;
+            }
+            let assignment = self.build_assignment(job, machine, score, violations, &batch);
+            let busy = assignment.estimated_finish_hours - Self::estimated_start(machine, &batch);
+            batch.record(&machine.machine_id, job, busy);
+            batch.record_finish(&job.job_id, assignment.estimated_finish_hours);
+            batch.record_recipe(&job.recipe_type);
+            assignments.push(assignment);
+        }
+        Some(assignments)
+    }
+
+    /// Greedy placement in dispatch order, on top of what `batch` already holds
+    ///
+    /// `locked` assignments (already recorded in `batch`) lead the result and
+    /// their jobs are not placed again.
+    fn schedule(
+        &self,
+        jobs: &[SchedulerJob],
+        machines: &[SchedulerMachine],
+        max_assignments: usize,
+        mut batch: BatchState,
+        locked: &[Assignment],
+    ) -> OptimizationResult {
+        let start = std::time::Instant::now();
+
+        // Jobs on the schedule so far, by id (candidates for preemption)
+        let locked_ids: HashSet<&str> = locked.iter().map(|a| a.job_id.as_str()).collect();
+        let mut placed_jobs: HashMap<String, SchedulerJob> = jobs
+            .iter()
+            .filter(|job| locked_ids.contains(job.job_id.as_str()))
+            .map(|job| (job.job_id.clone(), job.clone()))
+            .collect();
+
+        // Sort jobs by priority (hot lots first, then by priority level)
+        let mut sorted_jobs: Vec<SchedulerJob> = jobs
+            .iter()
+            .filter(|job| !locked_ids.contains(job.job_id.as_str()))
+            .cloned()
+            .collect();
+        sorted_jobs.sort_by(|a, b| self.dispatch_order(a, b));
+
+        // Predecessors go first, otherwise priority order is kept
+        let (sorted_jobs, blocked) = Self::order_by_dependencies(sorted_jobs, &batch.finished);
+
+        let mut assignments = locked.to_vec();
+        let mut placed = 0;
+        let mut unassigned_jobs = Vec::new();
+        let mut unassigned_reasons = HashMap::new();
+        let mut deferred_jobs = Vec::new();
+        let mut preempted_jobs = Vec::new();
+        for (job_id, reason) in blocked {
+            unassigned_jobs.push(job_id.clone());
+            unassigned_reasons.insert(job_id, reason);
+        }
+        let sorted_jobs: Vec<SchedulerJob> = sorted_jobs
+            .iter()
+            .flat_map(|job| self.split_lot(job))
+            .collect();
+
+        // Every job gets a placement attempt, in dispatch order, until the
+        // batch is full; unplaceable jobs don't use up the batch
+        let considered = self
+            .config
+            .max_jobs_considered
+            .unwrap_or(sorted_jobs.len())
+            .min(sorted_jobs.len());
+        let (sorted_jobs, untried) = sorted_jobs.split_at(considered);
+        deferred_jobs.extend(untried.iter().map(|job| job.job_id.clone()));
+
+        // One machine per recipe: each job only needs its own machine scored
+        let dedicated = self.dedicated_machines(sorted_jobs, machines);
+
+        for job in sorted_jobs {
+            if placed >= max_assignments {
+                break;
+            }
+
+            if let Some(dep) = job
+                .depends_on
+                .iter()
+                .find(|dep| !batch.finished.contains_key(*dep))
+            {
+                unassigned_jobs.push(job.job_id.clone());
+                unassigned_reasons.insert(
+                    job.job_id.clone(),
+                    format!("Dependency {} was not assigned", dep),
+                );
+                continue;
+            }
+
+            if self.recipe_budget_exhausted(job, &batch) {
+                unassigned_jobs.push(job.job_id.clone());
+                unassigned_reasons.insert(
+                    job.job_id.clone(),
+                    format!("Recipe budget exhausted for {}", job.recipe_type),
+                );
+                continue;
+            }
+
+            // Find best machine for this job
+            let candidates = match &dedicated {
+                Some(dedicated) => std::slice::from_ref(&machines[dedicated[&job.recipe_type]]),
+                None => machines,
+            };
+            let mut best_assignment = self.find_best_machine(job, candidates, &batch);
+            if best_assignment.is_none() && job.is_hot_lot && self.config.allow_hot_lot_preemption {
+                if let Some((victim, assignment, freed)) =
+                    self.preempt(job, machines, &assignments, &placed_jobs, &batch)
+                {
+                    // The victim leaves the schedule; if it was placed in
+                    // this batch, its slot is free again
+                    let victim = assignments.remove(victim);
+                    placed_jobs.remove(&victim.job_id);
+                    if !locked_ids.contains(victim.job_id.as_str()) {
+                        placed -= 1;
+                    }
+                    preempted_jobs.push(victim.job_id);
+                    batch = freed;
+                    best_assignment = Some(assignment);
+                }
+            }
+
+            match best_assignment {
+                Some(assignment) => {
+                    // The machine is busy until the job finishes, including
+                    // any changeover or wait for predecessors
+                    let busy = machines
+                        .iter()
+                        .find(|m| m.machine_id == assignment.machine_id)
+                        .map_or(self.processing_hours(job), |m| {
+                            assignment.estimated_finish_hours - Self::estimated_start(m, &batch)
+                        });
+                    batch.record(&assignment.machine_id, job, busy);
+                    batch.record_finish(&job.job_id, assignment.estimated_finish_hours);
+                    batch.record_recipe(&job.recipe_type);
+                    assignments.push(assignment);
+                    placed_jobs.insert(job.job_id.clone(), job.clone());
+                    placed += 1;
+                }
+                None => match self.unplaced_reason(job, machines, &batch) {
+                    Some(reason) => {
+                        unassigned_jobs.push(job.job_id.clone());
+                        unassigned_reasons.insert(job.job_id.clone(), reason.to_string());
+                    }
+                    None => deferred_jobs.push(job.job_id.clone()),
+                },
+            }
+        }
+
+        // Calculate total score
+        let total_score: f64 = assignments.iter().map(|a| a.score).sum();
+        let total_weighted_tardiness: f64 = assignments.iter().map(|a| a.weighted_tardiness).sum();
+        let (total_wafers_assigned, total_wafers_unassigned) = wafer_totals(jobs, &assignments);
+
+        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        OptimizationResult {
+            assignments,
+            total_score,
+            total_weighted_tardiness,
+            unassigned_jobs,
+            unassigned_reasons,
+            deferred_jobs,
+            preempted_jobs,
+            total_wafers_assigned,
+            total_wafers_unassigned,
+            optimization_time_ms: elapsed_ms,
+        }
+    }
+
+    /// Pure-Rust entry point behind `optimize_global`
+    pub fn optimize_global_native(
+        &self,
+        jobs: &[SchedulerJob],
+        machines: &[SchedulerMachine],
+        max_assignments: usize,
+        warm_start: Option<&OptimizationResult>,
+    ) -> OptimizationResult {
+        let start = std::time::Instant::now();
+        let batch = BatchState::default();
+
+        let mut batch_jobs = jobs.to_vec();
+        batch_jobs.sort_by(|a, b| self.dispatch_order(a, b));
+        batch_jobs.truncate(max_assignments);
+
+        // Job×machine scores; None where a hard constraint rules the pair out
+        let evaluations: Vec<Vec<(Option<f64>, Vec<String>)>> = batch_jobs
+            .iter()
+            .map(|job| {
+                machines
+                    .iter()
+                    .map(|machine| {
+                        if self.beyond_horizon(machine, &batch) {
+                            (None, Vec::new())
+                        } else {
+                            self.evaluate_machine(job, machine, &batch, false)
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let scores: Vec<Vec<Option<f64>>> = evaluations
+            .iter()
+            .map(|row| row.iter().map(|(score, _)| *score).collect())
+            .collect();
+        // Previous machine of each job, where it is still in the fleet
+        let seed: Vec<Option<usize>> = batch_jobs
+            .iter()
+            .map(|job| {
+                let previous = warm_start?
+                    .assignments
+                    .iter()
+                    .find(|a| a.job_id == job.job_id)?;
+                machines
+                    .iter()
+                    .position(|m| m.machine_id == previous.machine_id)
+            })
+            .collect();
+        let matching = assignment::max_weight_matching(&scores, &seed);
+
+        let mut assignments = Vec::new();
+        let mut unassigned_jobs = Vec::new();
+        let mut unassigned_reasons = HashMap::new();
+        let mut deferred_jobs = Vec::new();
+        for ((job, mut row), matched) in batch_jobs.iter().zip(evaluations).zip(matching) {
+            match matched {
+                Some(col) => {
+                    let (score, violations) = row.swap_remove(col);
+                    assignments.push(self.build_assignment(
+                        job,
+                        &machines[col],
+                        score.unwrap_or_default(),
+                        violations,
+                        &batch,
+                    ));
+                }
+                None if row.iter().any(|(score, _)| score.is_some()) => {
+                    unassigned_jobs.push(job.job_id.clone());
+                    unassigned_reasons.insert(
+                        job.job_id.clone(),
+                        "Eligible machines went to higher-scoring assignments".to_string(),
+                    );
+                }
+                None => match self.unplaced_reason(job, machines, &batch) {
+                    Some(reason) => {
+                        unassigned_jobs.push(job.job_id.clone());
+                        unassigned_reasons.insert(job.job_id.clone(), reason.to_string());
+                    }
+                    None => deferred_jobs.push(job.job_id.clone()),
+                },
+            }
+        }
+
+        let total_score: f64 = assignments.iter().map(|a| a.score).sum();
+        let total_weighted_tardiness: f64 = assignments.iter().map(|a| a.weighted_tardiness).sum();
+        let (total_wafers_assigned, total_wafers_unassigned) = wafer_totals(jobs, &assignments);
+
+        OptimizationResult {
+            assignments,
+            total_score,
+            total_weighted_tardiness,
+            unassigned_jobs,
+            unassigned_reasons,
+            deferred_jobs,
+            preempted_jobs: Vec::new(),
+            total_wafers_assigned,
+            total_wafers_unassigned,
+            optimization_time_ms: start.elapsed().as_secs_f64() * 1000.0,
+        }
+    }
+
+    fn find_best_machine(
+        &self,
+        job: &SchedulerJob,
+        machines: &[SchedulerMachine],
+        batch: &BatchState,
+    ) -> Option<Assignment> {
+        let idle_reserved = self.idle_reserved(job, machines, batch);
+
+        let mut best_score = f64::NEG_INFINITY;
+        let mut best_machine: Option<&SchedulerMachine> = None;
+        let mut best_violations: Vec<String> = Vec::new();
+        let tie_margin = if self.config.load_balance {
+            SCORE_TIE_EPSILON
+        } else {
+            0.0
+        };
+
+        // Scoring only reads the batch snapshot, so large fleets are scored in
+        // parallel; picking the best stays a serial pass in machine order so
+        // tie-breaks match the serial path exactly
+        let evaluate =
+            |machine: &SchedulerMachine| self.evaluate_machine(job, machine, batch, idle_reserved);
+        let evaluations: Vec<(Option<f64>, Vec<String>)> =
+            if machines.len() >= self.config.parallel_machine_threshold {
+                machines.par_iter().map(evaluate).collect()
+            } else {
+                machines.iter().map(evaluate).collect()
+            };
+
+        for (machine, (score, violations)) in machines.iter().zip(evaluations) {
+            let Some(score) = score else {
+                continue; // Hard constraint
+            };
+            if self.beyond_horizon(machine, batch) {
+                continue;
+            }
+
+            // Among equally good machines of one type, prefer the least loaded
+            let less_loaded_tie = self.config.load_balance
+                && best_machine.is_some_and(|best| {
+                    (score - best_score).abs() <= SCORE_TIE_EPSILON
+                        && best.machine_type == machine.machine_type
+                        && batch.queue_additions(&machine.machine_id)
+                            < batch.queue_additions(&best.machine_id)
+                });
+
+            if score > best_score + tie_margin || less_loaded_tie {
+                best_score = score;
+                best_machine = Some(machine);
+                best_violations = violations;
+            }
+        }
+
+        best_machine
+            .map(|machine| self.build_assignment(job, machine, best_score, best_violations, batch))
+    }
+
+    /// Free a compatible machine for a hot lot by bumping a placed job
+    ///
+    /// The lowest-priority job goes first (the latest placed among equals).
+    /// Hot lots and jobs another placed job depends on are never bumped.
+    /// Returns the victim's index in `assignments`, the hot lot's assignment
+    /// on the freed machine and the batch without the victim.
+    fn preempt(
+        &self,
+        job: &SchedulerJob,
+        machines: &[SchedulerMachine],
+        assignments: &[Assignment],
+        placed_jobs: &HashMap<String, SchedulerJob>,
+        batch: &BatchState,
+    ) -> Option<(usize, Assignment, BatchState)> {
+        let mut candidates: Vec<(usize, &SchedulerJob, &SchedulerMachine)> = assignments
+            .iter()
+            .enumerate()
+            .filter_map(|(index, assignment)| {
+                let victim = placed_jobs.get(&assignment.job_id)?;
+                let machine = machines
+                    .iter()
+                    .find(|m| m.machine_id == assignment.machine_id)?;
+                let parent = victim
+                    .job_id
+                    .rsplit_once('#')
+                    .map_or(victim.job_id.as_str(), |(parent, _)| parent);
+                let needed = placed_jobs.values().any(|other| {
+                    other
+                        .depends_on
+                        .iter()
+                        .any(|dep| *dep == victim.job_id || dep == parent)
+                });
+                (!victim.is_hot_lot && !needed && self.is_compatible(job, machine))
+                    .then_some((index, victim, machine))
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            self.effective_priority(b.1)
+                .total_cmp(&self.effective_priority(a.1))
+                .then(b.0.cmp(&a.0))
+        });
+
+        candidates.into_iter().find_map(|(index, victim, machine)| {
+            let mut freed = batch.clone();
+            freed.release(&machine.machine_id, victim);
+            let mut assignment =
+                self.find_best_machine(job, std::slice::from_ref(machine), &freed)?;
+            assignment
+                .reason_factors
+                .push(("preempted".to_string(), victim.job_id.clone()));
+            Some((index, assignment, freed))
+        })
+    }
+
+    /// Reorder jobs so each follows the jobs it depends on
+    ///
+    /// Among jobs whose predecessors are already placed, the earliest in the
+    /// incoming (priority) order goes next. Jobs depending on an id missing
+    /// from the batch, or caught in a cycle, come back separately with a reason.
+    /// Jobs in `finished` (already placed) count as satisfied dependencies.
+    fn order_by_dependencies(
+        jobs: Vec<SchedulerJob>,
+        finished: &HashMap<String, f64>,
+    ) -> (Vec<SchedulerJob>, Vec<(String, String)>) {
+        let ids: HashSet<&str> = jobs
+            .iter()
+            .map(|job| job.job_id.as_str())
+            .chain(finished.keys().map(String::as_str))
+            .collect();
+        let mut blocked = Vec::new();
+        let mut placed: HashSet<String> = finished.keys().cloned().collect();
+        let mut remaining = Vec::new();
+        for job in &jobs {
+            match job
+                .depends_on
+                .iter()
+                .find(|dep| !ids.contains(dep.as_str()))
+            {
+                Some(dep) => {
+                    blocked.push((job.job_id.clone(), format!("Missing dependency {}", dep)));
+                    // Dependents still sort after it and fail as unassigned
+                    placed.insert(job.job_id.clone());
+                }
+                None => remaining.push(job.clone()),
+            }
+        }
+
+        let mut ordered = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let Some(next) = remaining
+                .iter()
+                .position(|job| job.depends_on.iter().all(|dep| placed.contains(dep)))
+            else {
+                for job in remaining {
+                    blocked.push((job.job_id, "Circular dependency".to_string()));
+                }
+                break;
+            };
+            let job = remaining.remove(next);
+            placed.insert(job.job_id.clone());
+            ordered.push(job);
+        }
+        (ordered, blocked)
+    }
+
+    /// Split a lot over `max_wafers_per_assignment` into sub-lots
+    ///
+    /// Processing time is shared out by wafer count. Lots under the limit,
+    /// and hot lots under `no_split_hot_lots`, come back unchanged.
+    fn split_lot(&self, job: &SchedulerJob) -> Vec<SchedulerJob> {
+        let Some(max_wafers) = self.config.max_wafers_per_assignment.filter(|&m| m > 0) else {
+            return vec![job.clone()];
+        };
+        if job.wafer_count <= max_wafers || (job.is_hot_lot && self.config.no_split_hot_lots) {
+            return vec![job.clone()];
+        }
+
+        let parts = (job.wafer_count + max_wafers - 1) / max_wafers;
+        let hours = self.processing_hours(job);
+        (0..parts)
+            .map(|k| {
+                let wafers = (job.wafer_count - k * max_wafers).min(max_wafers);
+                let mut part = job.clone();
+                part.job_id = format!("{}#{}", job.job_id, k + 1);
+                part.job_name = format!("{} ({}/{})", job.job_name, k + 1, parts);
+                part.wafer_count = wafers;
+                part.processing_hours = Some(hours * wafers as f64 / job.wafer_count as f64);
+                part
+            })
+            .collect()
+    }
+
+    /// Jobs a machine may take in one batch
+    fn jobs_per_machine_cap(&self) -> usize {
+        let queueing = self.config.load_balance || self.config.target_utilization.is_some();
+        match self.config.max_jobs_per_machine {
+            Some(cap) => cap.max(1),
+            None if queueing => usize::MAX,
+            None => 1,
+        }
+    }
+
+    /// Whether the machine's next start falls past the planning horizon
+    fn beyond_horizon(&self, machine: &SchedulerMachine, batch: &BatchState) -> bool {
+        self.config
+            .planning_horizon_hours
+            .is_some_and(|horizon| Self::estimated_start(machine, batch) > horizon)
+    }
+
+    /// Whether the job has eligible machines, but all start past the horizon
+    fn only_beyond_horizon(
+        &self,
+        job: &SchedulerJob,
+        machines: &[SchedulerMachine],
+        batch: &BatchState,
+    ) -> bool {
+        let idle_reserved = self.idle_reserved(job, machines, batch);
+        machines.iter().any(|machine| {
+            self.beyond_horizon(machine, batch)
+                && self
+                    .evaluate_machine(job, machine, batch, idle_reserved)
+                    .0
+                    .is_some()
+        })
+    }
+
+    /// Why no machine took the job, or `None` when it only fits past the
+    /// planning horizon and should be deferred instead
+    fn unplaced_reason(
+        &self,
+        job: &SchedulerJob,
+        machines: &[SchedulerMachine],
+        batch: &BatchState,
+    ) -> Option<&'static str> {
+        if self.only_beyond_horizon(job, machines, batch) {
+            None
+        } else if self.capacity_exceeded(job, machines, batch) {
+            Some("Wafer capacity exceeded on a compatible machine")
+        } else {
+            Some("No eligible machine")
+        }
+    }
+
+    /// Whether some machine would take the job but lacks wafer capacity
+    fn capacity_exceeded(
+        &self,
+        job: &SchedulerJob,
+        machines: &[SchedulerMachine],
+        batch: &BatchState,
+    ) -> bool {
+        let idle_reserved = self.idle_reserved(job, machines, batch);
+        machines.iter().any(|machine| {
+            self.evaluate_machine(job, machine, batch, idle_reserved)
+                .1
+                .iter()
+                .any(|v| v.starts_with("Wafer capacity exceeded"))
+        })
+    }
+
+    /// Whether the job's recipe already used its per-batch assignment cap
+    fn recipe_budget_exhausted(&self, job: &SchedulerJob, batch: &BatchState) -> bool {
+        let recipe = job.recipe_type.to_lowercase();
+        self.config
+            .max_assignments_per_recipe
+            .iter()
+            .find(|(r, _)| r.to_lowercase() == recipe)
+            .is_some_and(|(_, &cap)| batch.recipe_count(&recipe) >= cap)
+    }
+
+    /// Index of the one available machine for each job's recipe, when every
+    /// recipe has exactly one (otherwise `None`)
+    ///
+    /// Every other machine is then excluded by a hard constraint, so scoring
+    /// just that machine gives the same plan as scoring the whole fleet.
+    fn dedicated_machines(
+        &self,
+        jobs: &[SchedulerJob],
+        machines: &[SchedulerMachine],
+    ) -> Option<HashMap<String, usize>> {
+        if !self.config.enforce_recipe_match {
+            return None;
+        }
+        let mut dedicated = HashMap::new();
+        for job in jobs {
+            if dedicated.contains_key(&job.recipe_type) {
+                continue;
+            }
+            let mut compatible = machines
+                .iter()
+                .enumerate()
+                .filter(|(_, m)| m.is_available() && self.is_compatible(job, m))
+                .map(|(index, _)| index);
+            match (compatible.next(), compatible.next()) {
+                (Some(index), None) => dedicated.insert(job.recipe_type.clone(), index),
+                _ => return None,
+            };
+        }
+        Some(dedicated)
+    }
+
+    /// Hot standby: normal jobs may not take the last idle compatible tools
+    fn idle_reserved(
+        &self,
+        job: &SchedulerJob,
+        machines: &[SchedulerMachine],
+        batch: &BatchState,
+    ) -> bool {
+        !job.is_hot_lot
+            && self.config.reserve_for_hot_lots > 0
+            && machines
+                .iter()
+                .filter(|m| {
+                    m.status == "IDLE"
+                        && !m.in_cooldown()
+                        && !batch.assigned.contains(&m.machine_id)
+                        && self.is_compatible(job, m)
+                })
+                .count()
+                <= self.config.reserve_for_hot_lots
+    }
+
+    /// Evaluate one machine for a job
+    ///
+    /// Returns `Some(score)` when the machine is eligible, `None` when a hard
+    /// constraint excludes it, along with every violation found.
+    fn evaluate_machine(
+        &self,
+        job: &SchedulerJob,
+        machine: &SchedulerMachine,
+        batch: &BatchState,
+        idle_reserved: bool,
+    ) -> (Option<f64>, Vec<String>) {
+        let mut violations = Vec::new();
+
+        // Skip unavailable machines
+        if let Some(violation) = Self::availability_violation(machine) {
+            violations.push(violation);
+            return (None, violations);
+        }
+
+        // Skip machines that already took their share of this batch
+        if batch.queue_additions(&machine.machine_id) as usize >= self.jobs_per_machine_cap() {
+            violations.push("Machine already assigned in this batch".to_string());
+            return (None, violations);
+        }
+
+        // Skip machines whose queue is physically full
+        if let Some(limit) = machine.max_queue_depth {
+            if machine.current_queue_depth + batch.queue_additions(&machine.machine_id) >= limit {
+                violations.push(format!("Queue at its limit of {} lots", limit));
+                return (None, violations);
+            }
+        }
+
+        // Skip idle machines held in reserve for hot lots
+        if idle_reserved && machine.status == "IDLE" {
+            violations.push("Idle machine reserved for hot lots".to_string());
+            return (None, violations);
+        }
+
+        // Check recipe compatibility
+        let type_matches = self.is_compatible(job, machine);
+
+        if !type_matches {
+            violations.push(format!(
+                "Recipe {} incompatible with {}",
+                job.recipe_type, machine.machine_type
+            ));
+            if self.config.enforce_recipe_match {
+                return (None, violations); // Hard constraint
+            }
+        }
+
+        // Skip machines without room for the job's wafers
+        if let Some(capacity) = machine.max_wafer_capacity {
+            let wafers = batch.wafers_added(&machine.machine_id) + job.wafer_count;
+            if wafers > capacity {
+                violations.push(format!(
+                    "Wafer capacity exceeded ({} of {} wafers)",
+                    wafers, capacity
+                ));
+                return (None, violations);
+            }
+        }
+
+        // Calculate score; NaN inputs (bad efficiency telemetry, weights)
+        // would otherwise lose every comparison and skew the ranking
+        let score = self.calculate_score(job, machine, batch);
+        if !score.is_finite() {
+            violations.push("Score is not finite (check efficiency and timing inputs)".to_string());
+            return (None, violations);
+        }
+
+        if let Some(late) = self.soft_deadline_lateness(job, machine, batch) {
+            violations.push(format!("Would miss soft deadline by {:.1}h", late));
+        }
+
+        // Check deadline constraint (start includes any changeover)
+        if let Some(deadline) = job.deadline_hours {
+            let estimated_start = self.job_start(job, machine, batch);
+            if estimated_start > deadline {
+                violations.push(format!(
+                    "Would miss deadline by {:.1}h",
+                    estimated_start - deadline
+                ));
+                if self.config.enforce_deadlines {
+                    return (None, violations); // Hard constraint
+                }
+            }
+        }
+
+        (Some(score), violations)
+    }
+
+    /// Why a machine can't take work right now, if it can't
+    fn availability_violation(machine: &SchedulerMachine) -> Option<String> {
+        if machine.is_available() {
+            None
+        } else if machine.in_cooldown() {
+            Some(format!(
+                "Machine in cooldown for {:.1}h",
+                machine.cooldown_until_hours.unwrap_or_default()
+            ))
+        } else {
+            Some(format!("Machine unavailable ({})", machine.status))
+        }
+    }
+
+    fn build_assignment(
+        &self,
+        job: &SchedulerJob,
+        machine: &SchedulerMachine,
+        score: f64,
+        violations: Vec<String>,
+        batch: &BatchState,
+    ) -> Assignment {
+        let estimated_start = self.job_start(job, machine, batch);
+
+        let reason = match self.config.reason_verbosity.as_str() {
+            "none" => String::new(),
+            "compact" => format!("Score: {:.2}", score),
+            _ => {
+                let mut reason_parts = vec![
+                    "Optimizer v1.0".to_string(),
+                    format!("Score: {:.2}", score),
+                    format!("Efficiency: {:.0}%", machine.efficiency_rating * 100.0),
+                ];
+                if job.is_hot_lot {
+                    reason_parts.insert(1, "HOT LOT".to_string());
+                }
+                reason_parts.join(" | ")
+            }
+        };
+
+        let mut reason_factors = vec![
+            ("optimizer_version".to_string(), "1.0".to_string()),
+            ("score".to_string(), format!("{:.2}", score)),
+            (
+                "efficiency".to_string(),
+                format!("{:.0}%", machine.efficiency_rating * 100.0),
+            ),
+        ];
+        if job.is_hot_lot {
+            reason_factors.insert(1, ("hot_lot".to_string(), "true".to_string()));
+        }
+
+        Assignment {
+            job_id: job.job_id.clone(),
+            job_name: job.job_name.clone(),
+            machine_id: machine.machine_id.clone(),
+            machine_name: machine.name.clone(),
+            score,
+            reason,
+            reason_factors,
+            estimated_start_hours: estimated_start,
+            estimated_finish_hours: estimated_start + self.processing_hours(job),
+            constraint_violations: violations,
+            score_breakdown: self.score_breakdown(job, machine, batch),
+            weighted_tardiness: self.weighted_tardiness(job, machine, batch),
+            wafer_count: job.wafer_count,
+        }
+    }
+
+    /// Hours a job occupies its machine
+    fn processing_hours(&self, job: &SchedulerJob) -> f64 {
+        job.processing_hours
+            .filter(|h| h.is_finite() && *h >= 0.0)
+            .unwrap_or(self.config.default_processing_hours.max(0.0))
+    }
+
+    /// When a machine could start its next job, given work added this batch
+    fn estimated_start(machine: &SchedulerMachine, batch: &BatchState) -> f64 {
+        machine.estimated_available_hours + batch.hours_added(&machine.machine_id)
+    }
+
+    /// Whether the job's recipe family differs from the machine's last one
+    fn needs_changeover(
+        job: &SchedulerJob,
+        machine: &SchedulerMachine,
+        batch: &BatchState,
+    ) -> bool {
+        batch
+            .last_recipe(&machine.machine_id)
+            .or(machine.last_recipe_type.as_ref())
+            .is_some_and(|last| {
+                get_compatible_machine_types(last) != get_compatible_machine_types(&job.recipe_type)
+            })
+    }
+
+    /// Setup hours before the job can start on the machine
+    fn changeover(
+        &self,
+        job: &SchedulerJob,
+        machine: &SchedulerMachine,
+        batch: &BatchState,
+    ) -> f64 {
+        if Self::needs_changeover(job, machine, batch) {
+            self.config.changeover_hours.max(0.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// When the job itself could start: the machine's next start plus any
+    /// changeover, but no earlier than its predecessors finish
+    fn job_start(&self, job: &SchedulerJob, machine: &SchedulerMachine, batch: &BatchState) -> f64 {
+        (Self::estimated_start(machine, batch) + self.changeover(job, machine, batch))
+            .max(batch.ready_at(job))
+    }
+
+    /// Hours past the job's soft deadline the machine would start it, if late
+    fn soft_deadline_lateness(
+        &self,
+        job: &SchedulerJob,
+        machine: &SchedulerMachine,
+        batch: &BatchState,
+    ) -> Option<f64> {
+        let soft = job.soft_deadline_hours?;
+        let late = self.job_start(job, machine, batch) - soft;
+        (late > 0.0).then_some(late)
+    }
+
+    /// Priority-scaled hours past the deadline the job would finish on the machine
+    fn weighted_tardiness(
+        &self,
+        job: &SchedulerJob,
+        machine: &SchedulerMachine,
+        batch: &BatchState,
+    ) -> f64 {
+        let Some(deadline) = job.deadline_hours else {
+            return 0.0;
+        };
+        let finish = self.job_start(job, machine, batch) + self.processing_hours(job);
+        (finish - deadline).max(0.0) * self.priority_factor(job)
+    }
+
+    /// Priority 1 (hot) = 1.0, Priority 5 = 0.2; waiting jobs age upward
+    fn priority_factor(&self, job: &SchedulerJob) -> f64 {
+        if job.is_hot_lot {
+            1.0
+        } else {
+            1.0 - ((self.effective_priority(job) - 1.0) * 0.2)
+        }
+    }
+
+    /// Whether a machine's type can run the job's recipe family
+    fn is_compatible(&self, job: &SchedulerJob, machine: &SchedulerMachine) -> bool {
+        if let Some(recipes) = self.qualifications.get(&machine.machine_id) {
+            return recipes
+                .iter()
+                .any(|recipe| recipe.eq_ignore_ascii_case(&job.recipe_type));
+        }
+        get_compatible_machine_types(&job.recipe_type)
+            .iter()
+            .any(|t| self.type_matches(&machine.machine_type, t))
+    }
+
+    /// Whether a machine type qualifies as `compatible` under `match_mode`
+    fn type_matches(&self, machine_type: &str, compatible: &str) -> bool {
+        let machine_type = machine_type.to_lowercase();
+        match self.config.match_mode.as_str() {
+            "exact" => machine_type == compatible,
+            _ => machine_type.contains(compatible),
+        }
+    }
+
+    fn calculate_score(
+        &self,
+        job: &SchedulerJob,
+        machine: &SchedulerMachine,
+        batch: &BatchState,
+    ) -> f64 {
+        self.score_terms(job, machine, batch)
+            .into_iter()
+            .map(|(_, term)| term)
+            .sum()
+    }
+
+    /// `calculate_score` per objective, for auditing the weights
+    fn score_breakdown(
+        &self,
+        job: &SchedulerJob,
+        machine: &SchedulerMachine,
+        batch: &BatchState,
+    ) -> HashMap<String, f64> {
+        let mut breakdown = HashMap::new();
+        for (objective, term) in self.score_terms(job, machine, batch) {
+            let always_listed = !matches!(
+                objective,
+                "utilization" | "changeover" | "tardiness" | "recipe_mismatch"
+            );
+            if always_listed || term != 0.0 {
+                *breakdown.entry(objective.to_string()).or_insert(0.0) += term;
+            }
+        }
+        breakdown
+    }
+
+    /// Weighted score terms, in the order they are summed
+    fn score_terms(
+        &self,
+        job: &SchedulerJob,
+        machine: &SchedulerMachine,
+        batch: &BatchState,
+    ) -> Vec<(&'static str, f64)> {
+        let mut terms = Vec::new();
+
+        // Priority score (higher priority = higher score)
+        let mut priority_score = self.priority_factor(job);
+
+        // Value-at-risk scales with lot size: blend a saturating wafer factor
+        // (0 for an empty lot, approaching 1 for very large lots)
+        let wafer_weight = self.config.wafer_weight.clamp(0.0, 1.0);
+        if wafer_weight > 0.0 {
+            let wafers = job.wafer_count.max(0) as f64;
+            let wafer_factor = wafers / (wafers + WAFER_REFERENCE_COUNT);
+            priority_score = priority_score * (1.0 - wafer_weight) + wafer_factor * wafer_weight;
+        }
+        terms.push(("priority", priority_score * self.config.priority_weight));
+
+        // Efficiency score (static rating blended with recent observations)
+        let efficiency = machine.blended_efficiency(
+            &machine.recent_efficiency,
+            self.config.observed_efficiency_weight,
+        );
+        terms.push(("efficiency", efficiency * self.config.efficiency_weight));
+
+        // Deadline urgency score
+        // More urgent = higher score
+        let urgency = job
+            .deadline_hours
+            .map_or(0.0, |deadline| (24.0 - deadline.min(24.0)) / 24.0);
+        terms.push(("deadline", urgency * self.config.deadline_weight));
+
+        // Soft deadline penalty, growing with lateness
+        if let Some(late) = self.soft_deadline_lateness(job, machine, batch) {
+            let lateness_factor = late / (late + SOFT_DEADLINE_REFERENCE_HOURS);
+            terms.push(("deadline", -lateness_factor * self.config.deadline_weight));
+        }
+
+        // Weighted tardiness penalty: lateness of the finish, by priority
+        if self.config.tardiness_weight > 0.0 {
+            let tardiness = self.weighted_tardiness(job, machine, batch);
+            terms.push(("tardiness", -tardiness * self.config.tardiness_weight));
+        }
+
+        // Queue depth score (lower queue = higher score)
+        let total_queue = machine.current_queue_depth + batch.queue_additions(&machine.machine_id);
+        let saturation = self.config.queue_saturation_factor.max(0.0);
+        let queue_score = 1.0 / (1.0 + total_queue as f64 * saturation);
+        terms.push(("queue_depth", queue_score * self.config.queue_depth_weight));
+
+        // Utilization penalty: booked hours including this job, past the target
+        if let Some(target) = self.config.target_utilization {
+            let window = self
+                .config
+                .planning_horizon_hours
+                .filter(|h| *h > 0.0)
+                .unwrap_or(UTILIZATION_WINDOW_HOURS);
+            let booked = self.job_start(job, machine, batch) + self.processing_hours(job);
+            let overshoot = (booked / window - target).max(0.0);
+            terms.push(("utilization", -overshoot * UTILIZATION_OVERSHOOT_PENALTY));
+        }
+
+        // Changeover penalty: prefer keeping a tool on one recipe family
+        if Self::needs_changeover(job, machine, batch) {
+            terms.push(("changeover", -self.config.changeover_weight));
+        }
+
+        // Soft recipe match: incompatible machines are a penalized fallback
+        if !self.config.enforce_recipe_match && !self.is_compatible(job, machine) {
+            terms.push((
+                "recipe_mismatch",
+                -self.config.recipe_mismatch_penalty.max(0.0),
+            ));
+        }
+
+        // Status bonus
+        let status_bonus = if machine.status == "IDLE" { 0.1 } else { 0.0 };
+        terms.push(("status_bonus", status_bonus));
+
+        terms
     }
 }
 
-/// Python module initialization
-#[pymodule]
-fn yieldops_scheduler(m: &Bound<'_, PyModule>) -> PyResult<()> {
-    m.add_class::<SchedulerJob>()?;
-    m.add_class::<SchedulerMachine>()?;
-    m.add_class::<Assignment>()?;
-    m.add_class::<OptimizationResult>()?;
-    m.add_class::<ConstraintConfig>()?;
-    m.add_class::<SchedulerOptimizer>()?;
-    Ok(())
+/// Parse a JSON array of jobs (as saved from `SchedulerJob` fields)
+///
+/// Optional fields may be `null` or left out.
+pub fn jobs_from_json(json: &str) -> Result<Vec<SchedulerJob>, String> {
+    serde_json::from_str(json).map_err(|e| format!("Invalid jobs JSON: {}", e))
+}
+
+/// Parse a JSON array of machines (as saved from `SchedulerMachine` fields)
+///
+/// Optional fields may be `null` or left out.
+pub fn machines_from_json(json: &str) -> Result<Vec<SchedulerMachine>, String> {
+    serde_json::from_str(json).map_err(|e| format!("Invalid machines JSON: {}", e))
 }
 
 #[cfg(test)]
@@ -484,68 +2371,1387 @@ mod tests {
                 25,
                 true,
                 "lithography".into(),
-                Some(4.0),
-            ),
-            SchedulerJob::new(
-                "j2".into(),
-                "WAFER-103".into(),
-                2,
-                50,
-                false,
-                "etching".into(),
+                Some(4.0),
+            ),
+            SchedulerJob::new(
+                "j2".into(),
+                "WAFER-103".into(),
+                2,
+                50,
+                false,
+                "etching".into(),
+                None,
+            ),
+            SchedulerJob::new(
+                "j3".into(),
+                "WAFER-104".into(),
+                3,
+                30,
+                false,
+                "deposition".into(),
+                Some(12.0),
+            ),
+        ]
+    }
+
+    fn sample_machines() -> Vec<SchedulerMachine> {
+        vec![
+            SchedulerMachine::new(
+                "m1".into(),
+                "LITHO-01".into(),
+                "lithography".into(),
+                "IDLE".into(),
+                0.95,
+                0,
+                0.0,
+            ),
+            SchedulerMachine::new(
+                "m2".into(),
+                "ETCH-01".into(),
+                "etching".into(),
+                "RUNNING".into(),
+                0.88,
+                1,
+                2.0,
+            ),
+            SchedulerMachine::new(
+                "m3".into(),
+                "DEP-01".into(),
+                "deposition".into(),
+                "IDLE".into(),
+                0.92,
+                0,
+                0.0,
+            ),
+            SchedulerMachine::new(
+                "m4".into(),
+                "LITHO-02".into(),
+                "lithography".into(),
+                "DOWN".into(),
+                0.90,
+                0,
+                0.0,
+            ),
+        ]
+    }
+
+    /// An idle lithography tool rated 0.9, named after its id
+    fn litho_machine(id: &str) -> SchedulerMachine {
+        SchedulerMachine::new(
+            id.into(),
+            id.into(),
+            "lithography".into(),
+            "IDLE".into(),
+            0.9,
+            0,
+            0.0,
+        )
+    }
+
+    /// Chaos hook: take a machine out of service mid-schedule
+    fn inject_machine_down(machines: &mut [SchedulerMachine], machine_id: &str) {
+        for machine in machines.iter_mut().filter(|m| m.machine_id == machine_id) {
+            machine.status = "DOWN".to_string();
+        }
+    }
+
+    /// Run a schedule, fail one machine, and reoptimize the same jobs
+    fn run_failure_scenario(
+        optimizer: &SchedulerOptimizer,
+        jobs: Vec<SchedulerJob>,
+        mut machines: Vec<SchedulerMachine>,
+        failed_machine: &str,
+    ) -> (HashMap<String, String>, HashMap<String, String>) {
+        let placements = |result: OptimizationResult| -> HashMap<String, String> {
+            result
+                .assignments
+                .into_iter()
+                .map(|a| (a.job_id, a.machine_id))
+                .collect()
+        };
+
+        let before = placements(
+            optimizer
+                .optimize(jobs.clone(), machines.clone(), 10)
+                .unwrap(),
+        );
+        inject_machine_down(&mut machines, failed_machine);
+        let after = placements(optimizer.optimize(jobs, machines, 10).unwrap());
+        (before, after)
+    }
+
+    #[test]
+    fn test_injected_failure_moves_only_affected_jobs() {
+        let optimizer = SchedulerOptimizer::new(None);
+        let mut machines = sample_machines();
+        machines[3].status = "IDLE".into(); // LITHO-02 back in service as the spare
+
+        let (before, after) = run_failure_scenario(&optimizer, sample_jobs(), machines, "m1");
+
+        assert_eq!(before["j1"], "m1");
+        // The hot lot fails over to the spare litho tool
+        assert_eq!(after["j1"], "m4");
+        // Jobs on healthy machines stay put
+        assert_eq!(after["j2"], before["j2"]);
+        assert_eq!(after["j3"], before["j3"]);
+    }
+
+    #[test]
+    fn test_load_balance_splits_identical_machines() {
+        let etcher = |id: &str| {
+            SchedulerMachine::new(
+                id.into(),
+                id.into(),
+                "etching".into(),
+                "IDLE".into(),
+                0.9,
+                0,
+                0.0,
+            )
+        };
+        let machines = vec![etcher("ETCH-01"), etcher("ETCH-02")];
+        let jobs: Vec<SchedulerJob> = (0..4)
+            .map(|i| {
+                SchedulerJob::new(
+                    format!("j{}", i),
+                    format!("E-{}", i),
+                    2,
+                    25,
+                    false,
+                    "etching".into(),
+                    None,
+                )
+            })
+            .collect();
+
+        // Without a queue bonus nothing separates the etchers but the tie-break
+        let config = ConstraintConfig {
+            queue_depth_weight: 0.0,
+            load_balance: true,
+            ..Default::default()
+        };
+        let result = SchedulerOptimizer::new(Some(config))
+            .optimize(jobs.clone(), machines.clone(), 10)
+            .unwrap();
+
+        assert_eq!(result.assignments.len(), 4);
+        let on_first = result
+            .assignments
+            .iter()
+            .filter(|a| a.machine_id == "ETCH-01")
+            .count();
+        assert_eq!(on_first, 2, "Expected a 2/2 split");
+
+        // Default batches give each machine at most one job
+        let result = SchedulerOptimizer::new(None)
+            .optimize(jobs.clone(), machines.clone(), 10)
+            .unwrap();
+        assert_eq!(result.assignments.len(), 2);
+        assert_eq!(result.unassigned_jobs.len(), 2);
+
+        // Load balancing never lifts an explicit cap, even a cap of one
+        let config = ConstraintConfig {
+            load_balance: true,
+            max_jobs_per_machine: Some(1),
+            ..Default::default()
+        };
+        let result = SchedulerOptimizer::new(Some(config))
+            .optimize(jobs, machines, 10)
+            .unwrap();
+        assert_eq!(result.assignments.len(), 2);
+        assert_eq!(result.unassigned_jobs.len(), 2);
+    }
+
+    #[test]
+    fn test_target_utilization_spreads_jobs() {
+        let litho = |id: &str, efficiency: f64| {
+            SchedulerMachine::new(
+                id.into(),
+                id.into(),
+                "lithography".into(),
+                "IDLE".into(),
+                efficiency,
+                0,
+                0.0,
+            )
+        };
+        let machines = vec![litho("LITHO-01", 0.95), litho("LITHO-02", 0.85)];
+        let jobs: Vec<SchedulerJob> = (0..6)
+            .map(|i| {
+                let mut job = SchedulerJob::new(
+                    format!("j{}", i),
+                    format!("L-{}", i),
+                    2,
+                    25,
+                    false,
+                    "lithography".into(),
+                    None,
+                );
+                job.processing_hours = Some(4.0);
+                job
+            })
+            .collect();
+        let on_best = |result: &OptimizationResult| {
+            result
+                .assignments
+                .iter()
+                .filter(|a| a.machine_id == "LITHO-01")
+                .count()
+        };
+
+        // Without a queue bonus the most efficient tool takes everything
+        let config = ConstraintConfig {
+            queue_depth_weight: 0.0,
+            ..Default::default()
+        };
+        let result = SchedulerOptimizer::new(Some(ConstraintConfig {
+            max_jobs_per_machine: Some(6),
+            ..config.clone()
+        }))
+        .optimize(jobs.clone(), machines.clone(), 10)
+        .unwrap();
+        assert_eq!(on_best(&result), 6);
+
+        // 50% of 24h: LITHO-01 fills to 12h, then work moves to LITHO-02;
+        // the target alone lets machines queue, without load balancing
+        let config = ConstraintConfig {
+            target_utilization: Some(0.5),
+            ..config
+        };
+        let result = SchedulerOptimizer::new(Some(config))
+            .optimize(jobs, machines, 10)
+            .unwrap();
+        assert_eq!(result.assignments.len(), 6);
+        assert_eq!(on_best(&result), 3, "Expected a 3/3 split at the target");
+    }
+
+    #[test]
+    fn test_unassigned_remedy_names_down_etchers() {
+        let mut machines = sample_machines();
+        machines.push(SchedulerMachine::new(
+            "m9".into(),
+            "ETCH-02".into(),
+            "etching".into(),
+            "IDLE".into(),
+            0.85,
+            0,
+            0.0,
+        ));
+        for machine in machines.iter_mut() {
+            if machine.machine_type == "etching" {
+                machine.status = "DOWN".to_string();
+            }
+        }
+        let mut down_etchers: Vec<String> = machines
+            .iter()
+            .filter(|m| m.machine_type == "etching")
+            .map(|m| m.machine_id.clone())
+            .collect();
+        down_etchers.sort();
+        let etch_job = |i: usize| {
+            SchedulerJob::new(
+                format!("e{}", i),
+                format!("E-{}", i),
+                2,
+                25,
+                false,
+                "etching".into(),
+                None,
+            )
+        };
+        let jobs: Vec<SchedulerJob> = (0..down_etchers.len()).map(etch_job).collect();
+
+        let optimizer = SchedulerOptimizer::new(None);
+        let result = optimizer
+            .optimize(jobs.clone(), machines.clone(), 10)
+            .unwrap();
+        let remedies = optimizer.unassigned_remedies(result, jobs, machines);
+
+        assert_eq!(remedies.len(), 1);
+        let remedy = &remedies[0];
+        assert_eq!(remedy.cause, "machines_unavailable");
+        assert_eq!(remedy.job_ids.len(), down_etchers.len());
+        let mut named = remedy.machines.clone();
+        named.sort();
+        assert_eq!(named, down_etchers);
+        for machine_id in &down_etchers {
+            assert!(remedy.suggestion.contains(machine_id.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_unassigned_remedy_covers_split_sub_lots() {
+        let mut machines = sample_machines();
+        machines[1].status = "DOWN".to_string();
+        let jobs = vec![sample_jobs()[1].clone()];
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            max_wafers_per_assignment: Some(25),
+            ..Default::default()
+        }));
+
+        let result = optimizer
+            .optimize(jobs.clone(), machines.clone(), 10)
+            .unwrap();
+        assert_eq!(result.unassigned_jobs, vec!["j2#1", "j2#2"]);
+        let remedies = optimizer.unassigned_remedies(result, jobs, machines);
+
+        assert_eq!(remedies.len(), 1);
+        assert_eq!(remedies[0].cause, "machines_unavailable");
+        assert_eq!(remedies[0].job_ids, vec!["j2#1", "j2#2"]);
+        assert_eq!(remedies[0].machines, vec!["m2"]);
+    }
+
+    #[test]
+    fn test_max_jobs_per_machine_queues_several_jobs() {
+        let machines = vec![litho_machine("LITHO-01"), litho_machine("LITHO-02")];
+        let jobs: Vec<SchedulerJob> = (0..6)
+            .map(|i| {
+                SchedulerJob::new(
+                    format!("j{}", i),
+                    format!("L-{}", i),
+                    2,
+                    25,
+                    false,
+                    "lithography".into(),
+                    None,
+                )
+            })
+            .collect();
+
+        // Default: one job per machine
+        let result = SchedulerOptimizer::new(None)
+            .optimize(jobs.clone(), machines.clone(), 10)
+            .unwrap();
+        assert_eq!(result.assignments.len(), 2);
+
+        let config = ConstraintConfig {
+            max_jobs_per_machine: Some(3),
+            ..Default::default()
+        };
+        let result = SchedulerOptimizer::new(Some(config))
+            .optimize(jobs, machines, 10)
+            .unwrap();
+        assert!(result.unassigned_jobs.is_empty());
+        assert_eq!(result.assignments.len(), 6);
+        for machine_id in ["LITHO-01", "LITHO-02"] {
+            let mut starts: Vec<f64> = result
+                .assignments
+                .iter()
+                .filter(|a| a.machine_id == machine_id)
+                .map(|a| a.estimated_start_hours)
+                .collect();
+            assert_eq!(starts.len(), 3);
+            // Each queued job starts after the one before it
+            starts.sort_by(f64::total_cmp);
+            assert_eq!(starts, vec![0.0, 2.0, 4.0]);
+        }
+    }
+
+    #[test]
+    fn test_timeline_bars_are_contiguous_per_machine() {
+        let machines = vec![litho_machine("LITHO-01"), litho_machine("LITHO-02")];
+        let jobs: Vec<SchedulerJob> = (0..5)
+            .map(|i| {
+                let mut job = SchedulerJob::new(
+                    format!("j{}", i),
+                    format!("L-{}", i),
+                    2,
+                    25,
+                    false,
+                    "lithography".into(),
+                    None,
+                );
+                job.processing_hours = Some(1.0 + i as f64 * 0.5);
+                job
+            })
+            .collect();
+        let config = ConstraintConfig {
+            max_jobs_per_machine: Some(3),
+            ..Default::default()
+        };
+        let result = SchedulerOptimizer::new(Some(config))
+            .optimize(jobs, machines, 10)
+            .unwrap();
+
+        let timeline = result.timeline();
+        assert_eq!(timeline.len(), 5);
+        assert!(timeline.windows(2).all(|w| w[0].0 <= w[1].0));
+        for pair in timeline.windows(2).filter(|w| w[0].0 == w[1].0) {
+            let (_, _, start, end) = &pair[0];
+            let (_, _, next_start, _) = &pair[1];
+            assert!(start < end);
+            // Next bar starts exactly where the previous one ends
+            assert!((next_start - end).abs() < 1e-9);
+        }
+        for (_, job_id, start, end) in &timeline {
+            let assignment = result
+                .assignments
+                .iter()
+                .find(|a| &a.job_id == job_id)
+                .unwrap();
+            assert_eq!(*start, assignment.estimated_start_hours);
+            assert_eq!(*end, assignment.estimated_finish_hours);
+        }
+    }
+
+    #[test]
+    fn test_reason_factors_flag_only_hot_lots() {
+        let litho = |id: &str| {
+            SchedulerMachine::new(
+                id.into(),
+                id.into(),
+                "lithography".into(),
+                "IDLE".into(),
+                0.95,
+                0,
+                0.0,
+            )
+        };
+        let machines = vec![litho("LITHO-01"), litho("LITHO-02")];
+        let jobs = vec![
+            SchedulerJob::new(
+                "hot".into(),
+                "HOT-1".into(),
+                1,
+                25,
+                true,
+                "lithography".into(),
                 None,
             ),
             SchedulerJob::new(
-                "j3".into(),
-                "WAFER-104".into(),
+                "std".into(),
+                "STD-1".into(),
                 3,
-                30,
+                25,
                 false,
-                "deposition".into(),
-                Some(12.0),
+                "lithography".into(),
+                None,
             ),
-        ]
+        ];
+        let result = SchedulerOptimizer::new(None)
+            .optimize(jobs, machines, 10)
+            .unwrap();
+        assert_eq!(result.assignments.len(), 2);
+
+        let factors = |job_id: &str| {
+            result
+                .assignments
+                .iter()
+                .find(|a| a.job_id == job_id)
+                .unwrap()
+                .reason_factors
+                .clone()
+        };
+        let hot_lot = ("hot_lot".to_string(), "true".to_string());
+        assert!(factors("hot").contains(&hot_lot));
+        assert!(factors("std").iter().all(|(label, _)| label != "hot_lot"));
+        assert!(factors("std").contains(&("efficiency".to_string(), "95%".to_string())));
+        assert_eq!(
+            factors("std")[0],
+            ("optimizer_version".to_string(), "1.0".to_string())
+        );
     }
 
-    fn sample_machines() -> Vec<SchedulerMachine> {
-        vec![
+    #[test]
+    fn test_tardiness_weight_prefers_machine_finishing_on_time() {
+        // Better tool is busy for 6h; the weaker one is free now
+        let machines = vec![
             SchedulerMachine::new(
-                "m1".into(),
+                "LITHO-01".into(),
                 "LITHO-01".into(),
                 "lithography".into(),
-                "IDLE".into(),
-                0.95,
+                "RUNNING".into(),
+                0.98,
                 0,
-                0.0,
+                6.0,
             ),
             SchedulerMachine::new(
-                "m2".into(),
-                "ETCH-01".into(),
-                "etching".into(),
+                "LITHO-02".into(),
+                "LITHO-02".into(),
+                "lithography".into(),
                 "RUNNING".into(),
-                0.88,
-                1,
-                2.0,
+                0.7,
+                0,
+                0.0,
             ),
+        ];
+        let mut job = SchedulerJob::new(
+            "j1".into(),
+            "L-1".into(),
+            2,
+            25,
+            false,
+            "lithography".into(),
+            Some(4.0),
+        );
+        job.processing_hours = Some(2.0);
+
+        let result = SchedulerOptimizer::new(None)
+            .optimize(vec![job.clone()], machines.clone(), 10)
+            .unwrap();
+        assert_eq!(result.assignments[0].machine_id, "LITHO-01");
+        // Finishes at 8h against a 4h deadline, at priority 2's 0.8
+        assert!((result.total_weighted_tardiness - 3.2).abs() < 1e-9);
+        assert!(!result.assignments[0]
+            .score_breakdown
+            .contains_key("tardiness"));
+
+        let config = ConstraintConfig {
+            tardiness_weight: 0.1,
+            ..Default::default()
+        };
+        let result = SchedulerOptimizer::new(Some(config))
+            .optimize(vec![job], machines, 10)
+            .unwrap();
+        assert_eq!(result.assignments[0].machine_id, "LITHO-02");
+        assert_eq!(result.total_weighted_tardiness, 0.0);
+    }
+
+    #[test]
+    fn test_json_round_trip_replays_run() {
+        let mut jobs = sample_jobs();
+        jobs[0].deadline_hours = Some(6.0);
+        jobs[1].deadline_hours = None;
+        let machines = sample_machines();
+
+        let jobs_json = serde_json::to_string(&jobs).unwrap();
+        let machines_json = serde_json::to_string(&machines).unwrap();
+        let replayed_jobs = jobs_from_json(&jobs_json).unwrap();
+        assert_eq!(replayed_jobs[0].deadline_hours, Some(6.0));
+        assert_eq!(replayed_jobs[1].deadline_hours, None);
+
+        let optimizer = SchedulerOptimizer::new(None);
+        let result = optimizer.optimize(jobs, machines, 10).unwrap();
+        let replayed = optimizer
+            .optimize(
+                replayed_jobs,
+                machines_from_json(&machines_json).unwrap(),
+                10,
+            )
+            .unwrap();
+        let plan = |r: &OptimizationResult| r.timeline();
+        assert_eq!(plan(&replayed), plan(&result));
+
+        // Full result survives a save/load cycle
+        let json = result.to_json();
+        let loaded: OptimizationResult = serde_json::from_str(&json).unwrap();
+        let value = |json: &str| serde_json::from_str::<serde_json::Value>(json).unwrap();
+        assert_eq!(value(&loaded.to_json()), value(&json));
+        assert_eq!(loaded.assignments.len(), result.assignments.len());
+
+        // Optional fields may be null or left out; required ones may not
+        let minimal = r#"[{"job_id": "j9", "job_name": "L-9", "priority_level": 3,
+            "wafer_count": 25, "is_hot_lot": false, "recipe_type": "etching",
+            "deadline_hours": null}]"#;
+        let job = &jobs_from_json(minimal).unwrap()[0];
+        assert_eq!(job.deadline_hours, None);
+        assert_eq!(job.processing_hours, None);
+        assert!(job.depends_on.is_empty());
+        assert!(jobs_from_json(r#"[{"job_id": "j9"}]"#).is_err());
+
+        // Configs load from partial JSON, the rest defaulting
+        let config: ConstraintConfig =
+            serde_json::from_str(r#"{"tardiness_weight": 0.1}"#).unwrap();
+        assert_eq!(config.tardiness_weight, 0.1);
+        assert_eq!(
+            config.priority_weight,
+            ConstraintConfig::default().priority_weight
+        );
+    }
+
+    #[test]
+    fn test_annealing_never_scores_below_greedy() {
+        // Greedy gives the first pick of tools to the top-priority lot, which
+        // pushes the lot with a target start onto the busy tool
+        let litho = |id: &str, efficiency: f64, available: f64| {
             SchedulerMachine::new(
-                "m3".into(),
-                "DEP-01".into(),
-                "deposition".into(),
-                "IDLE".into(),
-                0.92,
+                id.into(),
+                id.into(),
+                "lithography".into(),
+                "RUNNING".into(),
+                efficiency,
+                0,
+                available,
+            )
+        };
+        let machines = vec![litho("LITHO-01", 0.9, 0.0), litho("LITHO-02", 0.85, 5.0)];
+        let first = SchedulerJob::new(
+            "first".into(),
+            "L-1".into(),
+            1,
+            25,
+            false,
+            "lithography".into(),
+            None,
+        );
+        let mut targeted = SchedulerJob::new(
+            "targeted".into(),
+            "L-2".into(),
+            2,
+            25,
+            false,
+            "lithography".into(),
+            None,
+        );
+        targeted.soft_deadline_hours = Some(0.0);
+        let jobs = vec![first, targeted];
+
+        let optimizer = SchedulerOptimizer::new(None);
+        let greedy = optimizer.optimize_native(&jobs, &machines, 10);
+        let annealed = optimizer.optimize_annealed_native(&jobs, &machines, 10, 200, 7);
+        assert!(annealed.total_score >= greedy.total_score);
+        assert!(annealed.total_score > greedy.total_score + 0.05);
+        let machine_of = |job_id: &str| {
+            annealed
+                .assignments
+                .iter()
+                .find(|a| a.job_id == job_id)
+                .map(|a| a.machine_id.clone())
+        };
+        assert_eq!(machine_of("targeted").as_deref(), Some("LITHO-01"));
+        assert_eq!(machine_of("first").as_deref(), Some("LITHO-02"));
+
+        // Same seed, same plan; hard constraints hold on a larger batch
+        let (jobs, fleet) = workload::generate(40, 10, 3);
+        let runs: Vec<OptimizationResult> = (0..2)
+            .map(|_| optimizer.optimize_annealed_native(&jobs, &fleet, 10, 500, 11))
+            .collect();
+        assert_eq!(runs[0].timeline(), runs[1].timeline());
+        let greedy = optimizer.optimize_native(&jobs, &fleet, 10);
+        assert!(runs[0].total_score >= greedy.total_score);
+        for assignment in &runs[0].assignments {
+            let job = jobs.iter().find(|j| j.job_id == assignment.job_id).unwrap();
+            let machine = fleet
+                .iter()
+                .find(|m| m.machine_id == assignment.machine_id)
+                .unwrap();
+            assert!(machine.is_available() && optimizer.is_compatible(job, machine));
+        }
+    }
+
+    #[test]
+    fn test_single_machine_per_recipe_fast_path_matches_general() {
+        let machine = |id: &str, machine_type: &str, status: &str| {
+            SchedulerMachine::new(
+                id.into(),
+                id.to_uppercase(),
+                machine_type.into(),
+                status.into(),
+                0.9,
                 0,
                 0.0,
+            )
+        };
+        let mut machines = vec![
+            machine("litho-01", "lithography", "IDLE"),
+            machine("etch-01", "etching", "RUNNING"),
+            machine("dep-01", "deposition", "IDLE"),
+            // Down, so the recipe still has one available machine
+            machine("litho-02", "lithography", "DOWN"),
+        ];
+        let jobs: Vec<SchedulerJob> = (0..9)
+            .map(|i| {
+                let recipe = ["lithography", "etching", "deposition"][i % 3];
+                let mut job = SchedulerJob::new(
+                    format!("j{}", i),
+                    format!("L-{}", i),
+                    1 + (i % 5) as i32,
+                    25,
+                    i == 4,
+                    recipe.into(),
+                    Some(2.0 + i as f64),
+                );
+                job.processing_hours = Some(1.5);
+                job
+            })
+            .collect();
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            load_balance: true,
+            max_jobs_per_machine: Some(2),
+            ..Default::default()
+        }));
+        assert!(optimizer.dedicated_machines(&jobs, &machines).is_some());
+        let fast = optimizer.optimize_native(&jobs, &machines, 10);
+
+        // A twin whose queue is already full can never take a job, but it
+        // makes etching ambiguous, so the general path runs
+        let mut twin = machine("etch-02", "etching", "RUNNING");
+        twin.max_queue_depth = Some(0);
+        machines.push(twin);
+        assert!(optimizer.dedicated_machines(&jobs, &machines).is_none());
+        let general = optimizer.optimize_native(&jobs, &machines, 10);
+
+        let plan = |result: &OptimizationResult| {
+            serde_json::to_value((&result.assignments, &result.unassigned_jobs)).unwrap()
+        };
+        assert_eq!(fast.assignments.len(), 6);
+        assert_eq!(plan(&fast), plan(&general));
+    }
+
+    #[test]
+    fn test_hot_lot_preempts_low_priority_lot() {
+        let machines = vec![SchedulerMachine::new(
+            "m1".into(),
+            "LITHO-01".into(),
+            "lithography".into(),
+            "RUNNING".into(),
+            0.9,
+            0,
+            0.0,
+        )];
+        let litho = |id: &str, priority: i32, hot: bool| {
+            SchedulerJob::new(
+                id.into(),
+                id.to_uppercase(),
+                priority,
+                25,
+                hot,
+                "lithography".into(),
+                None,
+            )
+        };
+        let low = litho("low", 5, false);
+        let hot = litho("hot", 1, true);
+        let urgent = litho("urgent", 1, false);
+
+        let preempting = SchedulerOptimizer::new(Some(ConstraintConfig {
+            allow_hot_lot_preemption: true,
+            ..Default::default()
+        }));
+        // The low-priority lot already holds the only litho tool
+        let locked = preempting
+            .optimize(vec![low.clone()], machines.clone(), 10)
+            .unwrap()
+            .assignments;
+        assert_eq!(locked[0].machine_id, "m1");
+
+        let jobs = vec![low.clone(), hot.clone()];
+        let result = SchedulerOptimizer::new(None)
+            .optimize_incremental(jobs.clone(), machines.clone(), locked.clone(), 10)
+            .unwrap();
+        assert!(result.preempted_jobs.is_empty());
+        assert!(result.unassigned_jobs.contains(&"hot".to_string()));
+
+        let result = preempting
+            .optimize_incremental(jobs, machines.clone(), locked.clone(), 10)
+            .unwrap();
+        assert_eq!(result.preempted_jobs, vec!["low".to_string()]);
+        assert_eq!(result.assignments.len(), 1);
+        let assignment = &result.assignments[0];
+        assert_eq!(
+            (assignment.job_id.as_str(), assignment.machine_id.as_str()),
+            ("hot", "m1")
+        );
+        assert!(assignment
+            .reason_factors
+            .contains(&("preempted".to_string(), "low".to_string())));
+
+        // Only hot lots preempt, however high the priority
+        let result = preempting
+            .optimize_incremental(vec![low, urgent], machines, locked, 10)
+            .unwrap();
+        assert!(result.preempted_jobs.is_empty());
+        assert!(result.unassigned_jobs.contains(&"urgent".to_string()));
+    }
+
+    #[test]
+    fn test_no_split_hot_lots_keeps_hot_lot_whole() {
+        let machines: Vec<SchedulerMachine> = (1..=3)
+            .map(|i| litho_machine(&format!("LITHO-0{}", i)))
+            .collect();
+        let lot = |id: &str, hot: bool| {
+            SchedulerJob::new(
+                id.into(),
+                id.into(),
+                if hot { 1 } else { 2 },
+                50,
+                hot,
+                "lithography".into(),
+                None,
+            )
+        };
+        let jobs = vec![lot("HOT", true), lot("NORMAL", false)];
+        let config = ConstraintConfig {
+            max_wafers_per_assignment: Some(25),
+            no_split_hot_lots: true,
+            ..Default::default()
+        };
+
+        let result = SchedulerOptimizer::new(Some(config))
+            .optimize(jobs, machines, 10)
+            .unwrap();
+
+        let hot: Vec<&Assignment> = result
+            .assignments
+            .iter()
+            .filter(|a| a.job_id == "HOT")
+            .collect();
+        assert_eq!(hot.len(), 1, "hot lot should stay whole");
+
+        let mut normal: Vec<&Assignment> = result
+            .assignments
+            .iter()
+            .filter(|a| a.job_id.starts_with("NORMAL#"))
+            .collect();
+        normal.sort_by(|a, b| a.job_id.cmp(&b.job_id));
+        assert_eq!(normal.len(), 2, "normal lot of equal size should split");
+        assert_eq!(normal[0].job_id, "NORMAL#1");
+        assert_ne!(normal[0].machine_id, normal[1].machine_id);
+        assert!(normal.iter().all(|a| a.machine_id != hot[0].machine_id));
+    }
+
+    #[test]
+    fn test_wafer_totals_cover_every_input_wafer() {
+        let machines: Vec<SchedulerMachine> = (1..=4)
+            .map(|i| litho_machine(&format!("LITHO-0{}", i)))
+            .collect();
+        let lot = |id: &str, wafers: i32, recipe: &str| {
+            SchedulerJob::new(id.into(), id.into(), 2, wafers, false, recipe.into(), None)
+        };
+        // LOT-A splits 25/25/10; LOT-C has no etch machine; the batch fills
+        // before every sub-lot is placed
+        let jobs = vec![
+            lot("LOT-A", 60, "lithography"),
+            lot("LOT-B", 30, "lithography"),
+            lot("LOT-C", 25, "etching"),
+        ];
+        let config = ConstraintConfig {
+            max_wafers_per_assignment: Some(25),
+            ..Default::default()
+        };
+
+        let result = SchedulerOptimizer::new(Some(config))
+            .optimize(jobs, machines, 3)
+            .unwrap();
+
+        let on_plan: i64 = result
+            .assignments
+            .iter()
+            .map(|a| i64::from(a.wafer_count))
+            .sum();
+        assert_eq!(result.assignments.len(), 3);
+        assert!(result.assignments.iter().all(|a| a.wafer_count <= 25));
+        assert_eq!(result.total_wafers_assigned, on_plan);
+        assert!(result.total_wafers_unassigned >= 25);
+        assert_eq!(
+            result.total_wafers_assigned + result.total_wafers_unassigned,
+            115
+        );
+    }
+
+    #[test]
+    fn test_candidates_lead_with_optimizer_choice() {
+        let optimizer = SchedulerOptimizer::new(None);
+        let mut machines = sample_machines();
+        machines.push(SchedulerMachine::new(
+            "m9".into(),
+            "LITHO-02".into(),
+            "lithography".into(),
+            "RUNNING".into(),
+            0.80,
+            3,
+            30.0,
+        ));
+        let job = sample_jobs()[0].clone();
+
+        let candidates = optimizer.candidates(job.clone(), machines.clone(), 5);
+        let chosen = optimizer
+            .optimize(vec![job], machines, 10)
+            .unwrap()
+            .assignments
+            .remove(0);
+
+        assert!(candidates.len() >= 2);
+        assert_eq!(candidates[0].machine_id, chosen.machine_id);
+        assert_eq!(candidates[0].score, chosen.score);
+        assert!(candidates.windows(2).all(|w| w[0].score >= w[1].score));
+
+        // The runner-up only broke the soft deadline check
+        let runner_up = candidates.iter().find(|c| c.machine_id == "m9").unwrap();
+        assert!(runner_up
+            .constraint_violations
+            .iter()
+            .any(|v| v.contains("deadline")));
+    }
+
+    #[test]
+    fn test_recipe_mismatch_penalized_fallback() {
+        let machines: Vec<SchedulerMachine> = sample_machines()
+            .into_iter()
+            .filter(|m| m.machine_type != "etching")
+            .collect();
+        let etch_job = sample_jobs()[1].clone();
+        assert_eq!(etch_job.recipe_type, "etching");
+
+        // Hard mode: nothing can run it
+        let result = SchedulerOptimizer::new(None)
+            .optimize(vec![etch_job.clone()], machines.clone(), 10)
+            .unwrap();
+        assert!(result.assignments.is_empty());
+
+        let soft = |penalty: f64| ConstraintConfig {
+            enforce_recipe_match: false,
+            recipe_mismatch_penalty: penalty,
+            ..Default::default()
+        };
+        let result = SchedulerOptimizer::new(Some(soft(0.5)))
+            .optimize(vec![etch_job.clone()], machines.clone(), 10)
+            .unwrap();
+        assert_eq!(result.assignments.len(), 1);
+        let fallback = &result.assignments[0];
+        assert!(fallback
+            .constraint_violations
+            .iter()
+            .any(|v| v.contains("incompatible")));
+
+        let unpenalized = SchedulerOptimizer::new(Some(soft(0.0)))
+            .optimize(vec![etch_job], machines, 10)
+            .unwrap();
+        assert!((unpenalized.assignments[0].score - fallback.score - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_exact_match_mode_excludes_combined_type() {
+        let machines = vec![SchedulerMachine::new(
+            "EC-01".into(),
+            "EC-01".into(),
+            "etching_and_cleaning".into(),
+            "IDLE".into(),
+            0.9,
+            0,
+            0.0,
+        )];
+        let etch_job = sample_jobs()[1].clone();
+        assert_eq!(etch_job.recipe_type, "etching");
+
+        let result = SchedulerOptimizer::new(None)
+            .optimize(vec![etch_job.clone()], machines.clone(), 10)
+            .unwrap();
+        assert_eq!(result.assignments.len(), 1);
+        assert_eq!(result.assignments[0].machine_id, "EC-01");
+
+        let exact = ConstraintConfig {
+            match_mode: "exact".to_string(),
+            ..Default::default()
+        };
+        let result = SchedulerOptimizer::new(Some(exact))
+            .optimize(vec![etch_job.clone()], machines.clone(), 10)
+            .unwrap();
+        assert!(result.assignments.is_empty());
+        assert_eq!(result.unassigned_jobs, vec![etch_job.job_id.clone()]);
+
+        let unknown = ConstraintConfig {
+            match_mode: "prefix".to_string(),
+            ..Default::default()
+        };
+        assert!(SchedulerOptimizer::new(Some(unknown))
+            .optimize(vec![etch_job], machines, 10)
+            .is_err());
+    }
+
+    #[test]
+    fn test_changeover_costs_time_and_score() {
+        // A cluster tool that can run both lithography and etching
+        let cluster = SchedulerMachine::new(
+            "c1".into(),
+            "CLUSTER-01".into(),
+            "lithography_etching".into(),
+            "IDLE".into(),
+            0.9,
+            0,
+            0.0,
+        );
+        let job = |id: &str, priority: i32, recipe: &str| {
+            SchedulerJob::new(
+                id.into(),
+                id.into(),
+                priority,
+                25,
+                false,
+                recipe.into(),
+                None,
+            )
+        };
+        let config = ConstraintConfig {
+            max_jobs_per_machine: Some(2),
+            changeover_hours: 1.5,
+            changeover_weight: 0.1,
+            ..Default::default()
+        };
+        let optimizer = SchedulerOptimizer::new(Some(config));
+        let run = |second: SchedulerJob| {
+            let result = optimizer
+                .optimize(
+                    vec![job("L1", 1, "lithography"), second],
+                    vec![cluster.clone()],
+                    10,
+                )
+                .unwrap();
+            assert_eq!(result.assignments.len(), 2);
+            result.assignments[1].clone()
+        };
+
+        let litho_then_litho = run(job("L2", 2, "lithography"));
+        let litho_then_etch = run(job("E1", 2, "etching"));
+        assert!((litho_then_litho.estimated_start_hours - 2.0).abs() < 1e-9);
+        assert!((litho_then_etch.estimated_start_hours - 3.5).abs() < 1e-9);
+        assert!((litho_then_litho.score - litho_then_etch.score - 0.1).abs() < 1e-9);
+
+        // The deadline check sees the changeover too
+        let mut after_litho = cluster.clone();
+        after_litho.last_recipe_type = Some("lithography".into());
+        let mut urgent_etch = job("E2", 2, "etching");
+        urgent_etch.deadline_hours = Some(1.0);
+        let candidates = optimizer.candidates(urgent_etch, vec![after_litho], 1);
+        assert!(candidates[0]
+            .constraint_violations
+            .iter()
+            .any(|v| v == "Would miss deadline by 0.5h"));
+    }
+
+    #[test]
+    fn test_preemption_restores_machine_recipe() {
+        // The cluster tool last ran lithography before the etch lot was queued
+        let mut cluster = SchedulerMachine::new(
+            "c1".into(),
+            "CLUSTER-01".into(),
+            "lithography_etching".into(),
+            "RUNNING".into(),
+            0.9,
+            0,
+            0.0,
+        );
+        cluster.last_recipe_type = Some("lithography".into());
+        let low = SchedulerJob::new(
+            "low".into(),
+            "LOW".into(),
+            5,
+            25,
+            false,
+            "etching".into(),
+            None,
+        );
+        let hot = SchedulerJob::new(
+            "hot".into(),
+            "HOT".into(),
+            1,
+            25,
+            true,
+            "lithography".into(),
+            None,
+        );
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            allow_hot_lot_preemption: true,
+            changeover_hours: 1.5,
+            ..Default::default()
+        }));
+
+        let locked = optimizer
+            .optimize(vec![low.clone()], vec![cluster.clone()], 10)
+            .unwrap()
+            .assignments;
+        assert!((locked[0].estimated_start_hours - 1.5).abs() < 1e-9);
+
+        // Bumping the etch lot leaves the tool set up for lithography
+        let result = optimizer
+            .optimize_incremental(vec![low, hot], vec![cluster], locked, 10)
+            .unwrap();
+        assert_eq!(result.preempted_jobs, vec!["low".to_string()]);
+        assert_eq!(result.assignments[0].job_id, "hot");
+        assert!(result.assignments[0].estimated_start_hours.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_estimated_finish_follows_processing_time() {
+        let mut jobs = sample_jobs();
+        jobs[0].processing_hours = Some(3.5);
+        let optimizer = SchedulerOptimizer::new(None);
+        let result = optimizer
+            .optimize(jobs.clone(), sample_machines(), 10)
+            .unwrap();
+
+        assert!(!result.assignments.is_empty());
+        for assignment in &result.assignments {
+            let job = jobs.iter().find(|j| j.job_id == assignment.job_id).unwrap();
+            let duration = optimizer.processing_hours(job);
+            assert!(
+                (assignment.estimated_finish_hours - assignment.estimated_start_hours - duration)
+                    .abs()
+                    < 1e-9
+            );
+            assert!(assignment.estimated_finish_hours >= assignment.estimated_start_hours);
+        }
+        let hot = result
+            .assignments
+            .iter()
+            .find(|a| a.job_id == "j1")
+            .unwrap();
+        assert!((hot.estimated_finish_hours - hot.estimated_start_hours - 3.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dependent_step_starts_after_predecessor_finishes() {
+        let mut jobs = sample_jobs();
+        // Etch (priority 2) waits on deposition (priority 3)
+        jobs[1].depends_on = vec!["j3".into()];
+        jobs[2].processing_hours = Some(5.0);
+        let mut orphan = SchedulerJob::new(
+            "j4".into(),
+            "WAFER-105".into(),
+            1,
+            25,
+            false,
+            "etching".into(),
+            None,
+        );
+        orphan.depends_on = vec!["j9".into()];
+        jobs.push(orphan);
+
+        let result = SchedulerOptimizer::new(None)
+            .optimize(jobs, sample_machines(), 10)
+            .unwrap();
+
+        let find = |id: &str| result.assignments.iter().find(|a| a.job_id == id).unwrap();
+        let (dep, etch) = (find("j3"), find("j2"));
+        assert!((dep.estimated_finish_hours - 5.0).abs() < 1e-9);
+        // ETCH-01 frees up at 2h but the etch step has to wait for deposition
+        assert!((etch.estimated_start_hours - dep.estimated_finish_hours).abs() < 1e-9);
+
+        assert!(result.unassigned_jobs.contains(&"j4".to_string()));
+        assert!(result.unassigned_reasons["j4"].contains("j9"));
+    }
+
+    #[test]
+    fn test_recipe_budget_caps_batch() {
+        let mut jobs = sample_jobs();
+        jobs.push(SchedulerJob::new(
+            "j4".into(),
+            "WAFER-105".into(),
+            3,
+            25,
+            false,
+            "deposition".into(),
+            None,
+        ));
+        let mut machines = sample_machines();
+        machines.push(SchedulerMachine::new(
+            "m5".into(),
+            "DEP-02".into(),
+            "deposition".into(),
+            "IDLE".into(),
+            0.90,
+            0,
+            0.0,
+        ));
+
+        // Without a cap both deposition jobs find a DEP tool
+        let result = SchedulerOptimizer::new(None)
+            .optimize(jobs.clone(), machines.clone(), 10)
+            .unwrap();
+        assert!(result.unassigned_jobs.is_empty());
+
+        let mut config = ConstraintConfig::default();
+        config
+            .max_assignments_per_recipe
+            .insert("deposition".into(), 1);
+        let result = SchedulerOptimizer::new(Some(config))
+            .optimize(jobs, machines, 10)
+            .unwrap();
+
+        assert_eq!(result.unassigned_jobs, vec!["j4".to_string()]);
+        assert!(result.unassigned_reasons["j4"].contains("Recipe budget exhausted"));
+        assert_eq!(result.assignments.len(), 3);
+    }
+
+    #[test]
+    fn test_second_litho_tool_has_positive_marginal_value() {
+        let optimizer = SchedulerOptimizer::new(None);
+
+        // Litho is the bottleneck: three litho lots, one working litho tool
+        let mut jobs = sample_jobs();
+        jobs.extend((4..=5).map(|i| {
+            SchedulerJob::new(
+                format!("j{}", i),
+                format!("WAFER-10{}", i + 1),
+                3,
+                25,
+                false,
+                "lithography".into(),
+                None,
+            )
+        }));
+
+        let candidate = SchedulerMachine::new(
+            "m5".into(),
+            "LITHO-03".into(),
+            "lithography".into(),
+            "IDLE".into(),
+            0.93,
+            0,
+            0.0,
+        );
+        let value = optimizer
+            .marginal_machine_value(jobs.clone(), sample_machines(), candidate, 10)
+            .unwrap();
+
+        assert_eq!(value.machine_id, "m5");
+        assert_eq!(value.assignment_delta, 1);
+        assert!(value.score_delta > 0.0);
+
+        // Another deposition tool adds nothing: deposition isn't constrained
+        let idle_dep = SchedulerMachine::new(
+            "m6".into(),
+            "DEP-02".into(),
+            "deposition".into(),
+            "IDLE".into(),
+            0.92,
+            0,
+            0.0,
+        );
+        let value = optimizer
+            .marginal_machine_value(jobs, sample_machines(), idle_dep, 10)
+            .unwrap();
+        assert_eq!(value.assignment_delta, 0);
+    }
+
+    #[test]
+    fn test_cooldown_excludes_idle_machine_until_elapsed() {
+        let optimizer = SchedulerOptimizer::new(None);
+        let jobs = sample_jobs();
+
+        // LITHO-01 was emergency-stopped and awaits inspection for 4 hours
+        let mut machines = sample_machines();
+        machines[0].cooldown_until_hours = Some(4.0);
+        let result = optimizer
+            .optimize(jobs.clone(), machines.clone(), 10)
+            .unwrap();
+        assert!(result.unassigned_jobs.contains(&"j1".to_string()));
+        let ranking = optimizer.rank_machines_for_job(jobs[0].clone(), machines.clone());
+        let (_, score, violations) = ranking.iter().find(|(id, _, _)| id == "m1").unwrap();
+        assert!(score.is_none());
+        assert_eq!(
+            violations,
+            &vec!["Machine in cooldown for 4.0h".to_string()]
+        );
+
+        // Four hours on, the cooldown has elapsed
+        machines[0].cooldown_until_hours = Some(0.0);
+        let result = optimizer.optimize(jobs, machines, 10).unwrap();
+        let hot = result
+            .assignments
+            .iter()
+            .find(|a| a.job_id == "j1")
+            .unwrap();
+        assert_eq!(hot.machine_id, "m1");
+    }
+
+    #[test]
+    fn test_soft_deadline_prefers_machine_meeting_hard_deadline() {
+        let mut job = SchedulerJob::new(
+            "j1".into(),
+            "WAFER-201".into(),
+            2,
+            25,
+            false,
+            "etching".into(),
+            Some(6.0),
+        );
+        job.soft_deadline_hours = Some(2.0);
+
+        let etcher = |id: &str, name: &str, available: f64| {
+            SchedulerMachine::new(
+                id.into(),
+                name.into(),
+                "etching".into(),
+                "RUNNING".into(),
+                0.90,
+                1,
+                available,
+            )
+        };
+        // Misses the soft target by 2h but starts before the hard deadline
+        let near = etcher("m1", "ETCH-01", 4.0);
+        // Misses both
+        let far = etcher("m2", "ETCH-02", 8.0);
+
+        let optimizer = SchedulerOptimizer::new(None);
+        let result = optimizer
+            .optimize(vec![job.clone()], vec![far.clone(), near.clone()], 10)
+            .unwrap();
+        assert_eq!(result.assignments[0].machine_id, "m1");
+        assert_eq!(
+            result.assignments[0].constraint_violations,
+            vec!["Would miss soft deadline by 2.0h".to_string()]
+        );
+
+        // Hard deadline enforced: a machine that would miss it is excluded
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            enforce_deadlines: true,
+            ..Default::default()
+        }));
+        let result = optimizer
+            .optimize(vec![job.clone()], vec![far.clone(), near], 10)
+            .unwrap();
+        assert_eq!(result.assignments[0].machine_id, "m1");
+        let result = optimizer.optimize(vec![job], vec![far], 10).unwrap();
+        assert_eq!(result.unassigned_jobs, vec!["j1".to_string()]);
+    }
+
+    #[test]
+    fn test_job_beyond_planning_horizon_is_deferred() {
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            planning_horizon_hours: Some(8.0),
+            ..Default::default()
+        }));
+        let job = |id: &str, recipe: &str| {
+            SchedulerJob::new(
+                id.to_string(),
+                format!("WAFER-{}", id),
+                3,
+                25,
+                false,
+                recipe.to_string(),
+                None,
+            )
+        };
+        // The only etcher is busy for 12 hours; the litho tool is free
+        let machines = vec![
+            SchedulerMachine::new(
+                "m1".to_string(),
+                "ETCH-01".to_string(),
+                "etching".to_string(),
+                "RUNNING".to_string(),
+                0.9,
+                3,
+                12.0,
             ),
             SchedulerMachine::new(
-                "m4".into(),
-                "LITHO-02".into(),
-                "lithography".into(),
-                "DOWN".into(),
-                0.90,
+                "m2".to_string(),
+                "LITHO-01".to_string(),
+                "lithography".to_string(),
+                "IDLE".to_string(),
+                0.9,
                 0,
                 0.0,
             ),
-        ]
+        ];
+        let jobs = vec![
+            job("j1", "etching"),
+            job("j2", "lithography"),
+            job("j3", "deposition"),
+        ];
+
+        let result = optimizer.optimize_native(&jobs, &machines, 10);
+        assert_eq!(result.assignments.len(), 1);
+        assert_eq!(result.assignments[0].job_id, "j2");
+        assert_eq!(result.deferred_jobs, vec!["j1".to_string()]);
+        // No machine at all is a different problem from "not yet"
+        assert_eq!(result.unassigned_jobs, vec!["j3".to_string()]);
+
+        // Without a horizon the etcher's backlog is simply accepted
+        let result = SchedulerOptimizer::new(None).optimize_native(&jobs, &machines, 10);
+        assert!(result.deferred_jobs.is_empty());
+        assert!(result.assignments.iter().any(|a| a.job_id == "j1"));
     }
 
     #[test]
@@ -599,4 +3805,889 @@ mod tests {
             assert_ne!(assignment.machine_name, "LITHO-02");
         }
     }
+
+    #[test]
+    fn test_rank_machines_for_job() {
+        let optimizer = SchedulerOptimizer::new(None);
+        let ranking = optimizer.rank_machines_for_job(sample_jobs()[0].clone(), sample_machines());
+
+        assert_eq!(ranking.len(), 4);
+        assert_eq!(ranking[0].0, "m1");
+        assert!(ranking[0].1.is_some());
+
+        let down = ranking.iter().find(|r| r.0 == "m4").unwrap();
+        assert!(down.1.is_none());
+        assert!(down.2.iter().any(|v| v.contains("unavailable")));
+
+        let etcher = ranking.iter().find(|r| r.0 == "m2").unwrap();
+        assert!(etcher.1.is_none());
+        assert!(etcher.2.iter().any(|v| v.contains("incompatible")));
+    }
+
+    #[test]
+    fn test_processing_hours_override_pushes_later_jobs() {
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            max_jobs_per_machine: Some(2),
+            ..Default::default()
+        }));
+        let machines = sample_machines();
+        let next_job = sample_jobs()[0].clone();
+
+        // A 6-hour anneal already queued on LITHO-01 this batch (the machine
+        // stays open to more work, as it can take two jobs)
+        let mut anneal = sample_jobs()[0].clone();
+        anneal.processing_hours = Some(6.0);
+        let mut batch = BatchState::default();
+        batch.added_queue.insert("m1".into(), 1);
+        batch
+            .added_hours
+            .insert("m1".into(), optimizer.processing_hours(&anneal));
+
+        let assignment = optimizer
+            .find_best_machine(&next_job, &machines, &batch)
+            .unwrap();
+        assert_eq!(assignment.machine_name, "LITHO-01");
+        assert!((assignment.estimated_start_hours - 6.0).abs() < 1e-9);
+
+        // Without the override the default per-job time applies
+        assert_eq!(
+            optimizer.processing_hours(&next_job),
+            optimizer.config.default_processing_hours
+        );
+    }
+
+    #[test]
+    fn test_default_processing_hours_for_mixed_durations() {
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            max_jobs_per_machine: Some(3),
+            default_processing_hours: 8.0,
+            ..Default::default()
+        }));
+        let machines = vec![sample_machines()[0].clone()];
+        let mut quick = sample_jobs()[0].clone();
+        quick.job_id = "quick".into();
+        quick.processing_hours = Some(0.5);
+        let mut long = quick.clone();
+        long.job_id = "long".into();
+        long.processing_hours = None;
+        let mut last = quick.clone();
+        last.job_id = "last".into();
+
+        let result = optimizer
+            .optimize(vec![quick, long, last], machines, 10)
+            .unwrap();
+
+        let start = |id: &str| {
+            result
+                .assignments
+                .iter()
+                .find(|a| a.job_id == id)
+                .unwrap()
+                .estimated_start_hours
+        };
+        // The 0.5h job delays the next by half an hour; the job without its
+        // own duration takes the configured 8h
+        assert!((start("quick") - 0.0).abs() < 1e-9);
+        assert!((start("long") - 0.5).abs() < 1e-9);
+        assert!((start("last") - 8.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_machine_at_queue_limit_excluded() {
+        let optimizer = SchedulerOptimizer::new(None);
+        let job = sample_jobs()[1].clone(); // etching
+        let mut machines = sample_machines();
+        machines.push(SchedulerMachine::new(
+            "m5".into(),
+            "ETCH-02".into(),
+            "etching".into(),
+            "RUNNING".into(),
+            0.70,
+            1,
+            2.0,
+        ));
+
+        let best = optimizer.find_best_machine(&job, &machines, &BatchState::default());
+        assert_eq!(best.unwrap().machine_name, "ETCH-01");
+
+        // ETCH-01 still scores higher but its one cassette slot is taken
+        machines[1].max_queue_depth = Some(1);
+        let best = optimizer.find_best_machine(&job, &machines, &BatchState::default());
+        assert_eq!(best.unwrap().machine_name, "ETCH-02");
+        let (score, violations) =
+            optimizer.evaluate_machine(&job, &machines[1], &BatchState::default(), false);
+        assert!(score.is_none());
+        assert!(violations[0].contains("limit of 1"));
+    }
+
+    #[test]
+    fn test_unplaceable_jobs_do_not_use_up_the_batch() {
+        let machines = vec![litho_machine("LITHO-01"), litho_machine("LITHO-02")];
+        let job = |id: &str, priority: i32, recipe: &str| {
+            SchedulerJob::new(
+                id.into(),
+                id.into(),
+                priority,
+                25,
+                false,
+                recipe.into(),
+                None,
+            )
+        };
+        // The urgent etch jobs have no tool; the litho jobs behind them do
+        let jobs = vec![
+            job("etch-1", 1, "etching"),
+            job("etch-2", 1, "etching"),
+            job("litho-1", 3, "lithography"),
+            job("litho-2", 4, "lithography"),
+        ];
+
+        let result = SchedulerOptimizer::new(None)
+            .optimize(jobs.clone(), machines.clone(), 2)
+            .unwrap();
+        let assigned: Vec<&str> = result
+            .assignments
+            .iter()
+            .map(|a| a.job_id.as_str())
+            .collect();
+        assert_eq!(assigned, vec!["litho-1", "litho-2"]);
+        assert_eq!(result.unassigned_jobs, vec!["etch-1", "etch-2"]);
+        assert!(result.deferred_jobs.is_empty());
+
+        // Capped scan: the last job is deferred without an attempt
+        let config = ConstraintConfig {
+            max_jobs_considered: Some(3),
+            ..Default::default()
+        };
+        let result = SchedulerOptimizer::new(Some(config))
+            .optimize(jobs, machines, 2)
+            .unwrap();
+        assert_eq!(result.assignments.len(), 1);
+        assert_eq!(result.assignments[0].job_id, "litho-1");
+        assert_eq!(result.unassigned_jobs, vec!["etch-1", "etch-2"]);
+        assert_eq!(result.deferred_jobs, vec!["litho-2"]);
+    }
+
+    #[test]
+    fn test_incremental_keeps_locked_assignment() {
+        let mut machines = sample_machines();
+        machines.push(SchedulerMachine::new(
+            "m5".into(),
+            "LITHO-03".into(),
+            "lithography".into(),
+            "IDLE".into(),
+            0.80,
+            0,
+            0.0,
+        ));
+        let mut jobs = sample_jobs();
+        jobs.push(SchedulerJob::new(
+            "j4".into(),
+            "WAFER-105".into(),
+            2,
+            25,
+            false,
+            "lithography".into(),
+            None,
+        ));
+        let optimizer = SchedulerOptimizer::new(None);
+
+        // The hot lot is already running on LITHO-01 from an earlier run
+        let mut locked = optimizer
+            .optimize(jobs.clone(), machines.clone(), 10)
+            .unwrap()
+            .assignments
+            .into_iter()
+            .find(|a| a.job_id == "j1")
+            .unwrap();
+        assert_eq!(locked.machine_id, "m1");
+        locked.reason = "Running since last cycle".to_string();
+        locked.score = 0.5;
+
+        let result = optimizer
+            .optimize_incremental(jobs, machines, vec![locked.clone()], 10)
+            .unwrap();
+        let first = &result.assignments[0];
+        assert_eq!(
+            (&first.job_id, &first.machine_id, &first.reason, first.score),
+            (
+                &locked.job_id,
+                &locked.machine_id,
+                &locked.reason,
+                locked.score
+            )
+        );
+        assert_eq!(
+            result
+                .assignments
+                .iter()
+                .filter(|a| a.job_id == "j1")
+                .count(),
+            1
+        );
+
+        // The other litho job routes around the locked tool
+        let j4 = result
+            .assignments
+            .iter()
+            .find(|a| a.job_id == "j4")
+            .unwrap();
+        assert_eq!(j4.machine_id, "m5");
+        assert!(result.assignments[1..].iter().all(|a| a.machine_id != "m1"));
+        let total: f64 = result.assignments.iter().map(|a| a.score).sum();
+        assert!((result.total_score - total).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_qualification_overrides_machine_type() {
+        let machines: Vec<SchedulerMachine> = sample_machines()
+            .into_iter()
+            .filter(|m| m.machine_type != "etching")
+            .collect();
+        let etch_job = sample_jobs()[1].clone();
+        assert_eq!(etch_job.recipe_type, "etching");
+
+        // By type alone nothing can run the etch job
+        let result = SchedulerOptimizer::new(None)
+            .optimize(vec![etch_job.clone()], machines.clone(), 10)
+            .unwrap();
+        assert!(result.assignments.is_empty());
+
+        // LITHO-01 is qualified for etching; DEP-01 has an entry without it,
+        // which also rules out its usual deposition recipes
+        let qualifications = HashMap::from([
+            ("m1".to_string(), HashSet::from(["etching".to_string()])),
+            ("m3".to_string(), HashSet::from(["cleaning".to_string()])),
+        ]);
+        let optimizer = SchedulerOptimizer::new(None).with_qualifications(qualifications);
+        let result = optimizer
+            .optimize(vec![etch_job], machines.clone(), 10)
+            .unwrap();
+        assert_eq!(result.assignments.len(), 1);
+        assert_eq!(result.assignments[0].machine_id, "m1");
+        assert!(result.assignments[0].constraint_violations.is_empty());
+
+        let dep_job = sample_jobs()[2].clone();
+        let result = optimizer.optimize(vec![dep_job], machines, 10).unwrap();
+        assert!(result.assignments.is_empty());
+    }
+
+    #[test]
+    fn test_unassigned_remedy_follows_qualifications() {
+        let mut machines = sample_machines();
+        machines[1].status = "DOWN".to_string();
+        let jobs = vec![sample_jobs()[1].clone()];
+        // The down litho tool is qualified for etching; the down etcher is not
+        let qualifications = HashMap::from([
+            ("m2".to_string(), HashSet::from(["cleaning".to_string()])),
+            ("m4".to_string(), HashSet::from(["etching".to_string()])),
+        ]);
+        let optimizer = SchedulerOptimizer::new(None).with_qualifications(qualifications);
+
+        let result = optimizer
+            .optimize(jobs.clone(), machines.clone(), 10)
+            .unwrap();
+        let remedies = optimizer.unassigned_remedies(result, jobs, machines);
+
+        assert_eq!(remedies.len(), 1);
+        assert_eq!(remedies[0].cause, "machines_unavailable");
+        assert_eq!(remedies[0].machines, vec!["m4"]);
+    }
+
+    #[test]
+    fn test_wafer_capacity_rejects_oversized_job() {
+        let mut machine = litho_machine("LITHO-01");
+        machine.max_wafer_capacity = Some(50);
+        let job = |id: &str, priority: i32, wafers: i32| {
+            SchedulerJob::new(
+                id.into(),
+                id.into(),
+                priority,
+                wafers,
+                false,
+                "lithography".into(),
+                None,
+            )
+        };
+        let jobs = vec![job("big", 1, 60), job("small", 2, 30)];
+        let config = ConstraintConfig {
+            max_jobs_per_machine: Some(2),
+            ..Default::default()
+        };
+
+        let result = SchedulerOptimizer::new(Some(config))
+            .optimize(jobs, vec![machine], 10)
+            .unwrap();
+        assert_eq!(result.assignments.len(), 1);
+        assert_eq!(result.assignments[0].job_id, "small");
+        assert_eq!(result.unassigned_jobs, vec!["big".to_string()]);
+        assert!(result.unassigned_reasons["big"].contains("capacity exceeded"));
+    }
+
+    #[test]
+    fn test_priority_aging_lifts_long_waiting_job() {
+        let machines = vec![sample_machines()[0].clone()];
+        let litho = |id: &str, priority: i32, waiting_hours: f64| {
+            let mut job = SchedulerJob::new(
+                id.into(),
+                id.into(),
+                priority,
+                25,
+                false,
+                "lithography".into(),
+                None,
+            );
+            job.waiting_hours = waiting_hours;
+            job
+        };
+        let jobs = vec![litho("fresh-p3", 3, 0.0), litho("waiting-p4", 4, 36.0)];
+
+        // Without aging the priority-3 job takes the only tool
+        let result = SchedulerOptimizer::new(None)
+            .optimize(jobs.clone(), machines.clone(), 1)
+            .unwrap();
+        assert_eq!(result.assignments[0].job_id, "fresh-p3");
+
+        // 36h at 12h per level ages the priority-4 job to level 1
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            aging_hours_per_level: 12.0,
+            ..Default::default()
+        }));
+        assert_eq!(optimizer.effective_priority(&jobs[1]), 1.0);
+        let result = optimizer
+            .optimize(jobs.clone(), machines.clone(), 1)
+            .unwrap();
+        assert_eq!(result.assignments[0].job_id, "waiting-p4");
+        let batch = BatchState::default();
+        assert!(
+            optimizer.calculate_score(&jobs[1], &machines[0], &batch)
+                > optimizer.calculate_score(&jobs[0], &machines[0], &batch)
+        );
+    }
+
+    #[test]
+    fn test_score_breakdown_sums_to_score() {
+        let mut jobs = sample_jobs();
+        jobs[2].soft_deadline_hours = Some(0.5);
+        let mut machines = sample_machines();
+        machines[2].estimated_available_hours = 3.0;
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            target_utilization: Some(0.05),
+            ..Default::default()
+        }));
+
+        let result = optimizer.optimize(jobs, machines, 10).unwrap();
+
+        assert_eq!(result.assignments.len(), 3);
+        for assignment in &result.assignments {
+            let breakdown = &assignment.score_breakdown;
+            for key in [
+                "priority",
+                "efficiency",
+                "deadline",
+                "queue_depth",
+                "status_bonus",
+            ] {
+                assert!(breakdown.contains_key(key), "missing {}", key);
+            }
+            let total: f64 = breakdown.values().sum();
+            assert!((total - assignment.score).abs() < 1e-9);
+        }
+        let deposition = result
+            .assignments
+            .iter()
+            .find(|a| a.job_id == "j3")
+            .unwrap();
+        assert!(deposition.score_breakdown["utilization"] < 0.0);
+    }
+
+    #[test]
+    fn test_global_mode_beats_greedy_when_greedy_starves_a_job() {
+        // The hot anneal can run anywhere but prefers the better litho tool;
+        // the litho job has nowhere else to go
+        let jobs = vec![
+            SchedulerJob::new(
+                "j1".into(),
+                "HOT-ANNEAL".into(),
+                1,
+                25,
+                true,
+                "anneal".into(),
+                None,
+            ),
+            SchedulerJob::new(
+                "j2".into(),
+                "WAFER-201".into(),
+                2,
+                25,
+                false,
+                "lithography".into(),
+                None,
+            ),
+        ];
+        // LITHO-01 and a weaker DEP-01, both idle
+        let mut machines = sample_machines();
+        machines.truncate(3);
+        machines.remove(1);
+        machines[1].efficiency_rating = 0.6;
+        let optimizer = SchedulerOptimizer::new(None);
+
+        let greedy = optimizer
+            .optimize(jobs.clone(), machines.clone(), 10)
+            .unwrap();
+        let global = optimizer.optimize_global(jobs, machines, 10, None).unwrap();
+
+        assert_eq!(greedy.unassigned_jobs, vec!["j2".to_string()]);
+        assert_eq!(global.assignments.len(), 2);
+        assert!(global.unassigned_jobs.is_empty());
+        assert!(global.total_score > greedy.total_score);
+        let placed: HashMap<_, _> = global
+            .assignments
+            .iter()
+            .map(|a| (a.job_id.as_str(), a.machine_name.as_str()))
+            .collect();
+        assert_eq!(placed["j1"], "DEP-01");
+        assert_eq!(placed["j2"], "LITHO-01");
+    }
+
+    #[test]
+    fn test_global_warm_start_matches_cold_solve() {
+        // Two equal litho lots on two equal litho tools: either pairing is optimal
+        let mut jobs = vec![sample_jobs()[0].clone(), sample_jobs()[0].clone()];
+        jobs[1].job_id = "j1b".into();
+        let mut machines = vec![sample_machines()[0].clone(), sample_machines()[0].clone()];
+        machines[1].machine_id = "m5".into();
+        machines[1].name = "LITHO-03".into();
+        let optimizer = SchedulerOptimizer::new(None);
+
+        let cold = optimizer
+            .optimize_global(jobs.clone(), machines.clone(), 10, None)
+            .unwrap();
+        assert_eq!(cold.assignments.len(), 2);
+
+        // Seeded with the other pairing: the tie still goes the same way
+        let mut previous = cold.clone();
+        for assignment in &mut previous.assignments {
+            assignment.machine_id = if assignment.machine_id == "m1" {
+                "m5"
+            } else {
+                "m1"
+            }
+            .into();
+        }
+        let warm = optimizer
+            .optimize_global(jobs.clone(), machines.clone(), 10, Some(previous.clone()))
+            .unwrap();
+        assert_eq!(warm.assignments, cold.assignments);
+
+        // A previous machine that went down is dropped from the seed
+        machines[1].status = "DOWN".into();
+        let cold = optimizer
+            .optimize_global(jobs.clone(), machines.clone(), 10, None)
+            .unwrap();
+        let warm = optimizer
+            .optimize_global(jobs, machines, 10, Some(previous))
+            .unwrap();
+        assert_eq!(cold.assignments.len(), 1);
+        assert_eq!(warm.assignments, cold.assignments);
+    }
+
+    #[test]
+    fn test_pareto_front_trades_score_for_on_time_jobs() {
+        // The better litho tool frees up 1h after the lot's soft deadline;
+        // the weaker one finishes the 2h lot before it
+        let mut job = sample_jobs()[0].clone();
+        job.deadline_hours = None;
+        job.soft_deadline_hours = Some(3.0);
+        let mut fast = sample_machines()[0].clone();
+        fast.status = "RUNNING".into();
+        fast.estimated_available_hours = 4.0;
+        let mut slow = sample_machines()[3].clone();
+        slow.status = "RUNNING".into();
+        slow.efficiency_rating = 0.5;
+
+        let front = SchedulerOptimizer::new(None)
+            .pareto_schedules(vec![job], vec![fast, slow], 10, 9)
+            .unwrap();
+
+        assert!(front.len() >= 2);
+        for (i, a) in front.iter().enumerate() {
+            for b in &front[i + 1..] {
+                assert!(!a.dominates(b) && !b.dominates(a));
+            }
+        }
+        // Ends of the curve: late on the better tool, on time on the weaker
+        let (first, last) = (&front[0], &front[front.len() - 1]);
+        assert_eq!((first.on_time_count, last.on_time_count), (0, 1));
+        assert_eq!(first.assignments[0].machine_name, "LITHO-01");
+        assert_eq!(last.assignments[0].machine_name, "LITHO-02");
+        assert!(first.total_score > last.total_score);
+
+        // Starting before the deadline is not enough; the lot must finish by it
+        let mut job = sample_jobs()[0].clone();
+        job.deadline_hours = Some(1.0);
+        let front = SchedulerOptimizer::new(None)
+            .pareto_schedules(vec![job], vec![sample_machines()[0].clone()], 10, 2)
+            .unwrap();
+        assert_eq!(front.len(), 1);
+        assert_eq!(front[0].assignments.len(), 1);
+        assert_eq!(front[0].on_time_count, 0);
+    }
+
+    #[test]
+    fn test_parallel_scoring_matches_serial() {
+        let (jobs, machines) = workload::generate(300, 120, 7);
+        for load_balance in [false, true] {
+            let config = |parallel_machine_threshold| {
+                Some(ConstraintConfig {
+                    load_balance,
+                    parallel_machine_threshold,
+                    ..Default::default()
+                })
+            };
+            let serial =
+                SchedulerOptimizer::new(config(usize::MAX)).optimize_native(&jobs, &machines, 200);
+            let parallel =
+                SchedulerOptimizer::new(config(0)).optimize_native(&jobs, &machines, 200);
+
+            let placements = |result: &OptimizationResult| -> Vec<(String, String, f64)> {
+                result
+                    .assignments
+                    .iter()
+                    .map(|a| (a.job_id.clone(), a.machine_id.clone(), a.score))
+                    .collect()
+            };
+            assert!(!serial.assignments.is_empty());
+            assert_eq!(placements(&serial), placements(&parallel));
+            assert_eq!(serial.unassigned_jobs, parallel.unassigned_jobs);
+        }
+    }
+
+    #[test]
+    fn test_idle_machine_reserved_for_hot_lot() {
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            reserve_for_hot_lots: 1,
+            ..Default::default()
+        }));
+        let mut normal = sample_jobs()[0].clone();
+        normal.is_hot_lot = false;
+        normal.job_name = "WAFER-200".into();
+
+        // LITHO-01 is the last idle litho tool - held back from normal work
+        let result = optimizer
+            .optimize(vec![normal], sample_machines(), 10)
+            .unwrap();
+        assert!(result.assignments.is_empty());
+        assert_eq!(result.unassigned_jobs, vec!["j1".to_string()]);
+
+        // A hot lot claims it immediately
+        let hot = sample_jobs()[0].clone();
+        let result = optimizer
+            .optimize(vec![hot], sample_machines(), 10)
+            .unwrap();
+        assert_eq!(result.assignments[0].machine_name, "LITHO-01");
+    }
+
+    #[test]
+    fn test_recent_degradation_lowers_score() {
+        let optimizer = SchedulerOptimizer::new(None);
+        let job = &sample_jobs()[0];
+        let healthy = sample_machines()[0].clone();
+        let mut degraded = healthy.clone();
+        degraded.recent_efficiency = vec![0.62, 0.60, 0.58];
+
+        let effective = degraded.effective_efficiency(degraded.recent_efficiency.clone(), 0.5);
+        assert!((effective - (0.95 * 0.5 + 0.60 * 0.5)).abs() < 1e-9);
+
+        let queue = BatchState::default();
+        assert!(
+            optimizer.calculate_score(job, &degraded, &queue)
+                < optimizer.calculate_score(job, &healthy, &queue)
+        );
+    }
+
+    #[test]
+    fn test_nan_efficiency_excludes_machine_deterministically() {
+        let optimizer = SchedulerOptimizer::new(None);
+        let mut machines = sample_machines();
+        machines[0].efficiency_rating = f64::NAN;
+        machines[3].status = "IDLE".to_string();
+
+        // The hot lot goes to the healthy litho tool, every time
+        for _ in 0..3 {
+            let result = optimizer
+                .optimize(sample_jobs(), machines.clone(), 10)
+                .unwrap();
+            let hot = result
+                .assignments
+                .iter()
+                .find(|a| a.job_id == "j1")
+                .unwrap();
+            assert_eq!(hot.machine_id, "m4");
+            assert!(result.total_score.is_finite());
+        }
+
+        let ranking = optimizer.rank_machines_for_job(sample_jobs()[0].clone(), machines.clone());
+        let (_, score, violations) = ranking.iter().find(|(id, _, _)| id == "m1").unwrap();
+        assert!(score.is_none());
+        assert!(violations[0].contains("not finite"));
+
+        // A NaN weight is a configuration error, reported up front
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            efficiency_weight: f64::NAN,
+            ..Default::default()
+        }));
+        assert!(optimizer.optimize(sample_jobs(), machines, 10).is_err());
+    }
+
+    #[test]
+    fn test_reason_verbosity_levels() {
+        let hot_lot_reason = |verbosity: &str| {
+            let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+                reason_verbosity: verbosity.to_string(),
+                ..Default::default()
+            }));
+            optimizer
+                .optimize(sample_jobs(), sample_machines(), 10)
+                .map(|result| result.assignments[0].reason.clone())
+        };
+
+        let full = hot_lot_reason("full").unwrap();
+        assert!(full.starts_with("Optimizer v1.0 | HOT LOT | Score: "));
+        assert!(full.ends_with("Efficiency: 95%"));
+
+        let compact = hot_lot_reason("compact").unwrap();
+        assert!(compact.starts_with("Score: "));
+        assert!(!compact.contains('|'));
+        assert!(full.contains(&compact));
+
+        assert_eq!(hot_lot_reason("none").unwrap(), "");
+        assert!(hot_lot_reason("verbose").is_err());
+    }
+
+    #[test]
+    fn test_queue_saturation_factor_penalizes_deep_queues() {
+        let job = SchedulerJob::new(
+            "j1".into(),
+            "WAFER-201".into(),
+            3,
+            25,
+            false,
+            "etching".into(),
+            None,
+        );
+        let etcher = |id: &str, efficiency: f64, queue: i32| {
+            SchedulerMachine::new(
+                id.into(),
+                format!("ETCH-{}", id),
+                "etching".into(),
+                "RUNNING".into(),
+                efficiency,
+                queue,
+                0.0,
+            )
+        };
+        // Fast tool with a backlog vs. a slower tool with an empty queue
+        let machines = vec![etcher("m1", 0.95, 2), etcher("m2", 0.70, 0)];
+        let winner = |factor: f64| {
+            let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+                queue_saturation_factor: factor,
+                ..Default::default()
+            }));
+            optimizer
+                .optimize_native(std::slice::from_ref(&job), &machines, 1)
+                .assignments[0]
+                .machine_id
+                .clone()
+        };
+
+        assert_eq!(winner(0.2), "m1");
+        assert_eq!(winner(1.0), "m2");
+    }
+
+    #[test]
+    fn test_out_of_range_efficiency_clamped_or_rejected() {
+        let optimizer = SchedulerOptimizer::new(None);
+        let job = &sample_jobs()[0];
+        let mut perfect = sample_machines()[0].clone();
+        perfect.efficiency_rating = 1.0;
+        let mut mis_entered = perfect.clone();
+        mis_entered.efficiency_rating = 1.5;
+
+        let batch = BatchState::default();
+        assert_eq!(
+            optimizer.calculate_score(job, &mis_entered, &batch),
+            optimizer.calculate_score(job, &perfect, &batch)
+        );
+        assert_eq!(mis_entered.effective_efficiency(vec![1.2, 1.4], 0.5), 1.0);
+        assert!(mis_entered.validate().is_err());
+        assert!(perfect.validate().is_ok());
+
+        let strict = SchedulerOptimizer::new(Some(ConstraintConfig {
+            strict_validation: true,
+            ..Default::default()
+        }));
+        assert!(strict
+            .optimize(sample_jobs(), vec![mis_entered.clone()], 10)
+            .is_err());
+        assert!(optimizer
+            .optimize(sample_jobs(), vec![mis_entered], 10)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_wafer_weight_favors_larger_lots() {
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            wafer_weight: 0.5,
+            ..Default::default()
+        }));
+        let machine = &sample_machines()[1];
+        let queue = BatchState::default();
+
+        let small = SchedulerJob::new(
+            "j-small".into(),
+            "SMALL".into(),
+            2,
+            10,
+            false,
+            "etching".into(),
+            None,
+        );
+        let large = SchedulerJob::new(
+            "j-large".into(),
+            "LARGE".into(),
+            2,
+            500,
+            false,
+            "etching".into(),
+            None,
+        );
+
+        let small_score = optimizer.calculate_score(&small, machine, &queue);
+        let large_score = optimizer.calculate_score(&large, machine, &queue);
+        assert!(large_score > small_score);
+
+        // Without the weight, equal priority means equal score
+        let plain = SchedulerOptimizer::new(None);
+        assert_eq!(
+            plain.calculate_score(&small, machine, &queue),
+            plain.calculate_score(&large, machine, &queue)
+        );
+    }
+
+    #[test]
+    fn test_preemption_frees_the_victims_batch_slot() {
+        let job = |id: &str, priority: i32, hot: bool, recipe: &str| {
+            SchedulerJob::new(
+                id.into(),
+                id.to_uppercase(),
+                priority,
+                25,
+                hot,
+                recipe.into(),
+                None,
+            )
+        };
+        // The hot lot waits on a deposition step, so the low lot takes the
+        // only litho tool first; the etch lot follows the hot lot
+        let low = job("low", 4, false, "lithography");
+        let dep = job("dep", 5, false, "deposition");
+        let mut hot = job("hot", 1, true, "lithography");
+        hot.depends_on = vec!["dep".into()];
+        let mut etch = job("etch", 5, false, "etching");
+        etch.depends_on = vec!["hot".into()];
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            allow_hot_lot_preemption: true,
+            ..Default::default()
+        }));
+
+        // Exactly room for the three jobs left once the low lot is bumped
+        let result = optimizer
+            .optimize(vec![low, dep, hot, etch], sample_machines(), 3)
+            .unwrap();
+        assert_eq!(result.preempted_jobs, vec!["low".to_string()]);
+        let placed: Vec<&str> = result
+            .assignments
+            .iter()
+            .map(|a| a.job_id.as_str())
+            .collect();
+        assert_eq!(placed, vec!["dep", "hot", "etch"]);
+    }
+
+    #[test]
+    fn test_validate_assignments_reports_recipe_mismatch() {
+        let optimizer = SchedulerOptimizer::new(None);
+        let proposed = vec![
+            ("j1".to_string(), "m1".to_string()),
+            // Etch job hand-placed on the litho tool
+            ("j2".to_string(), "m1".to_string()),
+            ("j3".to_string(), "m3".to_string()),
+        ];
+
+        let report = optimizer.validate_assignments(proposed, sample_jobs(), sample_machines());
+
+        assert_eq!(report.len(), 3);
+        assert_eq!(report[0], ("j1".to_string(), vec![]));
+        let (job_id, violations) = &report[1];
+        assert_eq!(job_id, "j2");
+        assert!(violations.contains(&"Recipe etching incompatible with lithography".to_string()));
+        assert!(violations
+            .iter()
+            .any(|v| v.starts_with("Machine LITHO-01 double-booked")));
+        assert!(report[2].1.is_empty());
+
+        // A DOWN machine is reported without re-optimizing elsewhere
+        let report = optimizer.validate_assignments(
+            vec![("j1".to_string(), "m4".to_string())],
+            sample_jobs(),
+            sample_machines(),
+        );
+        assert_eq!(report[0].1, vec!["Machine unavailable (DOWN)".to_string()]);
+    }
+
+    #[test]
+    fn test_validate_assignments_reports_full_queue() {
+        let optimizer = SchedulerOptimizer::new(None);
+        let mut machines = sample_machines();
+        // ETCH-01 already holds one lot in a one-slot queue
+        machines[1].max_queue_depth = Some(1);
+
+        let report = optimizer.validate_assignments(
+            vec![("j2".to_string(), "m2".to_string())],
+            sample_jobs(),
+            machines,
+        );
+        assert_eq!(
+            report[0].1,
+            vec!["Queue at its limit of 1 lots".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_validate_assignments_reports_wafer_capacity() {
+        let optimizer = SchedulerOptimizer::new(Some(ConstraintConfig {
+            max_jobs_per_machine: Some(2),
+            ..Default::default()
+        }));
+        let mut jobs = sample_jobs();
+        jobs[2].recipe_type = "lithography".into();
+        jobs[2].deadline_hours = None;
+        let mut machines = sample_machines();
+        machines[0].max_wafer_capacity = Some(50);
+
+        // 25 wafers fit; the next 30 would overfill the chamber
+        let report = optimizer.validate_assignments(
+            vec![
+                ("j1".to_string(), "m1".to_string()),
+                ("j3".to_string(), "m1".to_string()),
+            ],
+            jobs,
+            machines,
+        );
+        assert!(report[0].1.is_empty());
+        assert_eq!(
+            report[1].1,
+            vec!["Wafer capacity exceeded (55 of 50 wafers)".to_string()]
+        );
+    }
 }
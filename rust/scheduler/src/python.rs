@@ -0,0 +1,334 @@
+//! Python bindings (the `python` feature)
+//!
+//! Thin wrappers over the pure-Rust API: constructors with Python defaults,
+//! `__repr__`, and input errors raised as `ValueError`.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    Assignment, ConstraintConfig, MarginalValue, OptimizationResult, ParetoPoint, SchedulerJob,
+    SchedulerMachine, SchedulerOptimizer, UnassignedRemedy,
+};
+
+#[pymethods]
+impl SchedulerJob {
+    #[new]
+    #[pyo3(signature = (job_id, job_name, priority_level, wafer_count, is_hot_lot, recipe_type, deadline_hours=None))]
+    fn py_new(
+        job_id: String,
+        job_name: String,
+        priority_level: i32,
+        wafer_count: i32,
+        is_hot_lot: bool,
+        recipe_type: String,
+        deadline_hours: Option<f64>,
+    ) -> Self {
+        Self::new(
+            job_id,
+            job_name,
+            priority_level,
+            wafer_count,
+            is_hot_lot,
+            recipe_type,
+            deadline_hours,
+        )
+    }
+}
+
+#[pymethods]
+impl SchedulerMachine {
+    #[new]
+    #[pyo3(signature = (machine_id, name, machine_type, status, efficiency_rating, current_queue_depth=0, estimated_available_hours=0.0))]
+    fn py_new(
+        machine_id: String,
+        name: String,
+        machine_type: String,
+        status: String,
+        efficiency_rating: f64,
+        current_queue_depth: i32,
+        estimated_available_hours: f64,
+    ) -> Self {
+        Self::new(
+            machine_id,
+            name,
+            machine_type,
+            status,
+            efficiency_rating,
+            current_queue_depth,
+            estimated_available_hours,
+        )
+    }
+
+    #[pyo3(name = "is_available")]
+    fn py_is_available(&self) -> bool {
+        self.is_available()
+    }
+
+    /// Blend the static efficiency rating with the mean of recent readings
+    #[pyo3(name = "effective_efficiency")]
+    fn py_effective_efficiency(&self, recent_readings: Vec<f64>, observed_weight: f64) -> f64 {
+        self.effective_efficiency(recent_readings, observed_weight)
+    }
+}
+
+#[pymethods]
+impl Assignment {
+    fn __repr__(&self) -> String {
+        format!(
+            "Assignment({} -> {}, score={:.2})",
+            self.job_name, self.machine_name, self.score
+        )
+    }
+}
+
+#[pymethods]
+impl OptimizationResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "OptimizationResult({} assignments, score={:.2}, {} unassigned, {} deferred)",
+            self.assignments.len(),
+            self.total_score,
+            self.unassigned_jobs.len(),
+            self.deferred_jobs.len()
+        )
+    }
+
+    /// Gantt bars for the plan: (machine_id, job_id, start_hours, end_hours)
+    #[pyo3(name = "timeline")]
+    fn py_timeline(&self) -> Vec<(String, String, f64, f64)> {
+        self.timeline()
+    }
+
+    /// The whole result as JSON, for saving and replaying a run
+    #[pyo3(name = "to_json")]
+    fn py_to_json(&self) -> String {
+        self.to_json()
+    }
+}
+
+#[pymethods]
+impl MarginalValue {
+    fn __repr__(&self) -> String {
+        format!(
+            "MarginalValue({}: score {:+.2}, assignments {:+})",
+            self.machine_id, self.score_delta, self.assignment_delta
+        )
+    }
+}
+
+#[pymethods]
+impl ParetoPoint {
+    fn __repr__(&self) -> String {
+        format!(
+            "ParetoPoint(score={:.2}, on_time={}, deadline_weight={:.2})",
+            self.total_score, self.on_time_count, self.deadline_weight
+        )
+    }
+}
+
+#[pymethods]
+impl UnassignedRemedy {
+    fn __repr__(&self) -> String {
+        format!("UnassignedRemedy({})", self.suggestion)
+    }
+}
+
+#[pymethods]
+impl ConstraintConfig {
+    #[new]
+    #[pyo3(signature = (
+        enforce_recipe_match=true,
+        enforce_deadlines=false,
+        priority_weight=0.3,
+        efficiency_weight=0.3,
+        deadline_weight=0.2,
+        queue_depth_weight=0.2
+    ))]
+    fn py_new(
+        enforce_recipe_match: bool,
+        enforce_deadlines: bool,
+        priority_weight: f64,
+        efficiency_weight: f64,
+        deadline_weight: f64,
+        queue_depth_weight: f64,
+    ) -> Self {
+        Self::new(
+            enforce_recipe_match,
+            enforce_deadlines,
+            priority_weight,
+            efficiency_weight,
+            deadline_weight,
+            queue_depth_weight,
+        )
+    }
+}
+
+#[pymethods]
+impl SchedulerOptimizer {
+    #[new]
+    #[pyo3(signature = (config=None, qualifications=None))]
+    fn py_new(
+        config: Option<ConstraintConfig>,
+        qualifications: Option<HashMap<String, HashSet<String>>>,
+    ) -> Self {
+        Self::new(config).with_qualifications(qualifications.unwrap_or_default())
+    }
+
+    /// Optimize job assignments to machines
+    #[pyo3(name = "optimize", signature = (jobs, machines, max_assignments=10))]
+    fn py_optimize(
+        &self,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+        max_assignments: usize,
+    ) -> PyResult<OptimizationResult> {
+        self.optimize(jobs, machines, max_assignments)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Optimize the batch as a whole instead of job by job
+    #[pyo3(name = "optimize_global", signature = (jobs, machines, max_assignments=10, warm_start=None))]
+    fn py_optimize_global(
+        &self,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+        max_assignments: usize,
+        warm_start: Option<OptimizationResult>,
+    ) -> PyResult<OptimizationResult> {
+        self.optimize_global(jobs, machines, max_assignments, warm_start)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Re-optimize around assignments that are already committed
+    #[pyo3(name = "optimize_incremental", signature = (jobs, machines, locked, max_assignments=10))]
+    fn py_optimize_incremental(
+        &self,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+        locked: Vec<Assignment>,
+        max_assignments: usize,
+    ) -> PyResult<OptimizationResult> {
+        self.optimize_incremental(jobs, machines, locked, max_assignments)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Refine the greedy plan by simulated annealing
+    #[pyo3(name = "optimize_annealed", signature = (jobs, machines, max_assignments=10, iterations=1000, seed=42))]
+    fn py_optimize_annealed(
+        &self,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+        max_assignments: usize,
+        iterations: usize,
+        seed: u64,
+    ) -> PyResult<OptimizationResult> {
+        self.optimize_annealed(jobs, machines, max_assignments, iterations, seed)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Score every machine for a single job (diagnostic)
+    #[pyo3(name = "rank_machines_for_job")]
+    fn py_rank_machines_for_job(
+        &self,
+        job: SchedulerJob,
+        machines: Vec<SchedulerMachine>,
+    ) -> Vec<(String, Option<f64>, Vec<String>)> {
+        self.rank_machines_for_job(job, machines)
+    }
+
+    /// The `n` best eligible machines for a single job (diagnostic)
+    #[pyo3(name = "candidates", signature = (job, machines, n=3))]
+    fn py_candidates(
+        &self,
+        job: SchedulerJob,
+        machines: Vec<SchedulerMachine>,
+        n: usize,
+    ) -> Vec<Assignment> {
+        self.candidates(job, machines, n)
+    }
+
+    /// Check a proposed schedule against the hard constraints
+    #[pyo3(name = "validate_assignments")]
+    fn py_validate_assignments(
+        &self,
+        assignments: Vec<(String, String)>,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+    ) -> Vec<(String, Vec<String>)> {
+        self.validate_assignments(assignments, jobs, machines)
+    }
+
+    /// Value of adding `candidate` to the fleet
+    #[pyo3(name = "marginal_machine_value", signature = (jobs, machines, candidate, max_assignments=10))]
+    fn py_marginal_machine_value(
+        &self,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+        candidate: SchedulerMachine,
+        max_assignments: usize,
+    ) -> PyResult<MarginalValue> {
+        self.marginal_machine_value(jobs, machines, candidate, max_assignments)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Schedules along the score vs on-time trade-off
+    #[pyo3(name = "pareto_schedules", signature = (jobs, machines, max_assignments=10, steps=9))]
+    fn py_pareto_schedules(
+        &self,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+        max_assignments: usize,
+        steps: usize,
+    ) -> PyResult<Vec<ParetoPoint>> {
+        self.pareto_schedules(jobs, machines, max_assignments, steps)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Group a result's unassigned jobs by cause and suggest the smallest fix
+    #[pyo3(name = "unassigned_remedies")]
+    fn py_unassigned_remedies(
+        &self,
+        result: OptimizationResult,
+        jobs: Vec<SchedulerJob>,
+        machines: Vec<SchedulerMachine>,
+    ) -> Vec<UnassignedRemedy> {
+        self.unassigned_remedies(result, jobs, machines)
+    }
+
+    /// Get the current constraint configuration
+    #[pyo3(name = "get_config")]
+    fn py_get_config(&self) -> ConstraintConfig {
+        self.get_config()
+    }
+}
+
+/// Parse a JSON array of jobs (as saved from `SchedulerJob` fields)
+#[pyfunction(name = "jobs_from_json")]
+fn py_jobs_from_json(json: &str) -> PyResult<Vec<SchedulerJob>> {
+    crate::jobs_from_json(json).map_err(PyValueError::new_err)
+}
+
+/// Parse a JSON array of machines (as saved from `SchedulerMachine` fields)
+#[pyfunction(name = "machines_from_json")]
+fn py_machines_from_json(json: &str) -> PyResult<Vec<SchedulerMachine>> {
+    crate::machines_from_json(json).map_err(PyValueError::new_err)
+}
+
+/// Python module initialization
+#[pymodule]
+fn yieldops_scheduler(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<SchedulerJob>()?;
+    m.add_class::<SchedulerMachine>()?;
+    m.add_class::<Assignment>()?;
+    m.add_class::<OptimizationResult>()?;
+    m.add_class::<MarginalValue>()?;
+    m.add_class::<UnassignedRemedy>()?;
+    m.add_class::<ParetoPoint>()?;
+    m.add_class::<ConstraintConfig>()?;
+    m.add_class::<SchedulerOptimizer>()?;
+    m.add_function(wrap_pyfunction!(py_jobs_from_json, m)?)?;
+    m.add_function(wrap_pyfunction!(py_machines_from_json, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,139 @@
+//! Synthetic scheduling workloads
+//!
+//! Deterministic job/machine sets with a realistic fab mix, used by the
+//! optimizer benchmarks.
+
+use crate::{SchedulerJob, SchedulerMachine};
+
+/// Recipe families paired with the machine type that runs them
+const RECIPES: [(&str, &str, &str); 5] = [
+    ("lithography", "lithography", "LITHO"),
+    ("etching", "etching", "ETCH"),
+    ("deposition", "deposition", "DEP"),
+    ("inspection", "inspection", "INSP"),
+    ("cleaning", "cleaning", "CLEAN"),
+];
+
+/// Small xorshift generator so workloads are reproducible without `rand`
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform in [0, 1)
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Generate `n_jobs` jobs and `n_machines` machines spread over the recipe families
+///
+/// About 5% of jobs are hot lots and a third carry a deadline; about 5% of
+/// machines are DOWN and the rest are IDLE or RUNNING with a short backlog.
+pub fn generate(
+    n_jobs: usize,
+    n_machines: usize,
+    seed: u64,
+) -> (Vec<SchedulerJob>, Vec<SchedulerMachine>) {
+    let mut rng = XorShift::new(seed);
+
+    let machines = (0..n_machines)
+        .map(|i| {
+            let (_, machine_type, prefix) = RECIPES[i % RECIPES.len()];
+            let roll = rng.unit();
+            let status = if roll < 0.05 {
+                "DOWN"
+            } else if roll < 0.5 {
+                "IDLE"
+            } else {
+                "RUNNING"
+            };
+            let busy = status == "RUNNING";
+            SchedulerMachine::new(
+                format!("m{}", i + 1),
+                format!("{}-{:02}", prefix, i / RECIPES.len() + 1),
+                machine_type.to_string(),
+                status.to_string(),
+                0.80 + rng.unit() * 0.18,
+                if busy { 1 + rng.below(4) as i32 } else { 0 },
+                if busy { rng.unit() * 8.0 } else { 0.0 },
+            )
+        })
+        .collect();
+
+    let jobs = (0..n_jobs)
+        .map(|i| {
+            let (recipe, _, _) = RECIPES[rng.below(RECIPES.len())];
+            let is_hot_lot = rng.unit() < 0.05;
+            let deadline = (rng.unit() < 0.33).then(|| 2.0 + rng.unit() * 46.0);
+            SchedulerJob::new(
+                format!("j{}", i + 1),
+                format!("WAFER-{:05}", i + 1),
+                if is_hot_lot {
+                    1
+                } else {
+                    1 + rng.below(5) as i32
+                },
+                1 + rng.below(50) as i32,
+                is_hot_lot,
+                recipe.to_string(),
+                deadline,
+            )
+        })
+        .collect();
+
+    (jobs, machines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SchedulerOptimizer;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_generated_workload_is_well_formed() {
+        let (jobs, machines) = generate(500, 100, 7);
+        assert_eq!(jobs.len(), 500);
+        assert_eq!(machines.len(), 100);
+
+        let job_ids: HashSet<_> = jobs.iter().map(|j| &j.job_id).collect();
+        let machine_ids: HashSet<_> = machines.iter().map(|m| &m.machine_id).collect();
+        assert_eq!(job_ids.len(), jobs.len());
+        assert_eq!(machine_ids.len(), machines.len());
+
+        for job in &jobs {
+            assert!((1..=5).contains(&job.priority_level));
+            assert!(job.wafer_count > 0);
+            assert!(!job.is_hot_lot || job.priority_level == 1);
+            assert!(job.deadline_hours.is_none_or(|d| d > 0.0));
+        }
+        for machine in &machines {
+            assert!(["IDLE", "RUNNING", "DOWN"].contains(&machine.status.as_str()));
+            assert!((0.0..=1.0).contains(&machine.efficiency_rating));
+            assert!(machine.estimated_available_hours >= 0.0);
+        }
+
+        // Every recipe family has a machine, so the optimizer can place work
+        let result = SchedulerOptimizer::new(None).optimize_native(&jobs, &machines, 50);
+        assert_eq!(result.assignments.len(), 50);
+
+        // Same seed, same workload
+        let (again, _) = generate(500, 100, 7);
+        assert_eq!(again[42].job_name, jobs[42].job_name);
+        assert_eq!(again[42].recipe_type, jobs[42].recipe_type);
+    }
+}